@@ -10,6 +10,7 @@ use std::sync::Arc;
 use std::sync::Mutex;
 use threadpool::{Builder, ThreadPool};
 
+/// Shared by all instances. Every open async transaction occupies one thread until it finishes.
 static THREAD_POOL: Lazy<Mutex<ThreadPool>> = Lazy::new(|| Mutex::new(Builder::new().build()));
 
 pub fn run_async<F: FnOnce() + Send + 'static>(job: F) {
@@ -89,7 +90,7 @@ impl IsarAsyncTxn {
         let txn = self.txn.clone();
         let job = move || -> Result<()> {
             let mut lock = txn.lock().unwrap();
-            if let Some(txn) = (*lock).take() {
+            if let Some(mut txn) = (*lock).take() {
                 txn.0.commit()
             } else {
                 Err(IsarError::TransactionClosed {})
@@ -102,7 +103,7 @@ impl IsarAsyncTxn {
         let txn = self.txn.clone();
         let job = move || -> Result<()> {
             let mut txn = txn.lock().unwrap();
-            if let Some(txn) = txn.take() {
+            if let Some(mut txn) = txn.take() {
                 txn.0.abort();
                 Ok(())
             } else {
@@ -116,7 +116,7 @@ pub unsafe extern "C" fn isar_export_json(
     json_length: *mut u32,
 ) -> i32 {
     isar_try! {
-        let exported_json = collection.export_json(txn, primitive_null)?;
+        let exported_json = collection.export_json(txn, primitive_null, None)?;
         let bytes = serde_json::to_vec(&exported_json).unwrap();
         let mut bytes = bytes.into_boxed_slice();
         json_length.write(bytes.len() as u32);
@@ -142,7 +142,7 @@ pub unsafe extern "C" fn isar_export_json_async(
     let json = JsonBytes(json_bytes);
     let json_length = JsonLen(json_length);
     txn.exec(move |txn| -> Result<()> {
-        let exported_json = collection.export_json(txn, primitive_null)?;
+        let exported_json = collection.export_json(txn, primitive_null, None)?;
         let bytes = serde_json::to_vec(&exported_json).unwrap();
         let mut bytes = bytes.into_boxed_slice();
         json_length.0.write(bytes.len() as u32);
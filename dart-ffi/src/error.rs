@@ -40,7 +40,7 @@ macro_rules! isar_try {
             match l() {
                 Ok(_) => 0,
                 Err(e) => {
-                    eprintln!("{}",e);
+                    isar_core::debug_log!("{}", e);
                     e.into_dart_err_code()
                 },
             }
@@ -1,7 +1,7 @@
 use float_next_after::NextAfter;
 use isar_core::collection::IsarCollection;
 use isar_core::error::illegal_arg;
-use isar_core::query::filter::{And, Filter, IsNull, Or};
+use isar_core::query::filter::{And, BoolEqual, Filter, IsNull, Or};
 use std::slice;
 
 #[no_mangle]
@@ -44,6 +44,27 @@ pub unsafe extern "C" fn isar_filter_is_null<'col>(
     }
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn isar_filter_bool_equal<'col>(
+    collection: &'col IsarCollection,
+    filter: *mut *const Filter<'col>,
+    value: bool,
+    is_null: bool,
+    property_index: u32,
+) -> i32 {
+    let property = collection.get_properties().get(property_index as usize);
+    isar_try! {
+        if let Some(property) = property {
+            let value = if is_null { None } else { Some(value) };
+            let query_filter = BoolEqual::filter(property, value)?;
+            let ptr = Box::into_raw(Box::new(query_filter));
+            filter.write(ptr);
+        } else {
+            illegal_arg("Property does not exist.")?;
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! filter_between_ffi {
     ($filter_name:ident, $function_name:ident, $next:ident, $prev:ident, $type:ty) => {
@@ -183,6 +204,35 @@ filter_between_ffi!(
     f64
 );
 
+filter_between_ffi!(
+    IntListAnyBetween,
+    isar_filter_int_list_any_between,
+    next_int,
+    prev_int,
+    i32
+);
+filter_between_ffi!(
+    IntListAllBetween,
+    isar_filter_int_list_all_between,
+    next_int,
+    prev_int,
+    i32
+);
+filter_between_ffi!(
+    DoubleListAnyBetween,
+    isar_filter_double_list_any_between,
+    next_double,
+    prev_double,
+    f64
+);
+filter_between_ffi!(
+    DoubleListAllBetween,
+    isar_filter_double_list_all_between,
+    next_double,
+    prev_double,
+    f64
+);
+
 #[macro_export]
 macro_rules! filter_not_equal_to_ffi {
     ($filter_name:ident, $function_name:ident, $type:ty) => {
@@ -207,6 +257,36 @@ macro_rules! filter_not_equal_to_ffi {
     }
 }
 
+#[macro_export]
+macro_rules! float_filter_equal_ffi {
+    ($filter_name:ident, $function_name:ident, $type:ty) => {
+        #[no_mangle]
+        pub unsafe extern "C" fn $function_name<'col>(
+            collection: &'col IsarCollection,
+            filter: *mut *const Filter<'col>,
+            value: $type,
+            epsilon: $type,
+            property_index: u32,
+        ) -> i32 {
+            let property = collection.get_properties().get(property_index as usize);
+            isar_try! {
+                if let Some(property) = property {
+                    let query_filter = isar_core::query::filter::$filter_name::filter(property, value, epsilon)?;
+                    let ptr = Box::into_raw(Box::new(query_filter));
+                    filter.write(ptr);
+                } else {
+                    illegal_arg("Property does not exist.")?;
+                }
+            }
+        }
+    }
+}
+
+float_filter_equal_ffi!(FloatEqual, isar_filter_float_equal, f32);
+float_filter_equal_ffi!(DoubleEqual, isar_filter_double_equal, f64);
+
 filter_not_equal_to_ffi!(ByteNotEqual, isar_filter_byte_not_equal, u8);
 filter_not_equal_to_ffi!(IntNotEqual, isar_filter_int_not_equal, i32);
 filter_not_equal_to_ffi!(LongNotEqual, isar_filter_long_not_equal, i64);
+filter_not_equal_to_ffi!(FloatNotEqual, isar_filter_float_not_equal, f32);
+filter_not_equal_to_ffi!(DoubleNotEqual, isar_filter_double_not_equal, f64);
@@ -5,7 +5,7 @@ use crate::error::DartErrCode;
 use crate::from_c_str;
 use isar_core::collection::IsarCollection;
 use isar_core::error::illegal_arg;
-use isar_core::instance::IsarInstance;
+use isar_core::instance::{EnvOptions, IsarInstance};
 use isar_core::schema::Schema;
 use once_cell::sync::Lazy;
 use std::collections::hash_map::Entry;
@@ -20,23 +20,42 @@ struct IsarInstanceSend(*mut *const IsarInstance);
 
 unsafe impl Send for IsarInstanceSend {}
 
+/// The options of `isar_create_instance`. The flags are the same as those of `EnvOptions`.
+#[repr(C)]
+pub struct IsarInstanceOptions {
+    max_readers: u32,
+    no_sync: bool,
+    no_meta_sync: bool,
+    write_map: bool,
+    map_async: bool,
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn isar_create_instance(
     isar: *mut *const IsarInstance,
     path: *const c_char,
     max_size: i64,
+    options: &IsarInstanceOptions,
     schema: *mut Schema,
     port: DartPort,
 ) {
     let isar = IsarInstanceSend(isar);
     let path = from_c_str(path).unwrap().to_string();
     let schema = Box::from_raw(schema);
+    let max_readers = options.max_readers;
+    let options = EnvOptions {
+        no_sync: options.no_sync,
+        no_meta_sync: options.no_meta_sync,
+        write_map: options.write_map,
+        map_async: options.map_async,
+    };
     run_async(move || {
         let mut lock = INSTANCES.lock().unwrap();
         let instance = match lock.entry(path) {
             Entry::Occupied(e) => Ok(&*e.into_mut()),
             Entry::Vacant(e) => {
-                let new_isar = IsarInstance::create(e.key(), max_size as usize, *schema);
+                let new_isar =
+                    IsarInstance::create(e.key(), max_size as usize, max_readers, options, *schema);
                 match new_isar {
                     Ok(new_isar) => Ok(&*e.insert(new_isar)),
                     Err(e) => Err(e),
@@ -71,3 +90,10 @@ pub unsafe extern "C" fn isar_get_collection<'a>(
         }
     }
 }
+
+#[no_mangle]
+pub unsafe extern "C" fn isar_sync(isar: &IsarInstance, force: bool) -> i32 {
+    isar_try! {
+        isar.sync(force)?;
+    }
+}
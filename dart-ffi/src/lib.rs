@@ -12,6 +12,7 @@ pub mod crud;
 mod dart;
 pub mod filter;
 pub mod instance;
+pub mod object_builder;
 pub mod query;
 pub mod raw_object_set;
 pub mod schema;
@@ -0,0 +1,199 @@
+use crate::from_c_str;
+use crate::raw_object_set::RawObject;
+use isar_core::collection::IsarCollection;
+use isar_core::error::illegal_arg;
+use isar_core::object::object_builder::{ObjectBuilder, ObjectBuilderResult};
+use isar_core::object::property_value::PropertyValue;
+use std::os::raw::c_char;
+use std::slice;
+
+unsafe fn list_from_raw<'a, T>(list: *const T, length: u32) -> Option<&'a [T]> {
+    if list.is_null() {
+        None
+    } else {
+        Some(slice::from_raw_parts(list, length as usize))
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn isar_object_builder_create(
+    collection: &'static IsarCollection,
+) -> *mut ObjectBuilder<'static> {
+    Box::into_raw(Box::new(collection.get_object_builder()))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn isar_object_builder_write_null(builder: &mut ObjectBuilder) -> i32 {
+    isar_try! {
+        builder.write_value(&PropertyValue::Null)?;
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn isar_object_builder_write_byte(
+    builder: &mut ObjectBuilder,
+    value: u8,
+) -> i32 {
+    isar_try! {
+        builder.write_value(&PropertyValue::Byte(value))?;
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn isar_object_builder_write_int(
+    builder: &mut ObjectBuilder,
+    value: i32,
+) -> i32 {
+    isar_try! {
+        builder.write_value(&PropertyValue::Int(value))?;
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn isar_object_builder_write_float(
+    builder: &mut ObjectBuilder,
+    value: f32,
+) -> i32 {
+    isar_try! {
+        builder.write_value(&PropertyValue::Float(value))?;
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn isar_object_builder_write_long(
+    builder: &mut ObjectBuilder,
+    value: i64,
+) -> i32 {
+    isar_try! {
+        builder.write_value(&PropertyValue::Long(value))?;
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn isar_object_builder_write_double(
+    builder: &mut ObjectBuilder,
+    value: f64,
+) -> i32 {
+    isar_try! {
+        builder.write_value(&PropertyValue::Double(value))?;
+    }
+}
+
+/// `value` may be null.
+#[no_mangle]
+pub unsafe extern "C" fn isar_object_builder_write_string(
+    builder: &mut ObjectBuilder,
+    value: *const c_char,
+) -> i32 {
+    isar_try! {
+        let value = if !value.is_null() {
+            PropertyValue::String(from_c_str(value)?)
+        } else {
+            PropertyValue::Null
+        };
+        builder.write_value(&value)?;
+    }
+}
+
+macro_rules! write_list_fn {
+    ($name:ident, $type:ty, $variant:ident) => {
+        /// `list` may be null.
+        #[no_mangle]
+        pub unsafe extern "C" fn $name(
+            builder: &mut ObjectBuilder,
+            list: *const $type,
+            length: u32,
+        ) -> i32 {
+            isar_try! {
+                let value = list_from_raw(list, length)
+                    .map_or(PropertyValue::Null, PropertyValue::$variant);
+                builder.write_value(&value)?;
+            }
+        }
+    };
+}
+
+write_list_fn!(isar_object_builder_write_byte_list, u8, ByteList);
+write_list_fn!(isar_object_builder_write_int_list, i32, IntList);
+write_list_fn!(isar_object_builder_write_float_list, f32, FloatList);
+write_list_fn!(isar_object_builder_write_long_list, i64, LongList);
+write_list_fn!(isar_object_builder_write_double_list, f64, DoubleList);
+
+/// `list` and its elements may be null.
+#[no_mangle]
+pub unsafe extern "C" fn isar_object_builder_write_string_list(
+    builder: &mut ObjectBuilder,
+    list: *const *const c_char,
+    length: u32,
+) -> i32 {
+    isar_try! {
+        let value = if let Some(list) = list_from_raw(list, length) {
+            let strings = list
+                .iter()
+                .map(|str| {
+                    if !str.is_null() {
+                        from_c_str(*str).map(Some)
+                    } else {
+                        Ok(None)
+                    }
+                })
+                .collect::<isar_core::error::Result<Vec<_>>>()?;
+            PropertyValue::StringList(strings)
+        } else {
+            PropertyValue::Null
+        };
+        builder.write_value(&value)?;
+    }
+}
+
+/// `list` and its elements may be null. `lengths` contains the length of each element and must
+/// not be null if `list` is not null.
+#[no_mangle]
+pub unsafe extern "C" fn isar_object_builder_write_bytes_list(
+    builder: &mut ObjectBuilder,
+    list: *const *const u8,
+    lengths: *const u32,
+    length: u32,
+) -> i32 {
+    isar_try! {
+        let value = if let Some(list) = list_from_raw(list, length) {
+            let lengths = if let Some(lengths) = list_from_raw(lengths, length) {
+                lengths
+            } else {
+                illegal_arg("The lengths of the list elements are missing.")?
+            };
+            let bytes = list
+                .iter()
+                .zip(lengths)
+                .map(|(bytes, length)| list_from_raw(*bytes, *length))
+                .collect();
+            PropertyValue::BytesList(bytes)
+        } else {
+            PropertyValue::Null
+        };
+        builder.write_value(&value)?;
+    }
+}
+
+/// Consumes the builder and points `object` to the finished object. The returned result owns
+/// the object and has to be freed with `isar_object_builder_free_result` after it was used.
+#[no_mangle]
+pub unsafe extern "C" fn isar_object_builder_finish(
+    builder: *mut ObjectBuilder,
+    object: &mut RawObject,
+) -> *mut ObjectBuilderResult {
+    let builder = Box::from_raw(builder);
+    let result = builder.finish();
+    object.set_object(result.as_bytes());
+    Box::into_raw(Box::new(result))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn isar_object_builder_free(builder: *mut ObjectBuilder) {
+    drop(Box::from_raw(builder));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn isar_object_builder_free_result(result: *mut ObjectBuilderResult) {
+    drop(Box::from_raw(result));
+}
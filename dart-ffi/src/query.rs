@@ -2,10 +2,11 @@ use super::raw_object_set::RawObjectSet;
 use crate::async_txn::IsarAsyncTxn;
 use crate::raw_object_set::RawObjectSetSend;
 use isar_core::collection::IsarCollection;
-use isar_core::error::Result;
+use isar_core::error::{illegal_arg, Result};
 use isar_core::instance::IsarInstance;
+use isar_core::option;
 use isar_core::query::filter::Filter;
-use isar_core::query::query::Query;
+use isar_core::query::query::{AggregationOp, Query};
 use isar_core::query::query_builder::QueryBuilder;
 use isar_core::query::where_clause::WhereClause;
 use isar_core::txn::IsarTxn;
@@ -39,6 +40,29 @@ pub unsafe extern "C" fn isar_qb_set_filter<'col>(
     builder.set_filter(filter);
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn isar_qb_add_filter<'col>(
+    builder: &mut QueryBuilder<'col>,
+    filter: *mut Filter<'col>,
+) {
+    let filter = *Box::from_raw(filter);
+    builder.add_filter(filter);
+}
+
+/// Negative values mean that there is no offset or no limit.
+#[no_mangle]
+pub extern "C" fn isar_qb_set_offset_limit(
+    builder: &mut QueryBuilder,
+    offset: i64,
+    limit: i64,
+) -> i32 {
+    isar_try! {
+        let offset = option!(offset >= 0, offset as usize);
+        let limit = option!(limit >= 0, limit as usize);
+        builder.add_offset_limit(offset, limit)?;
+    }
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn isar_qb_build(builder: *mut QueryBuilder) -> *mut Query {
     let query = Box::from_raw(builder).build();
@@ -89,3 +113,55 @@ pub unsafe extern "C" fn isar_q_count_async(
         Ok(())
     });
 }
+
+fn aggregate(
+    collection: &IsarCollection,
+    query: &Query,
+    txn: &IsarTxn,
+    property_index: u32,
+    op: u8,
+) -> Result<f64> {
+    let property = collection.get_properties().get(property_index as usize);
+    let op = AggregationOp::from_ordinal(op);
+    if let (Some(property), Some(op)) = (property, op) {
+        let result = query.aggregate(txn, property.clone(), op)?;
+        Ok(result.unwrap_or(f64::NAN))
+    } else {
+        illegal_arg("Property or aggregation does not exist.")
+    }
+}
+
+/// Writes `NaN` to `result` if there are no values to aggregate.
+#[no_mangle]
+pub unsafe extern "C" fn isar_q_aggregate(
+    collection: &IsarCollection,
+    query: &Query,
+    txn: &IsarTxn,
+    property_index: u32,
+    op: u8,
+    result: &mut f64,
+) -> i32 {
+    isar_try! {
+        *result = aggregate(collection, query, txn, property_index, op)?;
+    }
+}
+
+struct DoubleSend(&'static mut f64);
+
+unsafe impl Send for DoubleSend {}
+
+#[no_mangle]
+pub unsafe extern "C" fn isar_q_aggregate_async(
+    collection: &'static IsarCollection,
+    query: &'static Query,
+    txn: &IsarAsyncTxn,
+    property_index: u32,
+    op: u8,
+    result: &'static mut f64,
+) {
+    let result = DoubleSend(result);
+    txn.exec(move |txn| -> Result<()> {
+        *(result.0) = aggregate(collection, query, txn, property_index, op)?;
+        Ok(())
+    });
+}
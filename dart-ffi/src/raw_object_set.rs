@@ -32,7 +32,7 @@ impl RawObject {
 
     pub fn set_object_id(&mut self, oid: ObjectId) {
         self.oid_time = oid.get_time();
-        self.oid_counter = oid.get_time();
+        self.oid_counter = oid.get_counter();
         self.oid_rand = oid.get_rand();
     }
 
@@ -68,6 +68,8 @@ impl RawObject {
     }
 }
 
+/// The objects of a `RawObjectSet` point into the transaction they were read in and are only
+/// valid until the transaction ends.
 #[repr(C)]
 pub struct RawObjectSet {
     objects: *mut RawObject,
@@ -80,6 +82,13 @@ pub struct RawObjectSetSend(pub &'static mut RawObjectSet);
 unsafe impl Send for RawObjectSetSend {}
 
 impl RawObjectSet {
+    pub fn new() -> Self {
+        RawObjectSet {
+            objects: ptr::null_mut(),
+            length: 0,
+        }
+    }
+
     pub fn fill_from_query(&mut self, query: &Query, txn: &IsarTxn) -> Result<()> {
         let mut objects = vec![];
         query.find_all(txn, |oid, object| {
@@ -97,6 +106,31 @@ impl RawObjectSet {
     pub fn length(&self) -> u32 {
         self.length
     }
+
+    pub fn get_objects(&self) -> &[RawObject] {
+        if self.objects.is_null() {
+            &[]
+        } else {
+            unsafe { slice::from_raw_parts(self.objects, self.length as usize) }
+        }
+    }
+
+    fn free(&mut self) {
+        if !self.objects.is_null() {
+            unsafe {
+                let objects = slice::from_raw_parts_mut(self.objects, self.length as usize);
+                drop(Box::from_raw(objects));
+            }
+            self.objects = ptr::null_mut();
+            self.length = 0;
+        }
+    }
+}
+
+impl Default for RawObjectSet {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[no_mangle]
@@ -126,3 +160,61 @@ pub unsafe extern "C" fn isar_free_raw_obj(object: &mut RawObject) {
     let data = object.data.sub(padding);
     Vec::from_raw_parts(data as *mut u8, buffer_size, buffer_size);
 }
+
+/// Frees the objects of `set` but not the object data which belongs to the transaction.
+#[no_mangle]
+pub extern "C" fn isar_free_raw_object_set(set: &mut RawObjectSet) {
+    set.free();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RawObjectSet;
+    use isar_core::instance::{EnvOptions, IsarInstance};
+    use isar_core::object::data_type::DataType;
+    use isar_core::schema::collection_schema::CollectionSchema;
+    use isar_core::schema::Schema;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_fill_from_query() {
+        let dir = tempdir().unwrap();
+        let mut col_schema = CollectionSchema::new("col");
+        col_schema.add_property("f1", DataType::Int).unwrap();
+        let mut schema = Schema::new();
+        schema.add_collection(col_schema).unwrap();
+        let isar = IsarInstance::create(
+            dir.path().to_str().unwrap(),
+            10000000,
+            126,
+            EnvOptions::default(),
+            schema,
+        )
+        .unwrap();
+        let col = isar.get_collection(0).unwrap();
+
+        let mut txn = isar.begin_txn(true).unwrap();
+        let mut expected = vec![];
+        for i in 0..3 {
+            let mut ob = col.get_object_builder();
+            ob.write_int(i);
+            let object = ob.finish();
+            let oid = col.put(&txn, None, object.as_bytes()).unwrap();
+            expected.push((oid, object.as_bytes().to_vec()));
+        }
+
+        let query = isar.create_query_builder(col).build();
+        let mut set = RawObjectSet::new();
+        set.fill_from_query(&query, &txn).unwrap();
+        assert_eq!(set.length(), 3);
+        for (object, (oid, data)) in set.get_objects().iter().zip(&expected) {
+            assert_eq!(object.get_object_id(col), Some(*oid));
+            assert_eq!(object.object_as_slice(), &data[..]);
+        }
+
+        set.free();
+        assert_eq!(set.length(), 0);
+        assert!(set.get_objects().is_empty());
+        txn.abort();
+    }
+}
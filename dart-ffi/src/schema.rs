@@ -35,6 +35,14 @@ pub unsafe extern "C" fn isar_schema_add_collection(
     }
 }
 
+#[no_mangle]
+pub extern "C" fn isar_schema_set_collection_version(
+    collection_schema: &mut CollectionSchema,
+    version: u32,
+) {
+    collection_schema.set_version(version);
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn isar_schema_add_property(
     collection_schema: &mut CollectionSchema,
@@ -48,6 +56,32 @@ pub unsafe extern "C" fn isar_schema_add_property(
     }
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn isar_schema_rename_property(
+    collection_schema: &mut CollectionSchema,
+    old_name: *const c_char,
+    new_name: *const c_char,
+) -> i32 {
+    isar_try! {
+        let old_name_str = from_c_str(old_name)?;
+        let new_name_str = from_c_str(new_name)?;
+        collection_schema.rename_property(old_name_str, new_name_str)?;
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn isar_schema_add_link(
+    collection_schema: &mut CollectionSchema,
+    name: *const c_char,
+    target_collection: *const c_char,
+) -> i32 {
+    isar_try! {
+        let name_str = from_c_str(name)?;
+        let target_collection_str = from_c_str(target_collection)?;
+        collection_schema.add_link(name_str, target_collection_str)?;
+    }
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn isar_schema_add_index(
     collection_schema: &mut CollectionSchema,
@@ -55,6 +89,8 @@ pub unsafe extern "C" fn isar_schema_add_index(
     property_names_length: u32,
     unique: bool,
     hash_value: bool,
+    multi_entry: bool,
+    case_sensitive: bool,
 ) -> i32 {
     let property_names_slice =
         slice::from_raw_parts(property_names, property_names_length as usize);
@@ -65,6 +101,12 @@ pub unsafe extern "C" fn isar_schema_add_index(
         .map(|bs| std::str::from_utf8(bs).unwrap())
         .collect();
     isar_try! {
-        collection_schema.add_index(&property_names, unique,hash_value)?;
+        collection_schema.add_index(
+            &property_names,
+            unique,
+            hash_value,
+            multi_entry,
+            case_sensitive,
+        )?;
     }
 }
@@ -31,7 +31,7 @@ pub unsafe extern "C" fn isar_txn_begin_async(
 #[no_mangle]
 pub unsafe extern "C" fn isar_txn_commit(txn: *mut IsarTxn) -> i32 {
     isar_try! {
-        let txn = Box::from_raw(txn);
+        let mut txn = Box::from_raw(txn);
         txn.commit()?;
     }
 }
@@ -44,7 +44,7 @@ pub unsafe extern "C" fn isar_txn_commit_async(txn: *mut IsarAsyncTxn) {
 
 #[no_mangle]
 pub unsafe extern "C" fn isar_txn_abort(txn: *mut IsarTxn) {
-    let txn = Box::from_raw(txn);
+    let mut txn = Box::from_raw(txn);
     txn.abort();
 }
 
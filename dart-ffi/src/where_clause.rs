@@ -47,31 +47,145 @@ pub unsafe extern "C" fn isar_wc_add_oid_time(
     where_clause.add_oid_time(lower, upper);
 }
 
+#[no_mangle]
+pub extern "C" fn isar_wc_add_lower_oid_time(
+    where_clause: &mut WhereClause,
+    value: u32,
+    include: bool,
+) -> bool {
+    where_clause.add_lower_oid_time(value, include)
+}
+
+#[no_mangle]
+pub extern "C" fn isar_wc_add_upper_oid_time(
+    where_clause: &mut WhereClause,
+    value: u32,
+    include: bool,
+) -> bool {
+    where_clause.add_upper_oid_time(value, include)
+}
+
 #[no_mangle]
 pub extern "C" fn isar_wc_add_byte(where_clause: &mut WhereClause, lower: u8, upper: u8) {
     where_clause.add_byte(lower, upper);
 }
 
+#[no_mangle]
+pub extern "C" fn isar_wc_add_lower_byte(
+    where_clause: &mut WhereClause,
+    value: u8,
+    include: bool,
+) -> bool {
+    where_clause.add_lower_byte(value, include)
+}
+
+#[no_mangle]
+pub extern "C" fn isar_wc_add_upper_byte(
+    where_clause: &mut WhereClause,
+    value: u8,
+    include: bool,
+) -> bool {
+    where_clause.add_upper_byte(value, include)
+}
+
+#[no_mangle]
+pub extern "C" fn isar_wc_add_bool(where_clause: &mut WhereClause, value: bool, is_null: bool) {
+    let value = if is_null { None } else { Some(value) };
+    where_clause.add_bool(value);
+}
+
 #[no_mangle]
 pub extern "C" fn isar_wc_add_int(where_clause: &mut WhereClause, lower: i32, upper: i32) {
     where_clause.add_int(lower, upper);
 }
 
+#[no_mangle]
+pub extern "C" fn isar_wc_add_lower_int(
+    where_clause: &mut WhereClause,
+    value: i32,
+    include: bool,
+) -> bool {
+    where_clause.add_lower_int(value, include)
+}
+
+#[no_mangle]
+pub extern "C" fn isar_wc_add_upper_int(
+    where_clause: &mut WhereClause,
+    value: i32,
+    include: bool,
+) -> bool {
+    where_clause.add_upper_int(value, include)
+}
+
 #[no_mangle]
 pub extern "C" fn isar_wc_add_float(where_clause: &mut WhereClause, lower: f32, upper: f32) {
     where_clause.add_float(lower, upper);
 }
 
+#[no_mangle]
+pub extern "C" fn isar_wc_add_lower_float(
+    where_clause: &mut WhereClause,
+    value: f32,
+    include: bool,
+) -> bool {
+    where_clause.add_lower_float(value, include)
+}
+
+#[no_mangle]
+pub extern "C" fn isar_wc_add_upper_float(
+    where_clause: &mut WhereClause,
+    value: f32,
+    include: bool,
+) -> bool {
+    where_clause.add_upper_float(value, include)
+}
+
 #[no_mangle]
 pub extern "C" fn isar_wc_add_long(where_clause: &mut WhereClause, lower: i64, upper: i64) {
     where_clause.add_long(lower, upper);
 }
 
+#[no_mangle]
+pub extern "C" fn isar_wc_add_lower_long(
+    where_clause: &mut WhereClause,
+    value: i64,
+    include: bool,
+) -> bool {
+    where_clause.add_lower_long(value, include)
+}
+
+#[no_mangle]
+pub extern "C" fn isar_wc_add_upper_long(
+    where_clause: &mut WhereClause,
+    value: i64,
+    include: bool,
+) -> bool {
+    where_clause.add_upper_long(value, include)
+}
+
 #[no_mangle]
 pub extern "C" fn isar_wc_add_double(where_clause: &mut WhereClause, lower: f64, upper: f64) {
     where_clause.add_double(lower, upper);
 }
 
+#[no_mangle]
+pub extern "C" fn isar_wc_add_lower_double(
+    where_clause: &mut WhereClause,
+    value: f64,
+    include: bool,
+) -> bool {
+    where_clause.add_lower_double(value, include)
+}
+
+#[no_mangle]
+pub extern "C" fn isar_wc_add_upper_double(
+    where_clause: &mut WhereClause,
+    value: f64,
+    include: bool,
+) -> bool {
+    where_clause.add_upper_double(value, include)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn isar_wc_add_string_hash(
     where_clause: &mut WhereClause,
@@ -103,3 +217,31 @@ pub unsafe extern "C" fn isar_wc_add_string_value(
     };
     where_clause.add_string_value(lower_str, upper_str);
 }
+
+#[no_mangle]
+pub unsafe extern "C" fn isar_wc_add_lower_string_value(
+    where_clause: &mut WhereClause,
+    value: *const c_char,
+    include: bool,
+) -> bool {
+    let str = if !value.is_null() {
+        Some(from_c_str(value).unwrap())
+    } else {
+        None
+    };
+    where_clause.add_lower_string_value(str, include)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn isar_wc_add_upper_string_value(
+    where_clause: &mut WhereClause,
+    value: *const c_char,
+    include: bool,
+) -> bool {
+    let str = if !value.is_null() {
+        Some(from_c_str(value).unwrap())
+    } else {
+        None
+    };
+    where_clause.add_upper_string_value(str, include)
+}
@@ -1,26 +1,43 @@
-use crate::error::{IsarError, Result};
-use crate::index::{Index, IndexType};
+use crate::error::{illegal_arg, IsarError, Result};
+use crate::index::Index;
+use crate::link::Link;
 use crate::lmdb::db::Db;
 use crate::lmdb::txn::Txn;
-use crate::object::object_builder::ObjectBuilder;
+use crate::object::data_type::DataType;
+use crate::object::object_builder::{ObjectBuilder, ObjectBuilderResult};
 use crate::object::object_id::ObjectId;
 use crate::object::object_id_generator::ObjectIdGenerator;
 use crate::object::object_info::ObjectInfo;
+use crate::object::object_reader::ObjectReader;
+use crate::object::property_value::PropertyValue;
+use crate::query::query::Query;
 use crate::query::where_clause::WhereClause;
 use crate::txn::IsarTxn;
 
 use itertools::Itertools;
 use serde_json::{json, Value};
+use std::io::Write;
+use wyhash::wyhash;
 
 use crate::object::property::Property;
 #[cfg(test)]
-use {crate::utils::debug::dump_db, hashbrown::HashSet};
+use hashbrown::HashSet;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct CollectionStats {
+    pub count: u64,
+    /// Sum of the byte sizes of all objects excluding their ids.
+    pub total_size: u64,
+    pub average_size: u64,
+}
 
 pub struct IsarCollection {
     id: u16,
     name: String,
     object_info: ObjectInfo,
+    primary_index: Index,
     indexes: Vec<Index>,
+    links: Vec<Link>,
     db: Db,
     oidg: ObjectIdGenerator,
 }
@@ -31,13 +48,16 @@ impl IsarCollection {
         name: String,
         object_info: ObjectInfo,
         indexes: Vec<Index>,
+        links: Vec<Link>,
         db: Db,
     ) -> Self {
         IsarCollection {
             id,
             name,
             object_info,
+            primary_index: Index::primary(id, db),
             indexes,
+            links,
             db,
             oidg: ObjectIdGenerator::new(id),
         }
@@ -55,10 +75,22 @@ impl IsarCollection {
         self.object_info.get_properties()
     }
 
+    pub fn get_property_names(&self) -> Vec<&str> {
+        self.object_info.get_property_names()
+    }
+
+    pub fn get_property_types(&self) -> Vec<DataType> {
+        self.object_info.get_property_types()
+    }
+
     pub fn get_object_builder(&self) -> ObjectBuilder {
         ObjectBuilder::new(&self.object_info)
     }
 
+    pub fn get_object_reader<'a>(&'a self, object: &'a [u8]) -> ObjectReader<'a> {
+        ObjectReader::new(&self.object_info, object)
+    }
+
     pub fn get_object_id(&self, time: u32, counter: u32, rand: u32) -> ObjectId {
         ObjectId::new(self.id, time, counter, rand)
     }
@@ -67,6 +99,10 @@ impl IsarCollection {
         &self.indexes
     }
 
+    pub(crate) fn get_all_links(&self) -> &[Link] {
+        &self.links
+    }
+
     fn verify_object_id(&self, oid: ObjectId) -> Result<()> {
         if oid.get_prefix() != self.id {
             Err(IsarError::InvalidObjectId {})
@@ -78,59 +114,371 @@ impl IsarCollection {
     pub fn get<'txn>(&self, txn: &'txn IsarTxn, oid: ObjectId) -> Result<Option<&'txn [u8]>> {
         self.verify_object_id(oid)?;
         let oid_bytes = oid.as_bytes();
-        self.db.get(txn.get_txn(), &oid_bytes)
+        self.db.get(txn.get_txn()?, &oid_bytes)
+    }
+
+    /// Gets an object using a unique index. The index key is created from `key_object` which
+    /// has to contain the values of the indexed properties.
+    pub fn get_by_index<'txn>(
+        &self,
+        txn: &'txn IsarTxn,
+        index_index: usize,
+        key_object: &[u8],
+    ) -> Result<Option<(&'txn ObjectId, &'txn [u8])>> {
+        let index = if let Some(index) = self.indexes.get(index_index) {
+            index
+        } else {
+            return illegal_arg("Index does not exist.");
+        };
+        self.object_info.verify_object(key_object)?;
+
+        let lmdb_txn = txn.get_txn()?;
+        if let Some(oid_bytes) = index.get_unique(lmdb_txn, key_object)? {
+            let object = self
+                .db
+                .get(lmdb_txn, oid_bytes)?
+                .ok_or(IsarError::DbCorrupted {
+                    source: None,
+                    message: "Index entry without object.".to_string(),
+                })?;
+            Ok(Some((ObjectId::from_bytes(oid_bytes), object)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn get_all<'txn>(
+        &self,
+        txn: &'txn IsarTxn,
+        oids: &[ObjectId],
+    ) -> Result<Vec<Option<&'txn [u8]>>> {
+        for oid in oids {
+            self.verify_object_id(*oid)?;
+        }
+        let mut order = (0..oids.len()).collect_vec();
+        order.sort_unstable_by(|a, b| oids[*a].as_bytes().cmp(oids[*b].as_bytes()));
+
+        let mut cursor = self.db.cursor(txn.get_txn()?)?;
+        let mut objects = vec![None; oids.len()];
+        for i in order {
+            objects[i] = cursor.move_to(oids[i].as_bytes())?.map(|(_, val)| val);
+        }
+        Ok(objects)
+    }
+
+    /// Iterates over all objects of this collection in ObjectId order.
+    pub fn iter<'a, 'txn>(
+        &'a self,
+        txn: &'txn IsarTxn,
+    ) -> Result<impl Iterator<Item = Result<(&'txn ObjectId, &'txn [u8])>> + 'a>
+    where
+        'txn: 'a,
+    {
+        let iter = self.primary_index.iter(txn.get_txn()?)?;
+        Ok(iter.map_ok(|(key, object)| (ObjectId::from_bytes(key), object)))
     }
 
     pub fn put(&self, txn: &IsarTxn, oid: Option<ObjectId>, object: &[u8]) -> Result<ObjectId> {
+        txn.exec_atomic_write(|lmdb_txn| self.put_internal(lmdb_txn, oid, object))
+    }
+
+    /// Puts the object with an ObjectId derived from a hash of `user_key`. Putting an object
+    /// with the same key again replaces the existing object.
+    ///
+    /// The counter and random part of the ObjectId are the 64-bit wyhash of `user_key` and the
+    /// key itself is not stored. Two different keys with the same hash map to the same ObjectId
+    /// and silently replace each other's object.
+    ///
+    /// The time of derived ObjectIds is always `0`, so `where_created_between()` only matches
+    /// these objects if its range includes `0`.
+    pub fn put_by_key(&self, txn: &IsarTxn, user_key: &[u8], object: &[u8]) -> Result<ObjectId> {
+        let hash = wyhash(user_key, 0);
+        let oid = self.get_object_id(0, (hash >> 32) as u32, hash as u32);
+        self.put(txn, Some(oid), object)
+    }
+
+    pub fn put_all(
+        &self,
+        txn: &IsarTxn,
+        objects: &[(Option<ObjectId>, &[u8])],
+    ) -> Result<Vec<ObjectId>> {
         txn.exec_atomic_write(|lmdb_txn| {
-            let oid = if let Some(oid) = oid {
-                self.verify_object_id(oid)?;
-                self.delete_from_indexes(lmdb_txn, oid)?;
-                oid
-            } else {
-                self.oidg.generate()
-            };
+            objects
+                .iter()
+                .map(|(oid, object)| self.put_internal(lmdb_txn, *oid, object))
+                .collect()
+        })
+    }
 
-            if !self.object_info.verify_object(object) {
-                return Err(IsarError::InvalidObject {});
-            }
+    fn put_internal(
+        &self,
+        lmdb_txn: &Txn,
+        oid: Option<ObjectId>,
+        object: &[u8],
+    ) -> Result<ObjectId> {
+        let oid = if let Some(oid) = oid {
+            self.verify_object_id(oid)?;
+            self.delete_from_indexes(lmdb_txn, oid)?;
+            oid
+        } else {
+            self.oidg.generate()
+        };
 
-            let oid_bytes = oid.as_bytes();
-            for index in &self.indexes {
-                index.create_for_object(lmdb_txn, &oid_bytes, object)?;
+        self.object_info.verify_object(object)?;
+
+        let oid_bytes = oid.as_bytes();
+        for index in &self.indexes {
+            index.create_for_object(lmdb_txn, &oid_bytes, object)?;
+        }
+
+        self.db.put(lmdb_txn, &oid_bytes, object)?;
+        Ok(oid)
+    }
+
+    /// Replaces the given properties of an existing object. `changes` contains the index of
+    /// the property and its new value.
+    pub fn update(
+        &self,
+        txn: &IsarTxn,
+        oid: ObjectId,
+        changes: &[(usize, PropertyValue)],
+    ) -> Result<()> {
+        self.verify_object_id(oid)?;
+        let properties = self.get_properties();
+        for (property_index, value) in changes {
+            if let Some(property) = properties.get(*property_index) {
+                let data_type = value.get_data_type();
+                if data_type.is_some() && data_type != Some(property.data_type) {
+                    illegal_arg("The value does not match the property type.")?;
+                }
+            } else {
+                illegal_arg("Property does not exist.")?;
             }
+        }
+
+        txn.exec_atomic_write(|lmdb_txn| {
+            let existing_object = self.db.get(lmdb_txn, oid.as_bytes())?;
+            let existing_object = if let Some(existing_object) = existing_object {
+                existing_object
+            } else {
+                return illegal_arg("Object does not exist.");
+            };
 
-            self.db.put(lmdb_txn, &oid_bytes, object)?;
-            Ok(oid)
+            let mut builder = self.get_object_builder();
+            for (property_index, property) in properties.iter().enumerate() {
+                let change = changes.iter().rev().find(|(i, _)| *i == property_index);
+                if let Some((_, value)) = change {
+                    builder.write_value(value)?;
+                } else {
+                    builder.write_value(&PropertyValue::read(property, existing_object))?;
+                }
+            }
+            let object = builder.finish();
+            self.put_internal(lmdb_txn, Some(oid), object.as_bytes())?;
+            Ok(())
         })
     }
 
     pub fn delete(&self, txn: &IsarTxn, oid: ObjectId) -> Result<()> {
         self.verify_object_id(oid)?;
         txn.exec_atomic_write(|lmdb_txn| {
-            if self.delete_from_indexes(&lmdb_txn, oid)? {
-                let oid_bytes = oid.as_bytes();
-                self.db.delete(&lmdb_txn, &oid_bytes, None)?;
-            }
+            self.delete_internal(lmdb_txn, oid)?;
             Ok(())
         })
     }
 
-    pub(crate) fn delete_all_internal(&self, lmdb_txn: &Txn) -> Result<()> {
+    pub fn delete_query(&self, txn: &IsarTxn, query: &Query) -> Result<u32> {
+        txn.get_write_txn()?;
+        let mut oids = vec![];
+        query.find_all(txn, |oid, _| {
+            oids.push(*oid);
+            true
+        })?;
+        txn.exec_atomic_write(|lmdb_txn| {
+            let mut count = 0;
+            for oid in oids {
+                self.verify_object_id(oid)?;
+                if self.delete_internal(lmdb_txn, oid)? {
+                    count += 1;
+                }
+            }
+            Ok(count)
+        })
+    }
+
+    /// Deletes the first object matching `query` and returns its id.
+    pub fn delete_first(&self, txn: &IsarTxn, query: &Query) -> Result<Option<ObjectId>> {
+        txn.get_write_txn()?;
+        let oid = query.find_first(txn)?.map(|(oid, _)| *oid);
+        if let Some(oid) = oid {
+            self.verify_object_id(oid)?;
+            txn.exec_atomic_write(|lmdb_txn| self.delete_internal(lmdb_txn, oid))?;
+        }
+        Ok(oid)
+    }
+
+    fn delete_internal(&self, lmdb_txn: &Txn, oid: ObjectId) -> Result<bool> {
+        if self.delete_from_indexes(lmdb_txn, oid)? {
+            for link in &self.links {
+                link.delete_for_object(lmdb_txn, oid)?;
+            }
+            let oid_bytes = oid.as_bytes();
+            self.db.delete(lmdb_txn, &oid_bytes, None)?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    pub(crate) fn delete_all_internal(&self, lmdb_txn: &Txn) -> Result<usize> {
         for index in &self.indexes {
             index.clear(&lmdb_txn)?;
         }
-        self.db
-            .delete_key_prefix(&lmdb_txn, &self.id.to_le_bytes())?;
-        Ok(())
+        for link in &self.links {
+            link.clear(&lmdb_txn)?;
+        }
+        self.primary_index.clear(&lmdb_txn)
     }
 
     pub fn delete_all(&self, txn: &IsarTxn) -> Result<()> {
-        txn.exec_atomic_write(|lmdb_txn| self.delete_all_internal(lmdb_txn))
+        txn.exec_atomic_write(|lmdb_txn| self.delete_all_internal(lmdb_txn))?;
+        Ok(())
+    }
+
+    /// Deletes all objects of this collection in a single atomic write and returns how many
+    /// objects were deleted. Other collections sharing the same dbs are not affected.
+    pub fn clear(&self, txn: &IsarTxn) -> Result<u32> {
+        let count = txn.exec_atomic_write(|lmdb_txn| self.delete_all_internal(lmdb_txn))?;
+        Ok(count as u32)
+    }
+
+    /// Checks that the stored objects are valid and that the indexes contain exactly their
+    /// entries. Returns a description of every inconsistency.
+    pub fn verify_indexes(&self, txn: &IsarTxn) -> Result<Vec<String>> {
+        let lmdb_txn = txn.get_txn()?;
+        let mut errors = vec![];
+        self.primary_index.iter_entries(lmdb_txn, |oid, object| {
+            if let Err(e) = self.object_info.verify_stored_object(object) {
+                errors.push(format!(
+                    "Object {} is corrupted: {}",
+                    ObjectId::format_bytes(oid),
+                    e
+                ));
+            }
+            Ok(true)
+        })?;
+
+        for index in &self.indexes {
+            errors.extend(index.verify(lmdb_txn, &self.primary_index, &self.object_info)?);
+        }
+        Ok(errors)
+    }
+
+    /// Returns the number of objects in this collection and their size.
+    pub fn get_stats(&self, txn: &IsarTxn) -> Result<CollectionStats> {
+        let mut stats = CollectionStats::default();
+        self.primary_index
+            .iter_entries(txn.get_txn()?, |_, object| {
+                stats.count += 1;
+                stats.total_size += object.len() as u64;
+                Ok(true)
+            })?;
+        stats.average_size = stats.total_size.checked_div(stats.count).unwrap_or(0);
+        Ok(stats)
+    }
+
+    fn get_link(&self, link_index: usize) -> Result<&Link> {
+        if let Some(link) = self.links.get(link_index) {
+            Ok(link)
+        } else {
+            illegal_arg("Link does not exist.")
+        }
+    }
+
+    fn verify_link_objects(
+        &self,
+        lmdb_txn: &Txn,
+        link: &Link,
+        source: ObjectId,
+        target: ObjectId,
+    ) -> Result<()> {
+        self.verify_object_id(source)?;
+        if target.get_prefix() != link.get_target_col_id() {
+            return Err(IsarError::InvalidObjectId {});
+        }
+        if self.db.get(lmdb_txn, source.as_bytes())?.is_none()
+            || self.db.get(lmdb_txn, target.as_bytes())?.is_none()
+        {
+            illegal_arg("Linked objects have to exist.")?;
+        }
+        Ok(())
+    }
+
+    /// Links `source` to `target`. Returns `false` if the link already existed.
+    pub fn link(
+        &self,
+        txn: &IsarTxn,
+        link_index: usize,
+        source: ObjectId,
+        target: ObjectId,
+    ) -> Result<bool> {
+        let link = self.get_link(link_index)?;
+        txn.exec_atomic_write(|lmdb_txn| {
+            self.verify_link_objects(lmdb_txn, link, source, target)?;
+            link.create(lmdb_txn, source, target)
+        })
+    }
+
+    /// Removes the link from `source` to `target`. Returns `false` if there was no such link.
+    pub fn unlink(
+        &self,
+        txn: &IsarTxn,
+        link_index: usize,
+        source: ObjectId,
+        target: ObjectId,
+    ) -> Result<bool> {
+        let link = self.get_link(link_index)?;
+        self.verify_object_id(source)?;
+        txn.exec_atomic_write(|lmdb_txn| link.delete(lmdb_txn, source, target))
+    }
+
+    /// Returns the ObjectIds of all objects linked from `source`.
+    ///
+    /// Links are only removed when their source object is deleted.
+    pub fn get_links(
+        &self,
+        txn: &IsarTxn,
+        link_index: usize,
+        source: ObjectId,
+    ) -> Result<Vec<ObjectId>> {
+        let link = self.get_link(link_index)?;
+        self.verify_object_id(source)?;
+        link.get_targets(txn.get_txn()?, source)
     }
 
     pub fn create_primary_where_clause(&self) -> WhereClause {
-        WhereClause::new(&self.id.to_le_bytes(), IndexType::Primary)
+        self.primary_index.create_where_clause()
+    }
+
+    /// Creates a primary where clause that matches objects whose ObjectId time is between
+    /// `lower_time` and `upper_time` (inclusive). Objects put with `put_by_key()` have the
+    /// time `0`.
+    pub fn where_created_between(&self, lower_time: u32, upper_time: u32) -> WhereClause {
+        let mut wc = self.create_primary_where_clause();
+        wc.add_oid_time(lower_time, upper_time);
+        wc
+    }
+
+    /// Creates a primary where clause for each of the `oids`. Adding all of them to a query
+    /// selects exactly these objects in ObjectId order.
+    pub fn create_primary_where_clauses_for(&self, oids: &[ObjectId]) -> Vec<WhereClause> {
+        oids.iter()
+            .map(|oid| {
+                let mut wc = self.create_primary_where_clause();
+                wc.add_oid(*oid);
+                wc
+            })
+            .collect()
     }
 
     pub fn create_secondary_where_clause(&self, index_index: usize) -> Option<WhereClause> {
@@ -143,6 +491,7 @@ impl IsarCollection {
         let oid_bytes = oid.as_bytes();
         let existing_object = self.db.get(lmdb_txn, &oid_bytes)?;
         if let Some(existing_object) = existing_object {
+            self.object_info.verify_stored_object(existing_object)?;
             for index in &self.indexes {
                 index.delete_for_object(&lmdb_txn, oid_bytes, existing_object)?;
             }
@@ -152,25 +501,174 @@ impl IsarCollection {
         }
     }
 
-    pub fn export_json(&self, txn: &IsarTxn, primitive_null: bool) -> Result<Value> {
-        let mut cursor = self.db.cursor(txn.get_txn())?;
-        let result = cursor.move_to_gte(&self.id.to_le_bytes())?;
-        if result.is_none() {
-            return Ok(json!(Vec::<Value>::new()));
+    /// Exports all objects as a JSON array. Null strings are exported as `emit_null_strings_as`
+    /// instead of JSON null if it is set. The same value has to be passed to `import_json`. Fails
+    /// with `IllegalArg` if a non-null string is equal to `emit_null_strings_as`.
+    ///
+    /// `ByteList` values are encoded as arrays of numbers and `BytesList` values as arrays of
+    /// such arrays (or `null`) so arbitrary bytes survive `import_json`.
+    pub fn export_json(
+        &self,
+        txn: &IsarTxn,
+        primitive_null: bool,
+        emit_null_strings_as: Option<&str>,
+    ) -> Result<Value> {
+        let mut items = vec![];
+        self.primary_index
+            .iter_entries(txn.get_txn()?, |key, val| {
+                let json = self.object_info.entry_to_json(
+                    key,
+                    val,
+                    primitive_null,
+                    emit_null_strings_as,
+                )?;
+                items.push(json);
+                Ok(true)
+            })?;
+        Ok(json!(items))
+    }
+
+    /// Writes the objects of this collection to `writer` as newline delimited JSON. Objects
+    /// are written one at a time while iterating so the collection is never held in memory.
+    pub fn export_ndjson<W: Write>(
+        &self,
+        txn: &IsarTxn,
+        writer: &mut W,
+        primitive_null: bool,
+        emit_null_strings_as: Option<&str>,
+    ) -> Result<()> {
+        self.primary_index.iter_entries(txn.get_txn()?, |key, val| {
+            let json =
+                self.object_info
+                    .entry_to_json(key, val, primitive_null, emit_null_strings_as)?;
+            serde_json::to_writer(&mut *writer, &json).map_err(|e| IsarError::IoError {
+                source: Some(Box::new(e)),
+                message: "Could not write JSON object.".to_string(),
+            })?;
+            writer.write_all(b"\n").map_err(|e| IsarError::IoError {
+                source: Some(Box::new(e)),
+                message: "Could not write JSON object.".to_string(),
+            })?;
+            Ok(true)
+        })
+    }
+
+    /// Imports a JSON array of objects. Strings equal to `null_strings_as` are imported as null.
+    pub fn import_json(
+        &self,
+        txn: &IsarTxn,
+        json: &Value,
+        replace_ids: bool,
+        null_strings_as: Option<&str>,
+    ) -> Result<Vec<ObjectId>> {
+        let array = if let Some(array) = json.as_array() {
+            array
+        } else {
+            return illegal_arg("The JSON has to be an array of objects.");
+        };
+        txn.exec_atomic_write(|lmdb_txn| {
+            array
+                .iter()
+                .map(|value| {
+                    let (oid, object) = self.json_to_object(value, replace_ids, null_strings_as)?;
+                    self.put_internal(lmdb_txn, oid, object.as_bytes())
+                })
+                .collect()
+        })
+    }
+
+    fn json_to_object(
+        &self,
+        json: &Value,
+        replace_ids: bool,
+        null_strings_as: Option<&str>,
+    ) -> Result<(Option<ObjectId>, ObjectBuilderResult)> {
+        let map = if let Some(map) = json.as_object() {
+            map
+        } else {
+            return illegal_arg("The JSON array has to contain only objects.");
+        };
+        for key in map.keys() {
+            let known = key == "id" || self.get_properties().iter().any(|p| &p.name == key);
+            if !known {
+                return illegal_arg(&format!("Unknown property \"{}\".", key));
+            }
         }
-        let items: Result<Vec<Value>> = cursor
-            .iter()
-            .map_ok(|(key, val)| self.object_info.entry_to_json(key, val, primitive_null))
-            .collect();
-        Ok(json!(items?))
+
+        let oid = match map.get("id") {
+            Some(id) if !replace_ids => Some(self.json_to_object_id(id)?),
+            _ => None,
+        };
+
+        let mut builder = self.get_object_builder();
+        for property in self.get_properties() {
+            let value = map.get(&property.name).unwrap_or(&Value::Null);
+            Self::write_json_value(&mut builder, property.data_type, value, null_strings_as)?;
+        }
+        Ok((oid, builder.finish()))
+    }
+
+    fn json_to_object_id(&self, json: &Value) -> Result<ObjectId> {
+        if let Some(id) = json.as_str() {
+            ObjectId::from_str_for(id, self.id)
+        } else {
+            Err(IsarError::InvalidObjectId {})
+        }
+    }
+
+    fn write_json_value(
+        builder: &mut ObjectBuilder,
+        data_type: DataType,
+        value: &Value,
+        null_strings_as: Option<&str>,
+    ) -> Result<()> {
+        if value.is_null() {
+            builder.write_null();
+            return Ok(());
+        }
+        match data_type {
+            DataType::Byte => builder.write_byte(json_to_byte(value)?),
+            DataType::Int => builder.write_int(json_to_int(value)?),
+            DataType::Float => builder.write_float(json_to_double(value)? as f32),
+            DataType::Long => builder.write_long(json_to_long(value)?),
+            DataType::Double => builder.write_double(json_to_double(value)?),
+            DataType::String => builder.write_string(json_to_str_opt(value, null_strings_as)?),
+            DataType::ByteList => {
+                builder.write_byte_list(Some(&json_to_list(value, json_to_byte)?))
+            }
+            DataType::IntList => builder.write_int_list(Some(&json_to_list(value, json_to_int)?)),
+            DataType::FloatList => {
+                let list = json_to_list(value, |v| json_to_double(v).map(|d| d as f32))?;
+                builder.write_float_list(Some(&list))
+            }
+            DataType::LongList => {
+                builder.write_long_list(Some(&json_to_list(value, json_to_long)?))
+            }
+            DataType::DoubleList => {
+                builder.write_double_list(Some(&json_to_list(value, json_to_double)?))
+            }
+            DataType::StringList => {
+                let list = json_to_list(value, |v| json_to_str_opt(v, null_strings_as))?;
+                builder.write_string_list(Some(&list))
+            }
+            DataType::BytesList => {
+                let list = json_to_list(value, |v| {
+                    if v.is_null() {
+                        Ok(None)
+                    } else {
+                        json_to_list(v, json_to_byte).map(Some)
+                    }
+                })?;
+                let list = list.iter().map(|l| l.as_deref()).collect::<Vec<_>>();
+                builder.write_bytes_list(Some(&list))
+            }
+        }
+        Ok(())
     }
 
     #[cfg(test)]
     pub fn debug_dump(&self, txn: &IsarTxn) -> HashSet<(Vec<u8>, Vec<u8>)> {
-        dump_db(self.db, &txn, Some(&self.id.to_le_bytes()))
-            .into_iter()
-            .map(|(key, val)| (key.to_vec(), val))
-            .collect()
+        self.primary_index.debug_dump(txn)
     }
 
     #[cfg(test)]
@@ -189,9 +687,202 @@ impl IsarCollection {
     }
 }
 
+fn json_to_byte(value: &Value) -> Result<u8> {
+    match value.as_u64() {
+        Some(byte) if byte <= u8::MAX as u64 => Ok(byte as u8),
+        _ => illegal_arg("Expected a byte value."),
+    }
+}
+
+fn json_to_int(value: &Value) -> Result<i32> {
+    match value.as_i64() {
+        Some(int) if int >= i32::MIN as i64 && int <= i32::MAX as i64 => Ok(int as i32),
+        _ => illegal_arg("Expected an int value."),
+    }
+}
+
+fn json_to_long(value: &Value) -> Result<i64> {
+    match value.as_i64() {
+        Some(long) => Ok(long),
+        _ => illegal_arg("Expected a long value."),
+    }
+}
+
+fn json_to_double(value: &Value) -> Result<f64> {
+    match value.as_f64() {
+        Some(double) => Ok(double),
+        _ => illegal_arg("Expected a floating point value."),
+    }
+}
+
+fn json_to_str(value: &Value) -> Result<&str> {
+    match value.as_str() {
+        Some(str) => Ok(str),
+        _ => illegal_arg("Expected a string value."),
+    }
+}
+
+fn json_to_str_opt<'a>(value: &'a Value, null_strings_as: Option<&str>) -> Result<Option<&'a str>> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    let str = json_to_str(value)?;
+    if Some(str) == null_strings_as {
+        Ok(None)
+    } else {
+        Ok(Some(str))
+    }
+}
+
+fn json_to_list<'a, T>(
+    value: &'a Value,
+    convert: impl Fn(&'a Value) -> Result<T>,
+) -> Result<Vec<T>> {
+    match value.as_array() {
+        Some(list) => list.iter().map(convert).collect(),
+        _ => illegal_arg("Expected a list value."),
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::{CollectionStats, IsarCollection};
+    use crate::error::IsarError;
+    use crate::object::object_id::ObjectId;
+    use crate::object::property_value::PropertyValue;
+    use crate::query::filter::{IntBetween, Not};
+    use crate::txn::IsarTxn;
+    use crate::utils::aligned_vec;
     use crate::{col, ind, isar, set};
+    use serde_json::json;
+
+    #[test]
+    fn test_get_all() {
+        isar!(isar, col => col!(field1 => Int));
+        let txn = isar.begin_txn(true).unwrap();
+
+        let mut objects = vec![];
+        let mut oids = vec![];
+        for value in &[3, 1, 2] {
+            let mut builder = col.get_object_builder();
+            builder.write_int(*value);
+            let object = builder.finish();
+            oids.push(col.put(&txn, None, object.as_bytes()).unwrap());
+            objects.push(object);
+        }
+        let missing_oid = col.get_object_id(1, 2, 3);
+
+        let result = col
+            .get_all(&txn, &[oids[2], missing_oid, oids[0], oids[2]])
+            .unwrap();
+        assert_eq!(
+            result,
+            vec![
+                Some(objects[2].as_bytes()),
+                None,
+                Some(objects[0].as_bytes()),
+                Some(objects[2].as_bytes())
+            ]
+        );
+
+        let other_oid = ObjectId::new(col.get_id() + 1, 1, 2, 3);
+        assert!(col.get_all(&txn, &[oids[0], other_oid]).is_err());
+    }
+
+    #[test]
+    fn test_get_by_index() {
+        isar!(isar, col => col!(f1 => Int, f2 => Int; ind!(f1; true), ind!(f2)));
+        let txn = isar.begin_txn(true).unwrap();
+
+        let build = |f1: i32, f2: i32| {
+            let mut builder = col.get_object_builder();
+            builder.write_int(f1);
+            builder.write_int(f2);
+            builder.finish()
+        };
+        let object1 = build(1, 5);
+        let oid1 = col.put(&txn, None, object1.as_bytes()).unwrap();
+        let object2 = build(2, 5);
+        let oid2 = col.put(&txn, None, object2.as_bytes()).unwrap();
+
+        let result = col.get_by_index(&txn, 0, build(2, 0).as_bytes()).unwrap();
+        assert_eq!(result, Some((&oid2, object2.as_bytes())));
+        let result = col.get_by_index(&txn, 0, build(1, 9).as_bytes()).unwrap();
+        assert_eq!(result, Some((&oid1, object1.as_bytes())));
+
+        let result = col.get_by_index(&txn, 0, build(3, 5).as_bytes()).unwrap();
+        assert_eq!(result, None);
+
+        let result = col.get_by_index(&txn, 1, build(1, 5).as_bytes());
+        assert!(matches!(result, Err(IsarError::IllegalArg { .. })));
+        let result = col.get_by_index(&txn, 2, build(1, 5).as_bytes());
+        assert!(matches!(result, Err(IsarError::IllegalArg { .. })));
+    }
+
+    #[test]
+    fn test_primary_index() {
+        isar!(isar, col1 => col!("col1", f1 => Int), col2 => col!("col2", f1 => Int));
+        let txn = isar.begin_txn(true).unwrap();
+        let oids = (0..5).map(|i| put_int(&txn, col1, i)).collect::<Vec<_>>();
+        put_int(&txn, col2, 5);
+
+        let mut entries = vec![];
+        col1.primary_index
+            .iter_entries(txn.get_txn().unwrap(), |oid, _| {
+                entries.push(*ObjectId::from_bytes(oid));
+                Ok(true)
+            })
+            .unwrap();
+        assert_eq!(entries, oids);
+
+        let mut qb = isar.create_query_builder(col1);
+        qb.add_where_clause(col1.create_primary_where_clause(), true, true);
+        let results = qb.build().find_all_vec(&txn).unwrap();
+        assert_eq!(
+            results.iter().map(|(oid, _)| **oid).collect::<Vec<_>>(),
+            oids
+        );
+
+        let mut wc = col1.create_primary_where_clause();
+        wc.add_oid(oids[2]);
+        let mut qb = isar.create_query_builder(col1);
+        qb.add_where_clause(wc, true, true);
+        let results = qb.build().find_all_vec(&txn).unwrap();
+        assert_eq!(
+            results.iter().map(|(oid, _)| **oid).collect::<Vec<_>>(),
+            vec![oids[2]]
+        );
+
+        let lmdb_txn = txn.get_txn().unwrap();
+        assert_eq!(col1.primary_index.clear(lmdb_txn).unwrap(), 5);
+        assert_eq!(col2.debug_dump(&txn).len(), 1);
+    }
+
+    #[test]
+    fn test_iter() {
+        isar!(isar, col1 => col!("col1", f1 => Int), col2 => col!("col2", f1 => Int));
+
+        let txn = isar.begin_txn(true).unwrap();
+        let mut oids = vec![];
+        for (i, time) in [5, 1, 3].iter().enumerate() {
+            let mut builder = col1.get_object_builder();
+            builder.write_int(i as i32);
+            let oid = col1.get_object_id(*time, 0, 0);
+            col1.put(&txn, Some(oid), builder.finish().as_bytes())
+                .unwrap();
+            oids.push(oid);
+        }
+        put_int(&txn, col2, 10);
+
+        let entries = col1
+            .iter(&txn)
+            .unwrap()
+            .map(Result::unwrap)
+            .map(|(oid, object)| (*oid, col1.get_properties()[0].get_int(object)))
+            .collect::<Vec<_>>();
+        assert_eq!(entries, vec![(oids[1], 1), (oids[2], 2), (oids[0], 0)]);
+        assert_eq!(col2.iter(&txn).unwrap().count(), 1);
+    }
 
     #[test]
     fn test_put_new() {
@@ -256,6 +947,209 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_put_by_key() {
+        isar!(isar, col => col!(field1 => Int; ind!(field1)));
+
+        let txn = isar.begin_txn(true).unwrap();
+
+        let oid1 = put_int(&txn, col, 1);
+        let object2 = {
+            let mut builder = col.get_object_builder();
+            builder.write_int(2);
+            builder.finish()
+        };
+        let oid2 = col.put_by_key(&txn, b"key", object2.as_bytes()).unwrap();
+        assert_ne!(oid1, oid2);
+
+        let mut builder = col.get_object_builder();
+        builder.write_int(3);
+        let object3 = builder.finish();
+        let oid3 = col.put_by_key(&txn, b"key", object3.as_bytes()).unwrap();
+        assert_eq!(oid2, oid3);
+        assert_eq!(oid3.get_time(), 0);
+
+        let mut builder = col.get_object_builder();
+        builder.write_int(4);
+        let object4 = builder.finish();
+        let oid4 = col.put_by_key(&txn, b"other", object4.as_bytes()).unwrap();
+        assert_ne!(oid3, oid4);
+
+        assert_eq!(col.debug_dump(&txn).len(), 3);
+        assert_eq!(col.get(&txn, oid2).unwrap().unwrap(), object3.as_bytes());
+        assert_eq!(col.debug_get_index(0).debug_dump(&txn).len(), 3);
+
+        let find_created_between = |lower: u32, upper: u32| {
+            let mut qb = isar.create_query_builder(col);
+            qb.add_where_clause(col.where_created_between(lower, upper), true, true);
+            let results = qb.build().find_all_vec(&txn).unwrap();
+            results
+                .iter()
+                .map(|(oid, _)| **oid)
+                .collect::<hashbrown::HashSet<_>>()
+        };
+        assert_eq!(find_created_between(0, 0), set![oid2, oid4]);
+        assert_eq!(find_created_between(1, u32::MAX), set![oid1]);
+    }
+
+    #[test]
+    fn test_put_all() {
+        isar!(isar, col => col!(field1 => Int; ind!(field1)));
+        let txn = isar.begin_txn(true).unwrap();
+
+        let mut builder = col.get_object_builder();
+        builder.write_int(1111);
+        let object1 = builder.finish();
+
+        let mut builder = col.get_object_builder();
+        builder.write_int(2222);
+        let object2 = builder.finish();
+
+        let existing_oid = col.oidg.generate();
+        let oids = col
+            .put_all(
+                &txn,
+                &[
+                    (None, object1.as_bytes()),
+                    (Some(existing_oid), object2.as_bytes()),
+                ],
+            )
+            .unwrap();
+        assert_eq!(oids.len(), 2);
+        assert_eq!(oids[1], existing_oid);
+
+        assert_eq!(
+            col.debug_dump(&txn),
+            set![
+                (oids[0].as_bytes().to_vec(), object1.as_bytes().to_vec()),
+                (oids[1].as_bytes().to_vec(), object2.as_bytes().to_vec())
+            ]
+        );
+
+        let index = &col.indexes[0];
+        assert_eq!(
+            index.debug_dump(&txn),
+            set![
+                (
+                    index.debug_create_key(object1.as_bytes()),
+                    oids[0].as_bytes().to_vec()
+                ),
+                (
+                    index.debug_create_key(object2.as_bytes()),
+                    oids[1].as_bytes().to_vec()
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn test_put_all_unique_violated() {
+        isar!(isar, col => col!(field1 => Int; ind!(field1; true)));
+        let txn = isar.begin_txn(true).unwrap();
+
+        let mut builder = col.get_object_builder();
+        builder.write_int(1111);
+        let object = builder.finish();
+
+        let result = col.put_all(
+            &txn,
+            &[(None, object.as_bytes()), (None, object.as_bytes())],
+        );
+        assert!(matches!(result, Err(IsarError::UniqueViolated { .. })));
+        assert!(col.debug_dump(&txn).is_empty());
+    }
+
+    #[test]
+    fn test_put_update_unique_violated() {
+        isar!(isar, col => col!(field1 => Int; ind!(field1; true)));
+        let mut txn = isar.begin_txn(true).unwrap();
+        let oid1 = put_int(&txn, col, 1);
+        let oid2 = put_int(&txn, col, 2);
+        txn.commit().unwrap();
+
+        let mut txn = isar.begin_txn(true).unwrap();
+        let mut builder = col.get_object_builder();
+        builder.write_int(1);
+        let object = builder.finish();
+        let result = col.put(&txn, Some(oid2), object.as_bytes());
+        match result {
+            Err(IsarError::UniqueViolated {
+                index,
+                value,
+                existing_oid,
+            }) => {
+                assert_eq!(index, "field1");
+                assert_eq!(value, "1");
+                assert_eq!(existing_oid, oid1.to_string());
+            }
+            _ => panic!("wrong error"),
+        }
+        assert_eq!(
+            col.get(&txn, oid2)
+                .unwrap()
+                .map(|o| col.get_properties()[0].get_int(o)),
+            Some(2)
+        );
+        txn.abort();
+
+        let txn = isar.begin_txn(false).unwrap();
+        assert_eq!(
+            col.get(&txn, oid2)
+                .unwrap()
+                .map(|o| col.get_properties()[0].get_int(o)),
+            Some(2)
+        );
+        assert!(col.verify_indexes(&txn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_put_invalid_object() {
+        isar!(isar, col => col!(f1 => Int, f2 => IntList));
+        let txn = isar.begin_txn(true).unwrap();
+        let mut builder = col.get_object_builder();
+        builder.write_int(1);
+        builder.write_int_list(Some(&[1, 2]));
+        let object = builder.finish();
+        let len = object.as_bytes().len();
+
+        let oid_padding = ObjectId::get_size() % 8;
+        let mut buffer = aligned_vec(oid_padding + len + 8);
+        buffer.resize(oid_padding, 0);
+        buffer.extend_from_slice(object.as_bytes());
+        buffer.resize(oid_padding + len + 8, 0);
+
+        let assert_invalid = |object: &[u8], expected: &str| match col.put(&txn, None, object) {
+            Err(IsarError::InvalidObject { message }) => assert_eq!(message, expected),
+            _ => panic!("wrong error"),
+        };
+        let start = oid_padding;
+        assert_invalid(
+            &buffer[start + 1..start + len + 1],
+            "The object is not aligned.",
+        );
+        assert_invalid(
+            &buffer[start..start + len - 1],
+            "The object length is not aligned.",
+        );
+        assert_invalid(&buffer[start..start + len - 8], "The object is too short.");
+        assert_invalid(&buffer[start..start + len + 8], "The object is too long.");
+
+        let properties = col.get_properties();
+        assert!(properties[0].offset > 0);
+        buffer[start] = 1;
+        let message = "The padding contains non-zero bytes.";
+        assert_invalid(&buffer[start..start + len], message);
+        buffer[start] = 0;
+
+        let list_offset = start + properties[1].offset;
+        buffer[list_offset] += 4;
+        let message = "Hole in the dynamic data of property f2.";
+        assert_invalid(&buffer[start..start + len], message);
+        buffer[list_offset] -= 4;
+
+        col.put(&txn, None, &buffer[start..start + len]).unwrap();
+    }
+
     #[test]
     fn test_put_creates_index() {
         isar!(isar, col => col!(field1 => Int; ind!(field1)));
@@ -303,6 +1197,82 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_update() {
+        isar!(isar, col => col!(f1 => Int, f2 => Long, f3 => String; ind!(f1), ind!(f2)));
+
+        let txn = isar.begin_txn(true).unwrap();
+
+        let mut builder = col.get_object_builder();
+        builder.write_int(1);
+        builder.write_long(2);
+        builder.write_string(Some("isar"));
+        let object = builder.finish();
+        let oid = col.put(&txn, None, object.as_bytes()).unwrap();
+
+        col.update(&txn, oid, &[(0, PropertyValue::Int(5))])
+            .unwrap();
+
+        let mut builder = col.get_object_builder();
+        builder.write_int(5);
+        builder.write_long(2);
+        builder.write_string(Some("isar"));
+        let expected = builder.finish();
+        assert_eq!(
+            col.debug_dump(&txn),
+            set![(oid.as_bytes().to_vec(), expected.as_bytes().to_vec())]
+        );
+
+        for index in &col.indexes {
+            assert_eq!(
+                index.debug_dump(&txn),
+                set![(
+                    index.debug_create_key(expected.as_bytes()),
+                    oid.as_bytes().to_vec()
+                )]
+            );
+        }
+
+        col.update(
+            &txn,
+            oid,
+            &[(2, PropertyValue::Null), (1, PropertyValue::Long(7))],
+        )
+        .unwrap();
+        let object = col.get(&txn, oid).unwrap().unwrap();
+        let mut reader = col.get_object_reader(object);
+        assert_eq!(reader.read_int(), 5);
+        assert_eq!(reader.read_long(), 7);
+        assert_eq!(reader.read_string(), None);
+    }
+
+    #[test]
+    fn test_update_invalid() {
+        isar!(isar, col => col!(f1 => Int));
+
+        let txn = isar.begin_txn(true).unwrap();
+
+        let mut builder = col.get_object_builder();
+        builder.write_int(1);
+        let object = builder.finish();
+        let oid = col.put(&txn, None, object.as_bytes()).unwrap();
+
+        let result = col.update(&txn, oid, &[(1, PropertyValue::Int(5))]);
+        assert!(matches!(result, Err(IsarError::IllegalArg { .. })));
+
+        let result = col.update(&txn, oid, &[(0, PropertyValue::Long(5))]);
+        assert!(matches!(result, Err(IsarError::IllegalArg { .. })));
+
+        let missing = col.get_object_id(1, 2, 3);
+        let result = col.update(&txn, missing, &[(0, PropertyValue::Int(5))]);
+        assert!(matches!(result, Err(IsarError::IllegalArg { .. })));
+
+        assert_eq!(
+            col.debug_dump(&txn),
+            set![(oid.as_bytes().to_vec(), object.as_bytes().to_vec())]
+        );
+    }
+
     #[test]
     fn test_delete() {
         isar!(isar, col => col!(field1 => Int; ind!(field1)));
@@ -336,6 +1306,549 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_delete_first() {
+        isar!(isar, col => col!(field1 => Int; ind!(field1)));
+        let mut txn = isar.begin_txn(true).unwrap();
+        let oids = [3, 1, 2]
+            .iter()
+            .map(|value| put_int(&txn, col, *value))
+            .collect::<Vec<_>>();
+
+        let mut qb = isar.create_query_builder(col);
+        qb.add_where_clause(col.create_secondary_where_clause(0).unwrap(), true, true);
+        let query = qb.build();
+        assert_eq!(col.delete_first(&txn, &query).unwrap(), Some(oids[1]));
+        assert_eq!(col.delete_first(&txn, &query).unwrap(), Some(oids[2]));
+        assert!(col.get(&txn, oids[1]).unwrap().is_none());
+        assert!(col.get(&txn, oids[0]).unwrap().is_some());
+        assert_eq!(col.debug_get_index(0).debug_dump(&txn).len(), 1);
+
+        let mut qb = isar.create_query_builder(col);
+        let property = col.get_properties().first().unwrap();
+        qb.set_filter(IntBetween::filter(property, 5, 10).unwrap());
+        let empty_query = qb.build();
+        assert_eq!(col.delete_first(&txn, &empty_query).unwrap(), None);
+        txn.commit().unwrap();
+
+        let txn = isar.begin_txn(false).unwrap();
+        assert!(col.delete_first(&txn, &query).is_err());
+    }
+
+    #[test]
+    fn test_delete_query() {
+        isar!(isar, col => col!(field1 => Int; ind!(field1)));
+        let txn = isar.begin_txn(true).unwrap();
+
+        let mut objects = vec![];
+        let mut oids = vec![];
+        for value in &[1, 2, 3, 4, 5] {
+            let mut builder = col.get_object_builder();
+            builder.write_int(*value);
+            let object = builder.finish();
+            oids.push(col.put(&txn, None, object.as_bytes()).unwrap());
+            objects.push(object);
+        }
+
+        let mut wc = col.create_secondary_where_clause(0).unwrap();
+        wc.add_int(2, 5);
+        let mut qb = isar.create_query_builder(col);
+        qb.add_where_clause(wc, true, true);
+        let property = col.get_properties().first().unwrap();
+        qb.set_filter(Not::filter(IntBetween::filter(property, 3, 3).unwrap()));
+        let query = qb.build();
+
+        assert_eq!(col.delete_query(&txn, &query).unwrap(), 3);
+
+        assert_eq!(
+            col.debug_dump(&txn),
+            set![
+                (oids[0].as_bytes().to_vec(), objects[0].as_bytes().to_vec()),
+                (oids[2].as_bytes().to_vec(), objects[2].as_bytes().to_vec())
+            ]
+        );
+
+        let index = &col.indexes[0];
+        assert_eq!(
+            index.debug_dump(&txn),
+            set![
+                (
+                    index.debug_create_key(objects[0].as_bytes()),
+                    oids[0].as_bytes().to_vec()
+                ),
+                (
+                    index.debug_create_key(objects[2].as_bytes()),
+                    oids[2].as_bytes().to_vec()
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_requires_write_txn() {
+        isar!(isar, col => col!(field1 => Int; ind!(field1)));
+        let mut txn = isar.begin_txn(true).unwrap();
+        let oid = put_int(&txn, col, 1);
+        txn.commit().unwrap();
+
+        let txn = isar.begin_txn(false).unwrap();
+        let mut builder = col.get_object_builder();
+        builder.write_int(2);
+        let object = builder.finish();
+        assert!(matches!(
+            col.put(&txn, None, object.as_bytes()),
+            Err(IsarError::WriteTxnRequired {})
+        ));
+        assert!(matches!(
+            col.put(&txn, Some(oid), object.as_bytes()),
+            Err(IsarError::WriteTxnRequired {})
+        ));
+        assert!(matches!(
+            col.delete(&txn, oid),
+            Err(IsarError::WriteTxnRequired {})
+        ));
+        let query = isar.create_query_builder(col).build();
+        assert!(matches!(
+            col.delete_query(&txn, &query),
+            Err(IsarError::WriteTxnRequired {})
+        ));
+        assert!(col.get(&txn, oid).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_import_json() {
+        isar!(isar, col => col!(f1 => Byte, f2 => Int, f3 => Double, f4 => String, f5 => IntList; ind!(f2)));
+        let txn = isar.begin_txn(true).unwrap();
+
+        let mut builder = col.get_object_builder();
+        builder.write_byte(123);
+        builder.write_int(-555);
+        builder.write_double(1.25);
+        builder.write_string(Some("isar"));
+        builder.write_int_list(Some(&[1, 2, 3]));
+        let object1 = builder.finish();
+        col.put(&txn, None, object1.as_bytes()).unwrap();
+
+        let mut builder = col.get_object_builder();
+        builder.write_null();
+        builder.write_null();
+        builder.write_null();
+        builder.write_null();
+        builder.write_null();
+        let object2 = builder.finish();
+        col.put(&txn, None, object2.as_bytes()).unwrap();
+
+        let dump = col.debug_dump(&txn);
+        let index_dump = col.indexes[0].debug_dump(&txn);
+        let json = col.export_json(&txn, true, None).unwrap();
+        col.delete_all(&txn).unwrap();
+
+        let oids = col.import_json(&txn, &json, false, None).unwrap();
+        assert_eq!(oids.len(), 2);
+        assert_eq!(col.debug_dump(&txn), dump);
+        assert_eq!(col.indexes[0].debug_dump(&txn), index_dump);
+
+        let new_oids = col.import_json(&txn, &json, true, None).unwrap();
+        assert!(new_oids.iter().all(|oid| !oids.contains(oid)));
+        assert_eq!(col.debug_dump(&txn).len(), 4);
+    }
+
+    #[test]
+    fn test_export_json_long_list() {
+        isar!(isar, col => col!(f1 => LongList));
+        let txn = isar.begin_txn(true).unwrap();
+
+        let mut builder = col.get_object_builder();
+        builder.write_long_list(Some(&[1, -2, i64::MAX, i64::MIN]));
+        let object = builder.finish();
+        let oid = col.put(&txn, None, object.as_bytes()).unwrap();
+
+        let json = col.export_json(&txn, true, None).unwrap();
+        assert_eq!(
+            json,
+            json!([{"id": oid.to_string(), "f1": [1, -2, i64::MAX, i64::MIN]}])
+        );
+
+        col.delete_all(&txn).unwrap();
+        col.import_json(&txn, &json, false, None).unwrap();
+        assert_eq!(
+            col.debug_dump(&txn),
+            set![(oid.as_bytes().to_vec(), object.as_bytes().to_vec())]
+        );
+    }
+
+    #[test]
+    fn test_import_export_json_null_strings() {
+        isar!(isar, col => col!(f1 => String, f2 => StringList));
+        let txn = isar.begin_txn(true).unwrap();
+
+        let mut builder = col.get_object_builder();
+        builder.write_string(Some(""));
+        builder.write_string_list(Some(&[Some(""), None]));
+        let oid1 = col.put(&txn, None, builder.finish().as_bytes()).unwrap();
+        let mut builder = col.get_object_builder();
+        builder.write_string(None);
+        builder.write_string_list(None);
+        let oid2 = col.put(&txn, None, builder.finish().as_bytes()).unwrap();
+
+        let dump = col.debug_dump(&txn);
+        let json = col.export_json(&txn, true, Some("$null")).unwrap();
+        let mut expected = vec![
+            json!({"id": oid1.to_string(), "f1": "", "f2": ["", "$null"]}),
+            json!({"id": oid2.to_string(), "f1": "$null", "f2": null}),
+        ];
+        expected.sort_by_key(|o| o["id"].as_str().unwrap().to_string());
+        let mut exported = json.as_array().unwrap().clone();
+        exported.sort_by_key(|o| o["id"].as_str().unwrap().to_string());
+        assert_eq!(exported, expected);
+
+        col.delete_all(&txn).unwrap();
+        col.import_json(&txn, &json, false, Some("$null")).unwrap();
+        assert_eq!(col.debug_dump(&txn), dump);
+
+        col.delete_all(&txn).unwrap();
+        col.import_json(&txn, &json, false, None).unwrap();
+        let object = col.get(&txn, oid2).unwrap().unwrap();
+        assert_eq!(col.get_properties()[0].get_string(object), Some("$null"));
+    }
+
+    #[test]
+    fn test_export_json_null_string_collision() {
+        isar!(isar, col => col!(f1 => String, f2 => StringList));
+        let txn = isar.begin_txn(true).unwrap();
+
+        let mut builder = col.get_object_builder();
+        builder.write_string(Some("$null"));
+        builder.write_string_list(None);
+        let oid = col.put(&txn, None, builder.finish().as_bytes()).unwrap();
+        let result = col.export_json(&txn, true, Some("$null"));
+        assert!(matches!(result, Err(IsarError::IllegalArg { .. })));
+        let result = col.export_ndjson(&txn, &mut vec![], true, Some("$null"));
+        assert!(matches!(result, Err(IsarError::IllegalArg { .. })));
+        assert!(col.export_json(&txn, true, Some("$other")).is_ok());
+
+        col.delete(&txn, oid).unwrap();
+        let mut builder = col.get_object_builder();
+        builder.write_string(None);
+        builder.write_string_list(Some(&[Some("a"), Some("$null")]));
+        col.put(&txn, None, builder.finish().as_bytes()).unwrap();
+        let result = col.export_json(&txn, true, Some("$null"));
+        assert!(matches!(result, Err(IsarError::IllegalArg { .. })));
+    }
+
+    #[test]
+    fn test_export_json_corrupted() {
+        isar!(isar, col => col!(f1 => LongList));
+        let txn = isar.begin_txn(true).unwrap();
+        let mut builder = col.get_object_builder();
+        builder.write_long_list(Some(&[1, 2]));
+        let mut object = builder.finish().as_bytes().to_vec();
+        object[col.get_properties()[0].offset + 4] = 100;
+        let oid = col.get_object_id(1, 2, 3);
+        col.debug_get_db()
+            .put(txn.get_txn().unwrap(), oid.as_bytes(), &object)
+            .unwrap();
+
+        let result = col.export_json(&txn, true, None);
+        assert!(matches!(result, Err(IsarError::DbCorrupted { .. })));
+    }
+
+    #[test]
+    fn test_import_export_json_bytes() {
+        isar!(isar, col => col!(f1 => ByteList, f2 => BytesList));
+        let txn = isar.begin_txn(true).unwrap();
+
+        let mut builder = col.get_object_builder();
+        builder.write_byte_list(Some(&[0xff, 0xfe, 0, 0x80]));
+        builder.write_bytes_list(Some(&[Some(&[0xc3, 0x28]), None, Some(&[])]));
+        let object = builder.finish();
+        let oid = col.put(&txn, None, object.as_bytes()).unwrap();
+
+        let json = col.export_json(&txn, true, None).unwrap();
+        assert_eq!(
+            json,
+            json!([{
+                "id": oid.to_string(),
+                "f1": [0xff, 0xfe, 0, 0x80],
+                "f2": [[0xc3, 0x28], null, []]
+            }])
+        );
+
+        col.delete_all(&txn).unwrap();
+        col.import_json(&txn, &json, false, None).unwrap();
+        assert_eq!(
+            col.debug_dump(&txn),
+            set![(oid.as_bytes().to_vec(), object.as_bytes().to_vec())]
+        );
+    }
+
+    #[test]
+    fn test_export_ndjson() {
+        isar!(isar, col1 => col!("col1", f1 => Int, f2 => String), col2 => col!("col2", f1 => Int));
+        let txn = isar.begin_txn(true).unwrap();
+
+        let mut oids = vec![];
+        for (int, string) in &[(1, Some("a")), (2, None), (3, Some("c"))] {
+            let mut builder = col1.get_object_builder();
+            builder.write_int(*int);
+            builder.write_string(*string);
+            oids.push(col1.put(&txn, None, builder.finish().as_bytes()).unwrap());
+        }
+        put_int(&txn, col2, 4);
+
+        let mut ndjson = vec![];
+        col1.export_ndjson(&txn, &mut ndjson, true, None).unwrap();
+        let ndjson = String::from_utf8(ndjson).unwrap();
+        let lines = ndjson.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        let expected = col1.export_json(&txn, true, None).unwrap();
+        assert_eq!(expected.as_array().unwrap().len(), 3);
+        for (i, line) in lines.iter().enumerate() {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(value, expected[i]);
+        }
+        assert!(ndjson.ends_with('\n'));
+
+        let mut empty = vec![];
+        isar!(isar2, col3 => col!(f1 => Int));
+        let txn2 = isar2.begin_txn(false).unwrap();
+        col3.export_ndjson(&txn2, &mut empty, true, None).unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_import_json_invalid() {
+        isar!(isar, col => col!(f1 => Int, f2 => String));
+        let txn = isar.begin_txn(true).unwrap();
+
+        let invalid = vec![
+            json!({"f1": 1, "f2": "a"}),
+            json!([{"f1": 1, "f3": "a"}]),
+            json!([{"f1": "a", "f2": "a"}]),
+            json!([{"f1": 1, "f2": 2}]),
+            json!([{"f1": 1.5}]),
+            json!([{"f1": 1}, {"f1": i64::MAX}]),
+        ];
+        for json in invalid {
+            let result = col.import_json(&txn, &json, false, None);
+            assert!(matches!(result, Err(IsarError::IllegalArg { .. })));
+        }
+
+        for id in &[json!("abc"), json!(1)] {
+            let json = json!([{ "id": id, "f1": 1 }]);
+            let result = col.import_json(&txn, &json, false, None);
+            assert!(matches!(result, Err(IsarError::InvalidObjectId {})));
+        }
+        assert!(col.debug_dump(&txn).is_empty());
+    }
+
+    #[test]
+    fn test_clear() {
+        isar!(isar, col1 => col!(f1 => Int; ind!(f1)), col2 => col!(f2 => Int; ind!(f2)));
+
+        let txn = isar.begin_txn(true).unwrap();
+        for i in 0..100 {
+            put_int(&txn, col1, i);
+        }
+        for i in 0..50 {
+            put_int(&txn, col2, i);
+        }
+        let col2_dump = col2.debug_dump(&txn);
+        let col2_index_dump = col2.indexes[0].debug_dump(&txn);
+
+        let count = |col| isar.create_query_builder(col).build().count(&txn).unwrap();
+        assert_eq!(count(col1), 100);
+        assert_eq!(count(col2), 50);
+
+        assert_eq!(col1.clear(&txn).unwrap(), 100);
+        assert_eq!(count(col1), 0);
+        assert!(col1.indexes[0].debug_dump(&txn).is_empty());
+
+        assert_eq!(count(col2), 50);
+        assert_eq!(col2.debug_dump(&txn), col2_dump);
+        assert_eq!(col2.indexes[0].debug_dump(&txn), col2_index_dump);
+
+        assert_eq!(col1.clear(&txn).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_get_stats() {
+        isar!(isar, col1 => col!(f1 => String), col2 => col!(f2 => String));
+        let txn = isar.begin_txn(true).unwrap();
+        assert_eq!(col1.get_stats(&txn).unwrap(), CollectionStats::default());
+
+        let mut total_size = 0;
+        for len in &[0, 3, 20, 100] {
+            let mut builder = col1.get_object_builder();
+            builder.write_string(Some(&"a".repeat(*len)));
+            let object = builder.finish();
+            total_size += object.as_bytes().len() as u64;
+            col1.put(&txn, None, object.as_bytes()).unwrap();
+        }
+        let mut builder = col2.get_object_builder();
+        builder.write_string(Some("other collection"));
+        col2.put(&txn, None, builder.finish().as_bytes()).unwrap();
+
+        let stats = col1.get_stats(&txn).unwrap();
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.total_size, total_size);
+        assert_eq!(stats.average_size, total_size / 4);
+        assert_eq!(col2.get_stats(&txn).unwrap().count, 1);
+    }
+
+    #[test]
+    fn test_where_created_between() {
+        isar!(isar, col => col!(field => Int));
+        let txn = isar.begin_txn(true).unwrap();
+        for (i, time) in [0, 2, 5, 5, 9, u32::MAX].iter().enumerate() {
+            let mut builder = col.get_object_builder();
+            builder.write_int(i as i32);
+            let oid = col.get_object_id(*time, i as u32, 123);
+            col.put(&txn, Some(oid), builder.finish().as_bytes())
+                .unwrap();
+        }
+
+        let find_times = |lower: u32, upper: u32| {
+            let mut qb = isar.create_query_builder(col);
+            qb.add_where_clause(col.where_created_between(lower, upper), true, true);
+            let results = qb.build().find_all_vec(&txn).unwrap();
+            results
+                .iter()
+                .map(|(oid, _)| oid.get_time())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(find_times(2, 5), vec![2, 5, 5]);
+        assert_eq!(find_times(5, 5), vec![5, 5]);
+        assert_eq!(find_times(3, 4), Vec::<u32>::new());
+        assert_eq!(find_times(6, u32::MAX), vec![9, u32::MAX]);
+        assert_eq!(find_times(0, u32::MAX).len(), 6);
+        assert_eq!(find_times(5, 2), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_create_primary_where_clauses_for() {
+        isar!(isar, col => col!(field => Int));
+        let txn = isar.begin_txn(true).unwrap();
+        let oids = (0..6)
+            .map(|i| {
+                let mut builder = col.get_object_builder();
+                builder.write_int(i % 2);
+                let oid = col.get_object_id(i as u32, 0, 123);
+                col.put(&txn, Some(oid), builder.finish().as_bytes())
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let missing = col.get_object_id(100, 0, 123);
+
+        let selected = [oids[5], oids[0], missing, oids[2], oids[3], oids[2]];
+        let mut qb = isar.create_query_builder(col);
+        for wc in col.create_primary_where_clauses_for(&selected) {
+            qb.add_where_clause(wc, true, true);
+        }
+        let property = &col.get_properties()[0];
+        qb.add_filter(IntBetween::filter(property, 1, 1).unwrap());
+        let results = qb.build().find_all_vec(&txn).unwrap();
+        let result_oids = results.iter().map(|(oid, _)| **oid).collect::<Vec<_>>();
+        assert_eq!(result_oids, vec![oids[3], oids[5]]);
+
+        assert!(col.create_primary_where_clauses_for(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_verify_indexes() {
+        isar!(isar, col => col!(f1 => Int, f2 => Int; ind!(f1; true), ind!(f2)));
+        let txn = isar.begin_txn(true).unwrap();
+
+        let build = |f1: i32, f2: i32| {
+            let mut builder = col.get_object_builder();
+            builder.write_int(f1);
+            builder.write_int(f2);
+            builder.finish()
+        };
+        let object1 = build(1, 5);
+        let oid1 = col.put(&txn, None, object1.as_bytes()).unwrap();
+        let object2 = build(2, 5);
+        let oid2 = col.put(&txn, None, object2.as_bytes()).unwrap();
+        assert!(col.verify_indexes(&txn).unwrap().is_empty());
+
+        let lmdb_txn = txn.get_txn().unwrap();
+        let index0 = col.debug_get_index(0);
+        let key = index0.debug_create_key(object1.as_bytes());
+        index0.debug_get_db().delete(lmdb_txn, &key, None).unwrap();
+
+        let index1 = col.debug_get_index(1);
+        let key = index1.debug_create_key(build(0, 9).as_bytes());
+        index1
+            .debug_get_db()
+            .put(lmdb_txn, &key, oid2.as_bytes())
+            .unwrap();
+        let missing_oid = col.get_object_id(1, 2, 3);
+        index1
+            .debug_get_db()
+            .put(lmdb_txn, &key, missing_oid.as_bytes())
+            .unwrap();
+
+        let errors = col.verify_indexes(&txn).unwrap();
+        assert_eq!(
+            errors,
+            vec![
+                format!(
+                    "Index f1 is missing an entry for object {}.",
+                    oid1.to_string()
+                ),
+                format!(
+                    "Index f2 has an entry for missing object {}.",
+                    missing_oid.to_string()
+                ),
+                format!(
+                    "Index f2 has an entry for object {} with a wrong key.",
+                    oid2.to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_verify_indexes_corrupted() {
+        isar!(isar, col => col!(f1 => Int; ind!(f1; true)));
+        let txn = isar.begin_txn(true).unwrap();
+
+        let mut builder = col.get_object_builder();
+        builder.write_int(1);
+        let object = builder.finish();
+        let oid = col.put(&txn, None, object.as_bytes()).unwrap();
+
+        let lmdb_txn = txn.get_txn().unwrap();
+        let corrupted_oid = col.get_object_id(1, 2, 3);
+        col.debug_get_db()
+            .put(lmdb_txn, corrupted_oid.as_bytes(), &[1, 2, 3])
+            .unwrap();
+        let index = col.debug_get_index(0);
+        let key = index.debug_create_key(object.as_bytes());
+        index
+            .debug_get_db()
+            .put(lmdb_txn, &key, &[1, 2, 3])
+            .unwrap();
+
+        let errors = col.verify_indexes(&txn).unwrap();
+        assert_eq!(errors.len(), 3);
+        assert!(errors[0].starts_with(&format!(
+            "Object {} is corrupted: ",
+            corrupted_oid.to_string()
+        )));
+        assert_eq!(
+            errors[1],
+            "Index f1 has an entry with the invalid object id 010203."
+        );
+        assert_eq!(
+            errors[2],
+            format!(
+                "Index f1 is missing an entry for object {}.",
+                oid.to_string()
+            )
+        );
+    }
+
     #[test]
     fn test_delete_all() {
         isar!(isar, col1 => col!(f1 => Int; ind!(f1)), col2 => col!(f2 => Int; ind!(f2)));
@@ -383,4 +1896,102 @@ mod tests {
             ]
         );
     }
+
+    macro_rules! link_isar {
+        ($isar:ident, $col1:ident, $col2:ident) => {
+            let mut schema = col!("col1", f1 => Int);
+            schema.add_link("link", "col2").unwrap();
+            isar!($isar, $col1 => schema, $col2 => col!("col2", f2 => Int));
+        };
+    }
+
+    fn put_int(txn: &IsarTxn, col: &IsarCollection, value: i32) -> ObjectId {
+        let mut builder = col.get_object_builder();
+        builder.write_int(value);
+        let object = builder.finish();
+        col.put(txn, None, object.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_link() {
+        link_isar!(isar, col1, col2);
+        let txn = isar.begin_txn(true).unwrap();
+        let source1 = put_int(&txn, col1, 1);
+        let source2 = put_int(&txn, col1, 2);
+        let target1 = put_int(&txn, col2, 3);
+        let target2 = put_int(&txn, col2, 4);
+
+        assert!(col1.link(&txn, 0, source1, target1).unwrap());
+        assert!(col1.link(&txn, 0, source1, target2).unwrap());
+        assert!(!col1.link(&txn, 0, source1, target2).unwrap());
+        assert!(col1.link(&txn, 0, source2, target2).unwrap());
+
+        let mut links = col1.get_links(&txn, 0, source1).unwrap();
+        links.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+        let mut expected = vec![target1, target2];
+        expected.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+        assert_eq!(links, expected);
+        assert_eq!(col1.get_links(&txn, 0, source2).unwrap(), vec![target2]);
+
+        let target_object = col2.get(&txn, target2).unwrap().unwrap();
+        assert_eq!(col2.get_object_reader(target_object).read_int(), 4);
+
+        assert!(col1.unlink(&txn, 0, source1, target2).unwrap());
+        assert!(!col1.unlink(&txn, 0, source1, target2).unwrap());
+        assert_eq!(col1.get_links(&txn, 0, source1).unwrap(), vec![target1]);
+        assert_eq!(col1.get_links(&txn, 0, source2).unwrap(), vec![target2]);
+    }
+
+    #[test]
+    fn test_link_invalid() {
+        link_isar!(isar, col1, col2);
+        let txn = isar.begin_txn(true).unwrap();
+        let source = put_int(&txn, col1, 1);
+        let target = put_int(&txn, col2, 2);
+        let missing = col2.get_object_id(1, 2, 3);
+
+        assert!(col1.link(&txn, 1, source, target).is_err());
+        assert!(col1.link(&txn, 0, target, source).is_err());
+        assert!(col1.link(&txn, 0, source, source).is_err());
+        assert!(col1.link(&txn, 0, source, missing).is_err());
+        assert!(col2.get_links(&txn, 0, target).is_err());
+        assert!(col1.get_links(&txn, 0, source).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_link_missing_target_collection() {
+        let mut schema = col!("col1", f1 => Int);
+        schema.add_link("link", "col2").unwrap();
+        let mut isar_schema = crate::schema::Schema::new();
+        isar_schema.add_collection(schema).unwrap();
+
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().to_str().unwrap();
+        let result = crate::instance::IsarInstance::create(
+            path,
+            10000000,
+            126,
+            crate::instance::EnvOptions::default(),
+            isar_schema,
+        );
+        assert!(matches!(result, Err(IsarError::IllegalArg { .. })));
+    }
+
+    #[test]
+    fn test_delete_removes_links() {
+        link_isar!(isar, col1, col2);
+        let txn = isar.begin_txn(true).unwrap();
+        let source1 = put_int(&txn, col1, 1);
+        let source2 = put_int(&txn, col1, 2);
+        let target = put_int(&txn, col2, 3);
+        col1.link(&txn, 0, source1, target).unwrap();
+        col1.link(&txn, 0, source2, target).unwrap();
+
+        col1.delete(&txn, source1).unwrap();
+        assert!(col1.get_links(&txn, 0, source1).unwrap().is_empty());
+        assert_eq!(col1.get_links(&txn, 0, source2).unwrap(), vec![target]);
+
+        col1.delete_all(&txn).unwrap();
+        assert!(col1.get_links(&txn, 0, source2).unwrap().is_empty());
+    }
 }
@@ -6,6 +6,7 @@ pub struct DataDbs {
     pub primary: Db,
     pub secondary: Db,
     pub secondary_dup: Db,
+    pub links: Db,
 }
 
 impl DataDbs {
@@ -16,6 +17,7 @@ impl DataDbs {
             primary: Db::debug_new(false),
             secondary: Db::debug_new(false),
             secondary_dup: Db::debug_new(true),
+            links: Db::debug_new(true),
         }
     }
 }
@@ -12,11 +12,21 @@ pub enum IsarError {
     #[error("No such file or directory. Please make sure that the provided path is valid.")]
     PathError {},
 
+    #[error("No database exists at the provided path.")]
+    DbNotFound {},
+
     #[error("The database is full.")]
     DbFull {},
 
-    #[error("The unique index {index:?} violated.")]
-    UniqueViolated { index: String },
+    #[error("Too many concurrent read transactions.")]
+    TooManyReaders {},
+
+    #[error("The unique index {index:?} violated. The value {value} is already used by the object {existing_oid}.")]
+    UniqueViolated {
+        index: String,
+        value: String,
+        existing_oid: String,
+    },
 
     #[error("Write transaction required.")]
     WriteTxnRequired {},
@@ -24,8 +34,8 @@ pub enum IsarError {
     #[error("The ObjectId is not valid for this collection.")]
     InvalidObjectId {},
 
-    #[error("The provided object is invalid.")]
-    InvalidObject {},
+    #[error("InvalidObject: {message:?}.")]
+    InvalidObject { message: String },
 
     #[error("Transaction closed.")]
     TransactionClosed {},
@@ -33,6 +43,18 @@ pub enum IsarError {
     #[error("IllegalArg: {message:?}.")]
     IllegalArg { message: String },
 
+    #[error("The property {property:?} is not ordered by type and name.")]
+    SchemaPropertyOrder { property: String },
+
+    #[error("The property {property:?} already exists.")]
+    SchemaDuplicateProperty { property: String },
+
+    #[error("SchemaInvalidIndex: {message:?}.")]
+    SchemaInvalidIndex { message: String },
+
+    #[error("IllegalState: {message:?}.")]
+    IllegalState { message: String },
+
     #[error("DbCorrupted: {message:?}")]
     DbCorrupted {
         source: Option<Box<dyn Error>>,
@@ -45,6 +67,12 @@ pub enum IsarError {
         message: String,
     },
 
+    #[error("IoError: {message:?}")]
+    IoError {
+        source: Option<Box<dyn Error>>,
+        message: String,
+    },
+
     #[error("LmdbError ({code:?}): {message:?}")]
     LmdbError { code: i32, message: String },
 }
@@ -55,6 +83,7 @@ impl From<LmdbError> for IsarError {
     fn from(e: LmdbError) -> Self {
         match e {
             LmdbError::MapFull {} => IsarError::DbFull {},
+            LmdbError::ReadersFull {} => IsarError::TooManyReaders {},
             LmdbError::Other { code, message } => IsarError::LmdbError { code, message },
             _ => IsarError::LmdbError {
                 code: e.to_err_code(),
@@ -69,3 +98,15 @@ pub fn illegal_arg<T>(msg: &str) -> Result<T> {
         message: msg.to_string(),
     })
 }
+
+pub fn invalid_index<T>(msg: &str) -> Result<T> {
+    Err(IsarError::SchemaInvalidIndex {
+        message: msg.to_string(),
+    })
+}
+
+pub fn invalid_object<T>(msg: &str) -> Result<T> {
+    Err(IsarError::InvalidObject {
+        message: msg.to_string(),
+    })
+}
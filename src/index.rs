@@ -1,10 +1,13 @@
-use crate::error::{IsarError, Result};
+use crate::error::{illegal_arg, IsarError, Result};
+use crate::lmdb::cursor::{Cursor, PrefixIterator};
 use crate::lmdb::db::Db;
 use crate::lmdb::txn::Txn;
 use crate::object::data_type::DataType;
+use crate::object::object_id::ObjectId;
+use crate::object::object_info::ObjectInfo;
 use crate::object::property::Property;
+use crate::object::property_value::PropertyValue;
 use crate::query::where_clause::WhereClause;
-use std::mem::transmute;
 use wyhash::wyhash;
 
 use itertools::Itertools;
@@ -19,7 +22,7 @@ Null values are always considered the "smallest" element.
 
  */
 
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum IndexType {
     Primary,
     Secondary,
@@ -32,6 +35,8 @@ pub struct Index {
     properties: Vec<Property>,
     index_type: IndexType,
     hash_value: bool,
+    multi_entry: bool,
+    case_sensitive: bool,
     db: Db,
 }
 
@@ -41,61 +46,299 @@ impl Index {
         properties: Vec<Property>,
         index_type: IndexType,
         hash_value: bool,
+        multi_entry: bool,
+        case_sensitive: bool,
         db: Db,
     ) -> Self {
-        assert!(index_type == IndexType::Secondary || index_type == IndexType::SecondaryDup);
+        assert!(index_type != IndexType::Primary || (properties.is_empty() && !multi_entry));
+        assert!(!multi_entry || properties.len() == 1);
         Index {
             prefix: u16::to_le_bytes(id),
             properties,
             index_type,
             hash_value,
+            multi_entry,
+            case_sensitive,
             db,
         }
     }
 
+    /// The primary index of a collection. Its keys are the ObjectIds and its values the objects.
+    pub(crate) fn primary(collection_id: u16, db: Db) -> Self {
+        Index::new(
+            collection_id,
+            vec![],
+            IndexType::Primary,
+            false,
+            false,
+            true,
+            db,
+        )
+    }
+
     pub(crate) fn get_id(&self) -> u16 {
         u16::from_le_bytes(self.prefix)
     }
 
     pub(crate) fn create_for_object(&self, txn: &Txn, key: &[u8], object: &[u8]) -> Result<()> {
-        let index_key = self.create_key(object);
-        if self.index_type == IndexType::SecondaryDup {
-            self.db.put(txn, &index_key, key)
-        } else {
-            let success = self.db.put_no_override(txn, &index_key, key)?;
-            if success {
-                Ok(())
+        for index_key in self.create_keys(object)? {
+            if self.index_type == IndexType::SecondaryDup {
+                self.db.put(txn, &index_key, key)?;
             } else {
-                Err(IsarError::UniqueViolated {
-                    index: self.properties.iter().map(|p| &p.name).join(" | "),
-                })
+                let success = self.db.put_no_override(txn, &index_key, key)?;
+                if !success {
+                    let existing_oid =
+                        self.db
+                            .get(txn, &index_key)?
+                            .ok_or_else(|| IsarError::DbCorrupted {
+                                source: None,
+                                message: "The existing unique index entry could not be read."
+                                    .to_string(),
+                            })?;
+                    return Err(IsarError::UniqueViolated {
+                        index: self.properties.iter().map(|p| &p.name).join(" | "),
+                        value: self.format_key_value(object, &index_key),
+                        existing_oid: ObjectId::format_bytes(existing_oid),
+                    });
+                }
             }
         }
+        Ok(())
+    }
+
+    /// Formats the value of `object` that `index_key` was created from. For multi-entry indexes
+    /// this is the list element of the key.
+    fn format_key_value(&self, object: &[u8], index_key: &[u8]) -> String {
+        if self.multi_entry {
+            self.create_element_entries(object)
+                .unwrap_or_default()
+                .into_iter()
+                .find(|(key, _)| key == index_key)
+                .map_or_else(String::new, |(_, value)| value.to_string())
+        } else {
+            self.properties
+                .iter()
+                .map(|p| {
+                    if p.is_null(object) {
+                        PropertyValue::Null
+                    } else {
+                        PropertyValue::read(p, object)
+                    }
+                })
+                .join(" | ")
+        }
     }
 
     pub(crate) fn delete_for_object(&self, txn: &Txn, key: &[u8], object: &[u8]) -> Result<()> {
-        let index_key = self.create_key(object);
+        for index_key in self.create_keys(object)? {
+            if self.index_type == IndexType::SecondaryDup {
+                self.db.delete(txn, &index_key, Some(key))?;
+            } else {
+                self.db.delete(txn, &index_key, None)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Looks up the primary key of the object with the same index key as `object`. Only
+    /// supported for unique indexes that are not multi-entry.
+    pub(crate) fn get_unique<'txn>(
+        &self,
+        txn: &'txn Txn,
+        object: &[u8],
+    ) -> Result<Option<&'txn [u8]>> {
+        if self.index_type != IndexType::Secondary {
+            illegal_arg("The index is not unique.")
+        } else if self.multi_entry {
+            illegal_arg("Multi-entry indexes are not supported.")
+        } else {
+            self.db.get(txn, &self.create_key(object)?)
+        }
+    }
+
+    /// Compares the entries of this index with the keys created from the objects of `primary`
+    /// and returns a description of every inconsistency. Objects that fail verification are
+    /// skipped, the caller reports them.
+    pub(crate) fn verify(
+        &self,
+        txn: &Txn,
+        primary: &Index,
+        object_info: &ObjectInfo,
+    ) -> Result<Vec<String>> {
+        let name = self.properties.iter().map(|p| &p.name).join(" | ");
+        let mut errors = vec![];
+        self.iter_entries(txn, |key, oid| {
+            if oid.len() != ObjectId::get_size() {
+                errors.push(format!(
+                    "Index {} has an entry with the invalid object id {}.",
+                    name,
+                    hex::encode(oid)
+                ));
+                return Ok(true);
+            }
+            let oid_str = ObjectId::from_bytes(oid).to_string();
+            if let Some(object) = primary.db.get(txn, oid)? {
+                if object_info.verify_stored_object(object).is_ok()
+                    && !self.create_keys(object)?.iter().any(|k| k == key)
+                {
+                    errors.push(format!(
+                        "Index {} has an entry for object {} with a wrong key.",
+                        name, oid_str
+                    ));
+                }
+            } else {
+                errors.push(format!(
+                    "Index {} has an entry for missing object {}.",
+                    name, oid_str
+                ));
+            }
+            Ok(true)
+        })?;
+
+        primary.iter_entries(txn, |oid, object| {
+            if object_info.verify_stored_object(object).is_err() {
+                return Ok(true);
+            }
+            for key in self.create_keys(object)? {
+                if !self.contains_entry(txn, &key, oid)? {
+                    errors.push(format!(
+                        "Index {} is missing an entry for object {}.",
+                        name,
+                        ObjectId::format_bytes(oid)
+                    ));
+                }
+            }
+            Ok(true)
+        })?;
+        Ok(errors)
+    }
+
+    fn contains_entry(&self, txn: &Txn, key: &[u8], oid: &[u8]) -> Result<bool> {
         if self.index_type == IndexType::SecondaryDup {
-            self.db.delete(txn, &index_key, Some(key))
+            let mut cursor = self.db.cursor(txn)?;
+            let mut entry = cursor.move_to(key)?;
+            while let Some((_, val)) = entry {
+                if val == oid {
+                    return Ok(true);
+                }
+                entry = cursor.move_to_next_dup()?;
+            }
+            Ok(false)
         } else {
-            self.db.delete(txn, &index_key, None)
+            Ok(self.db.get(txn, key)? == Some(oid))
+        }
+    }
+
+    /// Calls `callback` for every entry of this index in key order until it returns `false`.
+    pub(crate) fn iter_entries<'txn, F>(&self, txn: &'txn Txn, mut callback: F) -> Result<()>
+    where
+        F: FnMut(&'txn [u8], &'txn [u8]) -> Result<bool>,
+    {
+        for entry in self.iter(txn)? {
+            let (key, val) = entry?;
+            if !callback(key, val)? {
+                break;
+            }
         }
+        Ok(())
     }
 
-    pub fn clear(&self, txn: &Txn) -> Result<()> {
+    pub(crate) fn iter<'a, 'txn>(
+        &'a self,
+        txn: &'txn Txn,
+    ) -> Result<PrefixIterator<'a, 'txn, Cursor<'txn>>> {
+        Ok(self.db.cursor(txn)?.into_iter_prefix(&self.prefix))
+    }
+
+    /// Deletes all entries of this index and returns how many were deleted.
+    pub fn clear(&self, txn: &Txn) -> Result<usize> {
         self.db.delete_key_prefix(txn, &self.prefix)
     }
 
     pub fn create_where_clause(&self) -> WhereClause {
-        WhereClause::new(&self.prefix, self.index_type)
+        WhereClause::new(
+            &self.prefix,
+            self.index_type,
+            self.case_sensitive,
+            self.multi_entry,
+        )
+    }
+
+    /// Creates the keys of `object`. Invalid lists or strings are reported as `DbCorrupted`.
+    fn create_keys(&self, object: &[u8]) -> Result<Vec<Vec<u8>>> {
+        if self.multi_entry {
+            self.create_element_keys(object)
+        } else {
+            Ok(vec![self.create_key(object)?])
+        }
     }
 
-    fn create_key(&self, object: &[u8]) -> Vec<u8> {
+    /// Creates one key for each distinct element of the list property.
+    fn create_element_keys(&self, object: &[u8]) -> Result<Vec<Vec<u8>>> {
+        let keys = self
+            .create_element_entries(object)?
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        Ok(keys)
+    }
+
+    /// Creates the key of each distinct element of the list property together with the element.
+    fn create_element_entries<'a>(
+        &self,
+        object: &'a [u8],
+    ) -> Result<Vec<(Vec<u8>, PropertyValue<'a>)>> {
+        let property = self.properties.first().unwrap();
+        let element_entries = match property.data_type {
+            DataType::ByteList => Self::map_list(property.try_get_byte_list(object)?, |v| {
+                (Self::get_byte_key(*v), PropertyValue::Byte(*v))
+            }),
+            DataType::IntList => Self::map_list(property.try_get_int_list(object)?, |v| {
+                (Self::get_int_key(*v), PropertyValue::Int(*v))
+            }),
+            DataType::FloatList => Self::map_list(property.try_get_float_list(object)?, |v| {
+                (Self::get_float_key(*v), PropertyValue::Float(*v))
+            }),
+            DataType::LongList => Self::map_list(property.try_get_long_list(object)?, |v| {
+                (Self::get_long_key(*v), PropertyValue::Long(*v))
+            }),
+            DataType::DoubleList => Self::map_list(property.try_get_double_list(object)?, |v| {
+                (Self::get_double_key(*v), PropertyValue::Double(*v))
+            }),
+            DataType::StringList => {
+                let list = property.try_get_string_list(object)?;
+                Self::map_list(list.as_deref(), |v| {
+                    let key = if self.hash_value {
+                        Self::get_string_hash_key(*v, self.case_sensitive)
+                    } else {
+                        Self::get_string_value_key(*v, self.case_sensitive)
+                    };
+                    (key, v.map_or(PropertyValue::Null, PropertyValue::String))
+                })
+            }
+            _ => unreachable!(),
+        };
+        let entries = element_entries
+            .into_iter()
+            .map(|(element_key, value)| {
+                let mut key = self.prefix.to_vec();
+                key.extend_from_slice(&element_key);
+                (key, value)
+            })
+            .sorted_by(|(key1, _), (key2, _)| key1.cmp(key2))
+            .dedup_by(|(key1, _), (key2, _)| key1 == key2)
+            .collect();
+        Ok(entries)
+    }
+
+    fn map_list<T, R>(list: Option<&[T]>, get_entry: impl Fn(&T) -> R) -> Vec<R> {
+        list.map_or(vec![], |list| list.iter().map(get_entry).collect())
+    }
+
+    fn create_key(&self, object: &[u8]) -> Result<Vec<u8>> {
         let mut bytes = self.prefix.to_vec();
-        let index_iter = self
-            .properties
-            .iter()
-            .flat_map(|property| match property.data_type {
+        for property in &self.properties {
+            let key = match property.data_type {
                 DataType::Byte => {
                     let value = property.get_byte(object);
                     Self::get_byte_key(value)
@@ -117,27 +360,26 @@ impl Index {
                     Self::get_double_key(value)
                 }
                 DataType::String => {
-                    let value = property.get_string(object);
+                    let value = property.try_get_string(object)?;
                     if self.hash_value {
-                        Self::get_string_hash_key(value)
+                        Self::get_string_hash_key(value, self.case_sensitive)
                     } else {
-                        Self::get_string_value_key(value)
+                        Self::get_string_value_key(value, self.case_sensitive)
                     }
                 }
                 _ => unimplemented!(),
-            });
-        bytes.extend(index_iter);
-        bytes
+            };
+            bytes.extend(key);
+        }
+        Ok(bytes)
     }
 
     pub fn get_int_key(value: i32) -> Vec<u8> {
-        let unsigned = unsafe { transmute::<i32, u32>(value) };
-        u32::to_be_bytes(unsigned ^ 1 << 31).to_vec()
+        u32::to_be_bytes(value as u32 ^ 1 << 31).to_vec()
     }
 
     pub fn get_long_key(value: i64) -> Vec<u8> {
-        let unsigned = unsafe { transmute::<i64, u64>(value) };
-        u64::to_be_bytes(unsigned ^ 1 << 63).to_vec()
+        u64::to_be_bytes(value as u64 ^ 1 << 63).to_vec()
     }
 
     pub fn get_float_key(value: f32) -> Vec<u8> {
@@ -170,18 +412,38 @@ impl Index {
         vec![value]
     }
 
-    pub fn get_string_hash_key(value: Option<&str>) -> Vec<u8> {
+    /// Null is stored as the smallest bool value so bool keys are ordered null < false < true.
+    pub fn get_bool_key(value: Option<bool>) -> Vec<u8> {
+        let byte = match value {
+            None => 0,
+            Some(false) => 1,
+            Some(true) => 2,
+        };
+        vec![byte]
+    }
+
+    pub fn get_string_hash_key(value: Option<&str>, case_sensitive: bool) -> Vec<u8> {
         let hash = if let Some(value) = value {
-            wyhash(value.as_bytes(), 0)
+            if case_sensitive {
+                wyhash(value.as_bytes(), 0)
+            } else {
+                wyhash(value.to_lowercase().as_bytes(), 0)
+            }
         } else {
             0
         };
         u64::to_be_bytes(hash).to_vec()
     }
 
-    pub fn get_string_value_key(value: Option<&str>) -> Vec<u8> {
+    pub fn get_string_value_key(value: Option<&str>, case_sensitive: bool) -> Vec<u8> {
         if let Some(value) = value {
-            let value = value.as_bytes();
+            let lowercase;
+            let value = if case_sensitive {
+                value.as_bytes()
+            } else {
+                lowercase = value.to_lowercase();
+                lowercase.as_bytes()
+            };
             let mut bytes = vec![1];
             if value.len() >= MAX_STRING_INDEX_SIZE {
                 bytes.extend_from_slice(&value[0..MAX_STRING_INDEX_SIZE]);
@@ -208,7 +470,7 @@ impl Index {
 
     #[cfg(test)]
     pub fn debug_create_key(&self, object: &[u8]) -> Vec<u8> {
-        self.create_key(object).to_vec()
+        self.create_key(object).unwrap()
     }
 
     #[cfg(test)]
@@ -239,7 +501,7 @@ mod tests {
 
                 assert_eq!(
                     index.debug_dump(&txn),
-                    set![(index.create_key(obj.as_bytes()), oid.as_bytes().to_vec())]
+                    set![(index.create_key(obj.as_bytes()).unwrap(), oid.as_bytes().to_vec())]
                 )
             };
         );
@@ -264,29 +526,196 @@ mod tests {
         o.write_int(5);
         let bytes = o.finish();
 
-        col.put(&txn, None, bytes.as_bytes()).unwrap();
+        let oid = col.put(&txn, None, bytes.as_bytes()).unwrap();
 
         let result = col.put(&txn, None, bytes.as_bytes());
         match result {
-            Err(IsarError::UniqueViolated { .. }) => {}
+            Err(IsarError::UniqueViolated {
+                index,
+                value,
+                existing_oid,
+            }) => {
+                assert_eq!(index, "field");
+                assert_eq!(value, "5");
+                assert_eq!(existing_oid, oid.to_string());
+            }
             _ => panic!("wrong error"),
         };
     }
 
+    #[test]
+    fn test_create_for_object_case_insensitive() {
+        fn put_strings(hash_value: bool, case_sensitive: bool) -> Result<()> {
+            isar!(isar, col => col!(field => String; ind!(field; true, hash_value, false, case_sensitive)));
+            let txn = isar.begin_txn(true).unwrap();
+            for value in &["Hello", "hello"] {
+                let mut builder = col.get_object_builder();
+                builder.write_string(Some(value));
+                col.put(&txn, None, builder.finish().as_bytes())?;
+            }
+            Ok(())
+        }
+
+        assert!(put_strings(false, true).is_ok());
+        assert!(put_strings(true, true).is_ok());
+        assert!(matches!(
+            put_strings(false, false),
+            Err(IsarError::UniqueViolated { .. })
+        ));
+        assert!(matches!(
+            put_strings(true, false),
+            Err(IsarError::UniqueViolated { .. })
+        ));
+    }
+
+    #[test]
+    fn test_where_clause_case_insensitive() {
+        isar!(isar, col => col!(field => String; ind!(field; false, false, false, false)));
+        let txn = isar.begin_txn(true).unwrap();
+        for value in &["Hello", "hello", "HELLO!"] {
+            let mut builder = col.get_object_builder();
+            builder.write_string(Some(value));
+            col.put(&txn, None, builder.finish().as_bytes()).unwrap();
+        }
+
+        let mut wc = col.create_secondary_where_clause(0).unwrap();
+        wc.add_string_value(Some("hELLO"), Some("HeLlO"));
+        let mut qb = isar.create_query_builder(col);
+        qb.add_where_clause(wc, true, true);
+        assert_eq!(qb.build().count(&txn).unwrap(), 2);
+    }
+
     #[test]
     fn test_create_for_object_compound() {}
 
     #[test]
     fn test_create_for_object_string() {}
 
+    #[test]
+    fn test_create_for_object_multi_entry() {
+        isar!(isar, col => col!(field => IntList; ind!(field; false, false, true)));
+        let txn = isar.begin_txn(true).unwrap();
+
+        let mut builder = col.get_object_builder();
+        builder.write_int_list(Some(&[3, 1, 3, 2]));
+        let obj = builder.finish();
+        let oid = col.put(&txn, None, obj.as_bytes()).unwrap();
+
+        let mut builder = col.get_object_builder();
+        builder.write_int_list(Some(&[2]));
+        let obj2 = builder.finish();
+        let oid2 = col.put(&txn, None, obj2.as_bytes()).unwrap();
+
+        let mut builder = col.get_object_builder();
+        builder.write_int_list(None);
+        col.put(&txn, None, builder.finish().as_bytes()).unwrap();
+
+        let index = col.debug_get_index(0);
+        let key = |value: i32| {
+            let mut key = index.prefix.to_vec();
+            key.extend_from_slice(&Index::get_int_key(value));
+            key
+        };
+        assert_eq!(
+            index.debug_dump(&txn),
+            set![
+                (key(1), oid.as_bytes().to_vec()),
+                (key(2), oid.as_bytes().to_vec()),
+                (key(3), oid.as_bytes().to_vec()),
+                (key(2), oid2.as_bytes().to_vec())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_create_for_object_multi_entry_unique() {
+        isar!(isar, col => col!(field => IntList; ind!(field; true, false, true)));
+        let txn = isar.begin_txn(true).unwrap();
+
+        let mut builder = col.get_object_builder();
+        builder.write_int_list(Some(&[1, 2, 2]));
+        let oid = col.put(&txn, None, builder.finish().as_bytes()).unwrap();
+
+        let mut builder = col.get_object_builder();
+        builder.write_int_list(Some(&[3, 2]));
+        let result = col.put(&txn, None, builder.finish().as_bytes());
+        match result {
+            Err(IsarError::UniqueViolated {
+                value,
+                existing_oid,
+                ..
+            }) => {
+                assert_eq!(value, "2");
+                assert_eq!(existing_oid, oid.to_string());
+            }
+            _ => panic!("wrong error"),
+        };
+    }
+
     #[test]
     fn test_delete_for_object() {}
 
+    #[test]
+    fn test_delete_for_object_multi_entry() {
+        isar!(isar, col => col!(field => IntList; ind!(field; false, false, true)));
+        let txn = isar.begin_txn(true).unwrap();
+
+        let mut builder = col.get_object_builder();
+        builder.write_int_list(Some(&[1, 2, 2]));
+        let oid = col.put(&txn, None, builder.finish().as_bytes()).unwrap();
+
+        let mut builder = col.get_object_builder();
+        builder.write_int_list(Some(&[2, 3]));
+        let oid2 = col.put(&txn, None, builder.finish().as_bytes()).unwrap();
+
+        col.delete(&txn, oid).unwrap();
+
+        let index = col.debug_get_index(0);
+        let key = |value: i32| {
+            let mut key = index.prefix.to_vec();
+            key.extend_from_slice(&Index::get_int_key(value));
+            key
+        };
+        assert_eq!(
+            index.debug_dump(&txn),
+            set![
+                (key(2), oid2.as_bytes().to_vec()),
+                (key(3), oid2.as_bytes().to_vec())
+            ]
+        );
+
+        col.delete(&txn, oid2).unwrap();
+        assert!(index.debug_dump(&txn).is_empty());
+    }
+
     #[test]
     fn test_clear() {}
 
     #[test]
-    fn test_create_key() {}
+    fn test_create_key() {
+        isar!(isar, col => col!(field => Byte; ind!(field)));
+        let index = col.debug_get_index(0);
+        let prefix = index.prefix.to_vec();
+
+        for value in &[Property::NULL_BYTE, 123, u8::MAX] {
+            let mut builder = col.get_object_builder();
+            builder.write_byte(*value);
+            let obj = builder.finish();
+
+            let mut expected = prefix.clone();
+            expected.extend_from_slice(&Index::get_byte_key(*value));
+            assert_eq!(index.create_key(obj.as_bytes()).unwrap(), expected);
+        }
+
+        let mut builder = col.get_object_builder();
+        builder.write_null();
+        let obj = builder.finish();
+        assert!(col.get_properties()[0].is_null(obj.as_bytes()));
+        assert_eq!(
+            index.create_key(obj.as_bytes()).unwrap(),
+            [&prefix[..], &[0]].concat()
+        );
+    }
 
     #[test]
     fn test_create_int_key() {
@@ -373,6 +802,42 @@ mod tests {
         assert_eq!(Index::get_byte_key(255), vec![255]);
     }
 
+    #[test]
+    fn test_get_bool_index_key() {
+        for value in &[None, Some(false), Some(true)] {
+            assert_eq!(
+                Index::get_bool_key(*value),
+                Index::get_byte_key(Property::bool_to_byte(*value))
+            );
+        }
+        assert!(Index::get_bool_key(None) < Index::get_bool_key(Some(false)));
+        assert!(Index::get_bool_key(Some(false)) < Index::get_bool_key(Some(true)));
+    }
+
+    #[test]
+    fn test_get_string_key_case_insensitive() {
+        assert_ne!(
+            Index::get_string_value_key(Some("Hello"), true),
+            Index::get_string_value_key(Some("hello"), true)
+        );
+        assert_eq!(
+            Index::get_string_value_key(Some("Hello"), false),
+            Index::get_string_value_key(Some("hello"), true)
+        );
+        assert_ne!(
+            Index::get_string_hash_key(Some("Hello"), true),
+            Index::get_string_hash_key(Some("hello"), true)
+        );
+        assert_eq!(
+            Index::get_string_hash_key(Some("Hello"), false),
+            Index::get_string_hash_key(Some("hello"), true)
+        );
+        assert_eq!(
+            Index::get_string_value_key(None, false),
+            Index::get_string_value_key(None, true)
+        );
+    }
+
     #[test]
     fn test_get_string_hash_key() {
         let long_str = (0..1500).map(|_| "a").collect::<String>();
@@ -390,7 +855,7 @@ mod tests {
             (&long_str[..], vec![107, 96, 243, 122, 159, 148, 180, 244]),
         ];
         for (str, hash) in pairs {
-            assert_eq!(hash, Index::get_string_hash_key(Some(str)));
+            assert_eq!(hash, Index::get_string_hash_key(Some(str), true));
         }
     }
 
@@ -407,7 +872,7 @@ mod tests {
             (Some("hello"), hello_bytes),
         ];
         for (str, hash) in pairs {
-            assert_eq!(hash, Index::get_string_value_key(str));
+            assert_eq!(hash, Index::get_string_value_key(str, true));
         }
     }
 }
@@ -8,25 +8,59 @@ use crate::schema::schema_manager::SchemaManger;
 use crate::schema::Schema;
 use crate::txn::IsarTxn;
 
+pub use crate::lmdb::env::EnvOptions;
+
+/// A database at a single path. Instances at different paths do not share any state and can be
+/// used concurrently from any thread. Within one instance, write transactions are serialized
+/// while read transactions run in parallel. Opening the same path twice in one process is not
+/// supported.
 pub struct IsarInstance {
     env: Env,
     dbs: DataDbs,
     collections: Vec<IsarCollection>,
+    read_only: bool,
+    nested_txns: bool,
 }
 
 impl IsarInstance {
-    pub fn create(path: &str, max_size: usize, schema: Schema) -> Result<Self> {
-        let env = Env::create(path, 4, max_size)?;
+    /// Opens or creates the database at `path`. At most `max_readers` read transactions can be
+    /// open at the same time. Additional read transactions fail with `TooManyReaders`.
+    /// `options` can trade durability for write performance. Use `EnvOptions::default()` to
+    /// flush every commit to disk.
+    pub fn create(
+        path: &str,
+        max_size: usize,
+        max_readers: u32,
+        options: EnvOptions,
+        schema: Schema,
+    ) -> Result<Self> {
+        let env = Env::create(path, 5, max_size, max_readers, options)?;
+        // stale readers of crashed processes prevent reusing free pages
+        env.check_readers()?;
         let dbs = IsarInstance::open_databases(&env)?;
+        IsarInstance::load(env, dbs, schema, false)
+    }
+
+    /// Opens an existing database without write access. The schema has to match the existing
+    /// schema because read-only instances cannot be migrated.
+    pub fn open(path: &str, max_size: usize, max_readers: u32, schema: Schema) -> Result<Self> {
+        let env = Env::open_read_only(path, 5, max_size, max_readers)?;
+        let dbs = IsarInstance::open_existing_databases(&env)?;
+        IsarInstance::load(env, dbs, schema, true)
+    }
 
-        let manager = SchemaManger::new(&env, dbs);
+    fn load(env: Env, dbs: DataDbs, schema: Schema, read_only: bool) -> Result<Self> {
+        let manager = SchemaManger::new(&env, dbs, read_only);
         manager.check_isar_version()?;
         let collections = manager.get_collections(schema)?;
+        let nested_txns = env.supports_nested_txns()?;
 
         Ok(IsarInstance {
             env,
             dbs,
             collections,
+            read_only,
+            nested_txns,
         })
     }
 
@@ -36,18 +70,70 @@ impl IsarInstance {
         let primary = Db::open(&txn, "data", false, false)?;
         let secondary = Db::open(&txn, "index", false, true)?;
         let secondary_dup = Db::open(&txn, "index_dup", true, true)?;
+        let links = Db::open(&txn, "links", true, true)?;
+        txn.commit()?;
+        Ok(DataDbs {
+            info,
+            primary,
+            secondary,
+            secondary_dup,
+            links,
+        })
+    }
+
+    fn open_existing_databases(env: &Env) -> Result<DataDbs> {
+        let txn = env.txn(false)?;
+        let info = Db::open_existing(&txn, "info", false, false)?;
+        let primary = Db::open_existing(&txn, "data", false, false)?;
+        let secondary = Db::open_existing(&txn, "index", false, true)?;
+        let secondary_dup = Db::open_existing(&txn, "index_dup", true, true)?;
+        let links = Db::open_existing(&txn, "links", true, true)?;
         txn.commit()?;
         Ok(DataDbs {
             info,
             primary,
             secondary,
             secondary_dup,
+            links,
         })
     }
 
     #[inline]
     pub fn begin_txn(&self, write: bool) -> Result<IsarTxn> {
-        Ok(IsarTxn::new(self.env.txn(write)?, write))
+        if write && self.read_only {
+            return illegal_arg("Write transactions are not allowed on read-only instances.");
+        }
+        Ok(IsarTxn::new(self.env.txn(write)?, write, self.nested_txns))
+    }
+
+    /// Increases the maximum size of the database. Fails if `new_size` is not larger than the
+    /// current maximum size or if any transaction is open.
+    pub fn increase_size(&self, new_size: usize) -> Result<()> {
+        if new_size <= self.env.stat()?.map_size {
+            return illegal_arg("The new size has to be larger than the current size.");
+        }
+        self.env.set_map_size(new_size)
+    }
+
+    /// Returns the number of bytes used by the database.
+    pub fn get_size_bytes(&self) -> Result<u64> {
+        let stat = self.env.stat()?;
+        Ok(stat.used_pages as u64 * stat.page_size as u64)
+    }
+
+    /// Writes a compacted copy of the database to `target_path` which has to be an existing,
+    /// empty directory. Space of deleted objects is not copied.
+    pub fn compact(&self, target_path: &str) -> Result<()> {
+        self.env.copy_compact(target_path)
+    }
+
+    /// Flushes committed transactions to disk. With `force` the flush is synchronous even if
+    /// the instance was created with [`EnvOptions::no_sync`].
+    pub fn sync(&self, force: bool) -> Result<()> {
+        if self.read_only {
+            return illegal_arg("Read-only instances cannot be synced.");
+        }
+        self.env.sync(force)
     }
 
     pub fn get_collection(&self, collection_index: usize) -> Option<&IsarCollection> {
@@ -60,6 +146,10 @@ impl IsarInstance {
             .find(|c| c.get_name() == collection_name)
     }
 
+    pub fn get_collection_names(&self) -> Vec<&str> {
+        self.collections.iter().map(|c| c.get_name()).collect()
+    }
+
     pub fn create_query_builder<'col>(
         &self,
         collection: &'col IsarCollection,
@@ -72,7 +162,17 @@ impl IsarInstance {
         )
     }
 
-    pub fn close(self) {}
+    /// Flushes committed transactions to disk and closes the instance. Transactions borrow the
+    /// instance so it cannot be closed while one of them is still open.
+    pub fn close(self) -> Result<()> {
+        let result = if self.read_only {
+            Ok(())
+        } else {
+            self.env.sync(true)
+        };
+        drop(self.env);
+        result
+    }
 
     #[cfg(test)]
     pub fn debug_get_primary_db(&self) -> Db {
@@ -92,7 +192,15 @@ impl IsarInstance {
 
 #[cfg(test)]
 mod tests {
-    use crate::{col, isar};
+    use crate::collection::IsarCollection;
+    use crate::error::IsarError;
+    use crate::instance::{EnvOptions, IsarInstance};
+    use crate::object::data_type::DataType;
+    use crate::object::property::Property;
+    use crate::schema::Schema;
+    use crate::{col, ind, isar};
+    use std::sync::Barrier;
+    use std::thread;
     use tempfile::tempdir;
 
     #[test]
@@ -103,15 +211,629 @@ mod tests {
         ob.write_int(123);
         let o = ob.finish();
 
-        let txn = isar.begin_txn(true).unwrap();
+        let mut txn = isar.begin_txn(true).unwrap();
         let oid = col.put(&txn, None, o.as_bytes()).unwrap();
         txn.commit().unwrap();
 
-        let txn = isar.begin_txn(false).unwrap();
+        let mut txn = isar.begin_txn(false).unwrap();
         assert_eq!(col.get(&txn, oid).unwrap().unwrap(), o.as_bytes());
         txn.abort();
     }
 
+    #[test]
+    fn test_increase_size() {
+        let dir = tempdir().unwrap();
+        let mut schema = Schema::new();
+        schema.add_collection(col!(f1 => ByteList)).unwrap();
+        let isar = IsarInstance::create(
+            dir.path().to_str().unwrap(),
+            100000,
+            126,
+            EnvOptions::default(),
+            schema,
+        )
+        .unwrap();
+        let col = isar.get_collection(0).unwrap();
+
+        let mut ob = col.get_object_builder();
+        ob.write_byte_list(Some(&[1; 50000]));
+        let o = ob.finish();
+
+        let mut txn = isar.begin_txn(true).unwrap();
+        let mut result = Ok(());
+        for _ in 0..10 {
+            result = col.put(&txn, None, o.as_bytes()).map(|_| ());
+            if result.is_err() {
+                break;
+            }
+        }
+        assert!(matches!(result, Err(IsarError::DbFull {})));
+        assert!(matches!(
+            isar.increase_size(10000000),
+            Err(IsarError::IllegalState { .. })
+        ));
+        txn.abort();
+
+        let mut txn = isar.begin_txn(false).unwrap();
+        assert!(matches!(
+            isar.increase_size(10000000),
+            Err(IsarError::IllegalState { .. })
+        ));
+        txn.abort();
+
+        assert!(matches!(
+            isar.increase_size(50000),
+            Err(IsarError::IllegalArg { .. })
+        ));
+        isar.increase_size(10000000).unwrap();
+        assert!(matches!(
+            isar.increase_size(10000000),
+            Err(IsarError::IllegalArg { .. })
+        ));
+        let mut txn = isar.begin_txn(true).unwrap();
+        for _ in 0..10 {
+            col.put(&txn, None, o.as_bytes()).unwrap();
+        }
+        txn.commit().unwrap();
+    }
+
+    #[test]
+    fn test_get_size_bytes() {
+        isar!(isar, col => col!(f1 => Long));
+        let initial_size = isar.get_size_bytes().unwrap();
+        assert!(initial_size > 0);
+
+        let mut txn = isar.begin_txn(true).unwrap();
+        for i in 0..1000 {
+            let mut ob = col.get_object_builder();
+            ob.write_long(i);
+            col.put(&txn, None, ob.finish().as_bytes()).unwrap();
+        }
+        txn.commit().unwrap();
+
+        assert!(isar.get_size_bytes().unwrap() > initial_size);
+    }
+
+    #[test]
+    fn test_migration_keeps_other_collections() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let (oid1, oid2, object2) = {
+            isar!(path: path, isar, col1 => col!("col1", f1 => Int), col2 => col!("col2", f1 => Int));
+            let mut txn = isar.begin_txn(true).unwrap();
+            let mut ob = col1.get_object_builder();
+            ob.write_int(1);
+            let oid1 = col1.put(&txn, None, ob.finish().as_bytes()).unwrap();
+            let mut ob = col2.get_object_builder();
+            ob.write_int(2);
+            let object2 = ob.finish();
+            let oid2 = col2.put(&txn, None, object2.as_bytes()).unwrap();
+            txn.commit().unwrap();
+            (oid1, oid2, object2.as_bytes().to_vec())
+        };
+
+        isar!(path: path, isar, col1 => col!("col1", f1 => Int, f2 => Int), col2 => col!("col2", f1 => Int));
+        let txn = isar.begin_txn(false).unwrap();
+        let object1 = col1.get(&txn, oid1).unwrap().unwrap();
+        assert_eq!(col1.get_properties()[0].get_int(object1), 1);
+        assert!(col1.get_properties()[1].is_null(object1));
+        assert_eq!(col2.get(&txn, oid2).unwrap().unwrap(), &object2[..]);
+    }
+
+    #[test]
+    fn test_migration_skipped_for_unchanged_version() {
+        // the added index is only filled by a migration
+        let index_len_after_adding_index = |version: u32| {
+            let dir = tempdir().unwrap();
+            let path = dir.path().to_str().unwrap();
+            {
+                let mut schema = col!("col", f1 => Int);
+                schema.set_version(1);
+                isar!(path: path, isar, col => schema);
+                let mut ob = col.get_object_builder();
+                ob.write_int(123);
+                let mut txn = isar.begin_txn(true).unwrap();
+                col.put(&txn, None, ob.finish().as_bytes()).unwrap();
+                txn.commit().unwrap();
+            }
+
+            let mut schema = col!("col", f1 => Int; ind!(f1));
+            schema.set_version(version);
+            isar!(path: path, isar, col => schema);
+            let mut txn = isar.begin_txn(false).unwrap();
+            let len = col.debug_get_index(0).debug_dump(&txn).len();
+            txn.abort();
+            len
+        };
+        assert_eq!(index_len_after_adding_index(1), 0);
+        assert_eq!(index_len_after_adding_index(2), 1);
+    }
+
+    #[test]
+    fn test_migration_bumped_version() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let oid = {
+            let mut schema = col!("col", f1 => Int);
+            schema.set_version(1);
+            isar!(path: path, isar, col => schema);
+            let mut ob = col.get_object_builder();
+            ob.write_int(123);
+            let mut txn = isar.begin_txn(true).unwrap();
+            let oid = col.put(&txn, None, ob.finish().as_bytes()).unwrap();
+            txn.commit().unwrap();
+            oid
+        };
+
+        let mut schema = col!("col", f1 => Int, f2 => Long);
+        schema.set_version(2);
+        isar!(path: path, isar, col => schema);
+        let mut txn = isar.begin_txn(false).unwrap();
+        let object = col.get(&txn, oid).unwrap().unwrap();
+        assert_eq!(col.get_properties()[0].get_int(object), 123);
+        assert!(col.get_properties()[1].is_null(object));
+        txn.abort();
+    }
+
+    #[test]
+    fn test_migration_lower_version() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        {
+            let mut schema = col!("col", f1 => Int);
+            schema.set_version(2);
+            isar!(path: path, _isar, _col => schema);
+        }
+
+        let mut schema = Schema::new();
+        let mut col = col!("col", f1 => Int);
+        col.set_version(1);
+        schema.add_collection(col).unwrap();
+        let result = IsarInstance::create(path, 10000000, 126, EnvOptions::default(), schema);
+        assert!(matches!(result, Err(IsarError::MigrationError { .. })));
+    }
+
+    #[test]
+    fn test_migration_int_to_long() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let (oid1, oid2) = {
+            isar!(path: path, isar, col => col!("col", f1 => Int));
+            let mut txn = isar.begin_txn(true).unwrap();
+            let mut ob = col.get_object_builder();
+            ob.write_int(-123);
+            let oid1 = col.put(&txn, None, ob.finish().as_bytes()).unwrap();
+            let mut ob = col.get_object_builder();
+            ob.write_null();
+            let oid2 = col.put(&txn, None, ob.finish().as_bytes()).unwrap();
+            txn.commit().unwrap();
+            (oid1, oid2)
+        };
+
+        isar!(path: path, isar, col => col!("col", f1 => Long));
+        let mut txn = isar.begin_txn(false).unwrap();
+        let property = &col.get_properties()[0];
+        let object1 = col.get(&txn, oid1).unwrap().unwrap();
+        assert_eq!(property.get_long(object1), -123);
+        let object2 = col.get(&txn, oid2).unwrap().unwrap();
+        assert_eq!(property.get_long(object2), Property::NULL_LONG);
+        txn.abort();
+    }
+
+    #[test]
+    fn test_migration_float_to_double() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let oid = {
+            isar!(path: path, isar, col => col!("col", f1 => Float));
+            let mut txn = isar.begin_txn(true).unwrap();
+            let mut ob = col.get_object_builder();
+            ob.write_float(1.5);
+            let oid = col.put(&txn, None, ob.finish().as_bytes()).unwrap();
+            txn.commit().unwrap();
+            oid
+        };
+
+        isar!(path: path, isar, col => col!("col", f1 => Double));
+        let mut txn = isar.begin_txn(false).unwrap();
+        let object = col.get(&txn, oid).unwrap().unwrap();
+        assert_eq!(col.get_properties()[0].get_double(object), 1.5);
+        txn.abort();
+    }
+
+    #[test]
+    fn test_compact() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+        let target_dir = tempdir().unwrap();
+        let target_path = target_dir.path().to_str().unwrap();
+
+        let (oids, size) = {
+            isar!(path: path, isar, col => col!("col", f1 => Long));
+
+            let mut txn = isar.begin_txn(true).unwrap();
+            let mut oids = vec![];
+            for i in 0..1000 {
+                let mut ob = col.get_object_builder();
+                ob.write_long(i);
+                oids.push(col.put(&txn, None, ob.finish().as_bytes()).unwrap());
+            }
+            for oid in &oids[10..] {
+                col.delete(&txn, *oid).unwrap();
+            }
+            txn.commit().unwrap();
+
+            isar.compact(target_path).unwrap();
+            (oids[..10].to_vec(), isar.get_size_bytes().unwrap())
+        };
+
+        let mut schema = Schema::new();
+        schema.add_collection(col!("col", f1 => Long)).unwrap();
+        let isar = IsarInstance::open(target_path, 10000000, 126, schema).unwrap();
+        assert!(isar.get_size_bytes().unwrap() < size);
+
+        let col = isar.get_collection(0).unwrap();
+        let txn = isar.begin_txn(false).unwrap();
+        let remaining = isar
+            .create_query_builder(col)
+            .build()
+            .find_all_vec(&txn)
+            .unwrap()
+            .into_iter()
+            .map(|(oid, _)| *oid)
+            .collect::<Vec<_>>();
+        assert_eq!(remaining, oids);
+    }
+
+    #[test]
+    fn test_too_many_readers() {
+        let dir = tempdir().unwrap();
+        let mut schema = Schema::new();
+        schema.add_collection(col!(f1 => Int)).unwrap();
+        let isar = IsarInstance::create(
+            dir.path().to_str().unwrap(),
+            100000,
+            2,
+            EnvOptions::default(),
+            schema,
+        )
+        .unwrap();
+
+        let barrier = Barrier::new(3);
+        let results = thread::scope(|s| {
+            let handles = (0..3)
+                .map(|_| {
+                    s.spawn(|| {
+                        // keep the txn open until all threads tried to begin one
+                        let result = isar.begin_txn(false);
+                        barrier.wait();
+                        match result {
+                            Ok(_) => Some(true),
+                            Err(IsarError::TooManyReaders {}) => Some(false),
+                            Err(_) => None,
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(results.iter().filter(|r| **r == Some(true)).count(), 2);
+        assert_eq!(results.iter().filter(|r| **r == Some(false)).count(), 1);
+    }
+
+    #[test]
+    fn test_multiple_instances_isolated() {
+        isar!(isar1, col1 => col!("col", f1 => Int));
+        isar!(isar2, col2 => col!("col", f1 => Int));
+
+        let put = |isar: &IsarInstance, col: &IsarCollection, value: i32| {
+            let mut txn = isar.begin_txn(true).unwrap();
+            let mut ob = col.get_object_builder();
+            ob.write_int(value);
+            let oid = col.put(&txn, None, ob.finish().as_bytes()).unwrap();
+            txn.commit().unwrap();
+            oid
+        };
+        let oid1 = put(&isar1, col1, 1);
+        put(&isar1, col1, 2);
+        let oid2 = put(&isar2, col2, 3);
+
+        let count = |isar: &IsarInstance, col| {
+            let txn = isar.begin_txn(false).unwrap();
+            isar.create_query_builder(col).build().count(&txn).unwrap()
+        };
+        assert_eq!(count(&isar1, col1), 2);
+        assert_eq!(count(&isar2, col2), 1);
+
+        // look up the ids with the prefix of the other collection so only the data is compared
+        let txn1 = isar1.begin_txn(false).unwrap();
+        let txn2 = isar2.begin_txn(false).unwrap();
+        let oid1_in_col2 = col2.get_object_id(oid1.get_time(), oid1.get_counter(), oid1.get_rand());
+        assert!(matches!(col2.get(&txn2, oid1_in_col2), Ok(None)));
+        let oid2_in_col1 = col1.get_object_id(oid2.get_time(), oid2.get_counter(), oid2.get_rand());
+        assert!(matches!(col1.get(&txn1, oid2_in_col1), Ok(None)));
+        assert!(matches!(col2.get(&txn2, oid2), Ok(Some(_))));
+    }
+
+    #[test]
+    fn test_multiple_instances_concurrent_writes() {
+        isar!(isar1, col1 => col!("col", f1 => Int));
+        isar!(isar2, col2 => col!("col", f1 => Int));
+
+        // both write txns are open at the same time
+        let barrier = Barrier::new(2);
+        thread::scope(|s| {
+            for (isar, col) in [(&isar1, col1), (&isar2, col2)] {
+                let barrier = &barrier;
+                s.spawn(move || {
+                    let mut txn = isar.begin_txn(true).unwrap();
+                    barrier.wait();
+                    for i in 0..100 {
+                        let mut ob = col.get_object_builder();
+                        ob.write_int(i);
+                        col.put(&txn, None, ob.finish().as_bytes()).unwrap();
+                    }
+                    txn.commit().unwrap();
+                });
+            }
+        });
+
+        for (isar, col) in [(&isar1, col1), (&isar2, col2)] {
+            let txn = isar.begin_txn(false).unwrap();
+            let count = isar.create_query_builder(col).build().count(&txn);
+            assert_eq!(count.unwrap(), 100);
+        }
+    }
+
+    #[test]
+    fn test_open_read_only() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let (oid, object) = {
+            isar!(path: path, isar, col => col!("col", f1 => Int));
+
+            let mut ob = col.get_object_builder();
+            ob.write_int(123);
+            let o = ob.finish();
+
+            let mut txn = isar.begin_txn(true).unwrap();
+            let oid = col.put(&txn, None, o.as_bytes()).unwrap();
+            txn.commit().unwrap();
+
+            (oid, o.as_bytes().to_vec())
+        };
+
+        let mut schema = Schema::new();
+        schema.add_collection(col!("col", f1 => Int)).unwrap();
+        let isar = IsarInstance::open(path, 10000000, 126, schema).unwrap();
+        let col = isar.get_collection(0).unwrap();
+
+        let mut txn = isar.begin_txn(false).unwrap();
+        assert_eq!(col.get(&txn, oid).unwrap().unwrap().to_vec(), object);
+        txn.abort();
+
+        assert!(matches!(
+            isar.begin_txn(true),
+            Err(IsarError::IllegalArg { .. })
+        ));
+    }
+
+    #[test]
+    fn test_sync() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let oid = {
+            let mut schema = Schema::new();
+            schema.add_collection(col!("col", f1 => Int)).unwrap();
+            let isar = IsarInstance::create(
+                path,
+                10000000,
+                126,
+                EnvOptions {
+                    no_sync: true,
+                    ..Default::default()
+                },
+                schema,
+            )
+            .unwrap();
+            let col = isar.get_collection(0).unwrap();
+
+            let mut ob = col.get_object_builder();
+            ob.write_int(123);
+            let mut txn = isar.begin_txn(true).unwrap();
+            let oid = col.put(&txn, None, ob.finish().as_bytes()).unwrap();
+            txn.commit().unwrap();
+
+            isar.sync(true).unwrap();
+            oid
+        };
+
+        let mut schema = Schema::new();
+        schema.add_collection(col!("col", f1 => Int)).unwrap();
+        let isar = IsarInstance::open(path, 10000000, 126, schema).unwrap();
+        let col = isar.get_collection(0).unwrap();
+        let txn = isar.begin_txn(false).unwrap();
+        let object = col.get(&txn, oid).unwrap().unwrap();
+        assert_eq!(col.get_properties()[0].get_int(object), 123);
+
+        assert!(matches!(isar.sync(true), Err(IsarError::IllegalArg { .. })));
+    }
+
+    #[test]
+    fn test_close() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let (oid, object) = {
+            isar!(path: path, isar, col => col!(f1 => Int));
+            let mut ob = col.get_object_builder();
+            ob.write_int(123);
+            let o = ob.finish();
+            let oid = {
+                let mut txn = isar.begin_txn(true).unwrap();
+                let oid = col.put(&txn, None, o.as_bytes()).unwrap();
+                txn.commit().unwrap();
+                oid
+            };
+            isar.close().unwrap();
+            (oid, o.as_bytes().to_vec())
+        };
+
+        let mut schema = Schema::new();
+        schema.add_collection(col!(f1 => Int)).unwrap();
+        let isar = IsarInstance::open(path, 10000000, 126, schema).unwrap();
+        {
+            let col = isar.get_collection(0).unwrap();
+            let mut txn = isar.begin_txn(false).unwrap();
+            assert_eq!(col.get(&txn, oid).unwrap().unwrap(), &object[..]);
+            txn.abort();
+        }
+        isar.close().unwrap();
+    }
+
+    #[test]
+    fn test_write_map() {
+        let dir = tempdir().unwrap();
+        let mut schema = Schema::new();
+        schema.add_collection(col!(f1 => Int; ind!(f1))).unwrap();
+        let options = EnvOptions {
+            write_map: true,
+            map_async: true,
+            no_meta_sync: true,
+            ..Default::default()
+        };
+        let path = dir.path().to_str().unwrap();
+        let isar = IsarInstance::create(path, 10000000, 126, options, schema).unwrap();
+        let col = isar.get_collection(0).unwrap();
+        let property = &col.get_properties()[0];
+
+        let mut txn = isar.begin_txn(true).unwrap();
+        let mut ob = col.get_object_builder();
+        ob.write_int(1);
+        let oid = col.put(&txn, None, ob.finish().as_bytes()).unwrap();
+        let mut ob = col.get_object_builder();
+        ob.write_int(2);
+        let oid2 = col.put(&txn, None, ob.finish().as_bytes()).unwrap();
+        txn.commit().unwrap();
+
+        let mut txn = isar.begin_txn(true).unwrap();
+        let mut ob = col.get_object_builder();
+        ob.write_int(3);
+        col.put(&txn, Some(oid), ob.finish().as_bytes()).unwrap();
+        col.delete(&txn, oid2).unwrap();
+        txn.commit().unwrap();
+
+        let txn = isar.begin_txn(false).unwrap();
+        let object = col.get(&txn, oid).unwrap().unwrap();
+        assert_eq!(property.get_int(object), 3);
+        assert!(col.get(&txn, oid2).unwrap().is_none());
+        assert!(col.verify_indexes(&txn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_write_map_failed_write() {
+        let dir = tempdir().unwrap();
+        let mut schema = Schema::new();
+        schema
+            .add_collection(col!(f1 => Int; ind!(f1; true)))
+            .unwrap();
+        let options = EnvOptions {
+            write_map: true,
+            ..Default::default()
+        };
+        let path = dir.path().to_str().unwrap();
+        let isar = IsarInstance::create(path, 10000000, 126, options, schema).unwrap();
+        let col = isar.get_collection(0).unwrap();
+
+        let mut ob = col.get_object_builder();
+        ob.write_int(1);
+        let object = ob.finish();
+        let mut txn = isar.begin_txn(true).unwrap();
+        let oid = col.put(&txn, None, object.as_bytes()).unwrap();
+        txn.commit().unwrap();
+
+        let mut txn = isar.begin_txn(true).unwrap();
+        assert!(matches!(
+            txn.savepoint(|_| Ok(())),
+            Err(IsarError::IllegalArg { .. })
+        ));
+        assert!(matches!(
+            col.put(&txn, None, object.as_bytes()),
+            Err(IsarError::UniqueViolated { .. })
+        ));
+        assert!(matches!(
+            col.get(&txn, oid),
+            Err(IsarError::IllegalState { .. })
+        ));
+        assert!(matches!(txn.commit(), Err(IsarError::IllegalState { .. })));
+        assert!(!txn.is_active());
+
+        let txn = isar.begin_txn(false).unwrap();
+        assert!(col.get(&txn, oid).unwrap().is_some());
+        assert!(col.verify_indexes(&txn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_open_read_only_changed_schema() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+        {
+            isar!(path: path, _isar, _col => col!("col", f1 => Int));
+        }
+
+        let mut schema = Schema::new();
+        schema.add_collection(col!("col", f2 => Int)).unwrap();
+        assert!(matches!(
+            IsarInstance::open(path, 10000000, 126, schema),
+            Err(IsarError::MigrationError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_open_read_only_missing_db() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let mut schema = Schema::new();
+        schema.add_collection(col!("col", f1 => Int)).unwrap();
+        assert!(matches!(
+            IsarInstance::open(path, 10000000, 126, schema),
+            Err(IsarError::DbNotFound {})
+        ));
+    }
+
+    #[test]
+    fn test_get_collection_names() {
+        isar!(isar, _col1 => col!("col1", f1 => Int), _col2 => col!("col2", f1 => Int));
+        assert_eq!(isar.get_collection_names(), vec!["col1", "col2"]);
+    }
+
+    #[test]
+    fn test_get_property_names_and_types() {
+        isar!(isar, col => col!("col", a => Byte, b => Int, c => Int, d => String, e => LongList));
+        assert_eq!(col.get_property_names(), vec!["a", "b", "c", "d", "e"]);
+        assert_eq!(
+            col.get_property_types(),
+            vec![
+                DataType::Byte,
+                DataType::Int,
+                DataType::Int,
+                DataType::String,
+                DataType::LongList
+            ]
+        );
+    }
+
     #[test]
     fn test_open_instance_added_collection() {
         let dir = tempdir().unwrap();
@@ -124,7 +846,7 @@ mod tests {
             ob.write_int(123);
             let o = ob.finish();
 
-            let txn = isar.begin_txn(true).unwrap();
+            let mut txn = isar.begin_txn(true).unwrap();
             let oid = col1.put(&txn, None, o.as_bytes()).unwrap();
             txn.commit().unwrap();
 
@@ -133,7 +855,7 @@ mod tests {
 
         isar!(path: path, isar, col1 => col!("col1", f1 => Int), col2 => col!("col2", f1 => Int));
 
-        let txn = isar.begin_txn(false).unwrap();
+        let mut txn = isar.begin_txn(false).unwrap();
         assert_eq!(col1.get(&txn, oid).unwrap().unwrap().to_vec(), object);
         assert_eq!(
             isar.create_query_builder(col2).build().count(&txn).unwrap(),
@@ -154,7 +876,7 @@ mod tests {
             ob.write_int(123);
             let o = ob.finish();
 
-            let txn = isar.begin_txn(true).unwrap();
+            let mut txn = isar.begin_txn(true).unwrap();
             //col1.put(&txn, None, o.as_bytes()).unwrap();
             col1.put(&txn, None, o.as_bytes()).unwrap();
             txn.commit().unwrap();
@@ -166,11 +888,39 @@ mod tests {
 
         isar!(path: path, isar, col1 => col!("col1", f1 => Int), _col2 => col!("col2", f1 => Int));
 
-        let txn = isar.begin_txn(false).unwrap();
+        let mut txn = isar.begin_txn(false).unwrap();
         assert_eq!(
             isar.create_query_builder(col1).build().count(&txn).unwrap(),
             0
         );
         txn.abort();
     }
+
+    #[test]
+    fn test_reopen_instance_renamed_property() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let oid = {
+            isar!(path: path, isar, col => col!("col", f1 => Int));
+
+            let mut ob = col.get_object_builder();
+            ob.write_int(123);
+            let o = ob.finish();
+
+            let mut txn = isar.begin_txn(true).unwrap();
+            let oid = col.put(&txn, None, o.as_bytes()).unwrap();
+            txn.commit().unwrap();
+            oid
+        };
+
+        let mut schema = col!("col", f2 => Int);
+        schema.rename_property("f1", "f2").unwrap();
+        isar!(path: path, isar, col => schema);
+
+        let mut txn = isar.begin_txn(false).unwrap();
+        let object = col.get(&txn, oid).unwrap().unwrap();
+        assert_eq!(col.get_object_reader(object).read_int(), 123);
+        txn.abort();
+    }
 }
@@ -11,6 +11,7 @@ pub mod data_dbs;
 pub mod error;
 pub mod index;
 pub mod instance;
+mod link;
 mod lmdb;
 pub mod object;
 pub mod query;
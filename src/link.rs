@@ -0,0 +1,93 @@
+use crate::error::Result;
+use crate::lmdb::db::Db;
+use crate::lmdb::txn::Txn;
+use crate::object::object_id::ObjectId;
+
+/*
+
+Links are stored in a dup db. The key is the source ObjectId followed by the link id and the
+values are the target ObjectIds.
+
+ */
+
+#[derive(Clone)]
+pub(crate) struct Link {
+    id: u16,
+    source_col_id: u16,
+    target_col_id: u16,
+    db: Db,
+}
+
+impl Link {
+    pub(crate) fn new(id: u16, source_col_id: u16, target_col_id: u16, db: Db) -> Self {
+        Link {
+            id,
+            source_col_id,
+            target_col_id,
+            db,
+        }
+    }
+
+    pub(crate) fn get_id(&self) -> u16 {
+        self.id
+    }
+
+    pub(crate) fn get_target_col_id(&self) -> u16 {
+        self.target_col_id
+    }
+
+    fn get_key(&self, source: ObjectId) -> Vec<u8> {
+        let mut key = source.as_bytes().to_vec();
+        key.extend_from_slice(&self.id.to_le_bytes());
+        key
+    }
+
+    pub(crate) fn create(&self, txn: &Txn, source: ObjectId, target: ObjectId) -> Result<bool> {
+        self.db
+            .put_no_dup_data(txn, &self.get_key(source), target.as_bytes())
+    }
+
+    pub(crate) fn delete(&self, txn: &Txn, source: ObjectId, target: ObjectId) -> Result<bool> {
+        if self.get_targets(txn, source)?.contains(&target) {
+            self.db
+                .delete(txn, &self.get_key(source), Some(target.as_bytes()))?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    pub(crate) fn delete_for_object(&self, txn: &Txn, source: ObjectId) -> Result<()> {
+        self.db.delete_key_prefix(txn, &self.get_key(source))?;
+        Ok(())
+    }
+
+    pub(crate) fn get_targets(&self, txn: &Txn, source: ObjectId) -> Result<Vec<ObjectId>> {
+        let key = self.get_key(source);
+        let mut cursor = self.db.cursor(txn)?;
+        let mut targets = vec![];
+        let mut entry = cursor.move_to(&key)?;
+        while let Some((entry_key, target)) = entry {
+            if entry_key != &key[..] {
+                break;
+            }
+            targets.push(*ObjectId::from_bytes(target));
+            entry = cursor.move_to_next()?;
+        }
+        Ok(targets)
+    }
+
+    pub(crate) fn clear(&self, txn: &Txn) -> Result<()> {
+        let prefix = self.source_col_id.to_le_bytes();
+        let id = self.id.to_le_bytes();
+        let mut cursor = self.db.cursor(txn)?;
+        let mut iter = cursor.iter_prefix(&prefix);
+        while let Some(entry) = iter.next() {
+            let (key, _) = entry?;
+            if key[ObjectId::get_size()..] == id {
+                iter.delete_current(false)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -6,6 +6,7 @@ use crate::lmdb::{from_mdb_val, to_mdb_val, KeyVal, EMPTY_KEY, EMPTY_VAL};
 use core::ptr;
 use lmdb_sys as ffi;
 use lmdb_sys::MDB_val;
+use std::borrow::BorrowMut;
 use std::marker::PhantomData;
 
 #[derive(Debug)]
@@ -90,6 +91,35 @@ impl<'txn> Cursor<'txn> {
         self.op_get(ffi::MDB_NEXT, None)
     }
 
+    pub fn move_to_prev(&mut self) -> Result<Option<KeyVal<'txn>>> {
+        self.op_get(ffi::MDB_PREV, None)
+    }
+
+    /// Requires the cursor to have a valid position. Only supported for dup-sort dbs.
+    pub fn move_to_first_dup(&mut self) -> Result<Option<KeyVal<'txn>>> {
+        // MDB_FIRST_DUP only returns the value so we pass the current key along.
+        if let Some((key, _)) = self.get()? {
+            let key = unsafe { to_mdb_val(key) };
+            self.op_get(ffi::MDB_FIRST_DUP, Some(key))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Requires the cursor to have a valid position. Returns `None` after the last duplicate
+    /// value of the current key.
+    pub fn move_to_next_dup(&mut self) -> Result<Option<KeyVal<'txn>>> {
+        self.op_get(ffi::MDB_NEXT_DUP, None)
+    }
+
+    /// Requires the cursor to have a valid position. Returns the number of values of the
+    /// current key. Only supported for dup-sort dbs.
+    pub fn count_dup(&self) -> Result<u64> {
+        let mut count: usize = 0;
+        unsafe { lmdb_result(ffi::mdb_cursor_count(self.cursor, &mut count))? };
+        Ok(count as u64)
+    }
+
     /// Requires the cursor to have a valid position
     pub fn delete_current(&mut self, delete_dup: bool) -> Result<()> {
         let op = if delete_dup { ffi::MDB_NODUPDATA } else { 0 };
@@ -99,17 +129,19 @@ impl<'txn> Cursor<'txn> {
         Ok(())
     }
 
-    /// Requires the cursor to have a valid position
+    /// Requires the cursor to have a valid position. Returns the number of deletions.
     #[allow(clippy::while_let_loop)]
-    pub fn delete_while<F>(&mut self, predicate: F, delete_dup: bool) -> Result<()>
+    pub fn delete_while<F>(&mut self, predicate: F, delete_dup: bool) -> Result<usize>
     where
         F: Fn(&[u8], &[u8]) -> bool,
     {
+        let mut count = 0;
         if let Some((key, val)) = self.get()? {
             if !predicate(key, val) {
-                return Ok(());
+                return Ok(0);
             }
             self.delete_current(delete_dup)?;
+            count += 1;
             loop {
                 if let Some((key, val)) = self.move_to_next()? {
                     if !predicate(key, val) {
@@ -119,10 +151,11 @@ impl<'txn> Cursor<'txn> {
                     break;
                 }
                 self.delete_current(delete_dup)?;
+                count += 1;
             }
         }
 
-        Ok(())
+        Ok(count)
     }
 
     /// Requires the cursor to have a valid position
@@ -130,6 +163,22 @@ impl<'txn> Cursor<'txn> {
         CursorIterator::new(self, ffi::MDB_GET_CURRENT, ffi::MDB_NEXT)
     }
 
+    /// Requires the cursor to have a valid position
+    pub fn iter_prev<'a>(&'a mut self) -> CursorIterator<'a, 'txn> {
+        CursorIterator::new(self, ffi::MDB_GET_CURRENT, ffi::MDB_PREV)
+    }
+
+    /// Iterates over all entries whose key starts with `prefix`. An empty prefix matches all
+    /// entries.
+    pub fn iter_prefix<'a>(&'a mut self, prefix: &'a [u8]) -> PrefixIterator<'a, 'txn> {
+        PrefixIterator::new(self, prefix)
+    }
+
+    /// Like `iter_prefix` but the iterator owns the cursor.
+    pub fn into_iter_prefix(self, prefix: &[u8]) -> PrefixIterator<'_, 'txn, Cursor<'txn>> {
+        PrefixIterator::new(self, prefix)
+    }
+
     /*/// Requires the cursor to have a valid position
     pub fn iter_no_dup<'a>(&'a mut self) -> CursorIterator<'a, 'txn> {
         CursorIterator::new(self, ffi::MDB_GET_CURRENT, ffi::MDB_NODUPDATA)
@@ -186,6 +235,66 @@ impl<'a, 'txn> Iterator for CursorIterator<'a, 'txn> {
     }
 }
 
+/// An iterator over the key/value pairs with a common key prefix.
+pub struct PrefixIterator<'a, 'txn, C = &'a mut Cursor<'txn>> {
+    cursor: C,
+    prefix: &'a [u8],
+    started: bool,
+    done: bool,
+    _marker: PhantomData<Cursor<'txn>>,
+}
+
+impl<'a, 'txn, C: BorrowMut<Cursor<'txn>>> PrefixIterator<'a, 'txn, C> {
+    fn new(cursor: C, prefix: &'a [u8]) -> Self {
+        PrefixIterator {
+            cursor,
+            prefix,
+            started: false,
+            done: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Deletes the entry that was returned last. The iteration continues with the next entry.
+    pub fn delete_current(&mut self, delete_dup: bool) -> Result<()> {
+        self.cursor.borrow_mut().delete_current(delete_dup)
+    }
+}
+
+impl<'a, 'txn, C: BorrowMut<Cursor<'txn>>> Iterator for PrefixIterator<'a, 'txn, C> {
+    type Item = Result<KeyVal<'txn>>;
+
+    fn next(&mut self) -> Option<Result<KeyVal<'txn>>> {
+        if self.done {
+            return None;
+        }
+        let cursor = self.cursor.borrow_mut();
+        let result = if self.started {
+            cursor.move_to_next()
+        } else {
+            self.started = true;
+            // LMDB does not support searching for an empty key
+            if self.prefix.is_empty() {
+                cursor.move_to_first()
+            } else {
+                cursor.move_to_gte(self.prefix)
+            }
+        };
+
+        match result {
+            Ok(Some((key, val))) if key.starts_with(self.prefix) => Some(Ok((key, val))),
+            Ok(_) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::lmdb::db::Db;
@@ -437,6 +546,105 @@ mod tests {
         assert!(entry.is_none());
     }
 
+    #[test]
+    fn test_move_to_prev() {
+        let (env, db) = get_filled_db();
+
+        let txn = env.txn(false).unwrap();
+        let mut cur = db.cursor(&txn).unwrap();
+
+        let entry = cur.move_to_last().unwrap();
+        assert_eq!(entry, Some((&b"key4"[..], &b"val4"[..])));
+
+        let entry = cur.move_to_prev().unwrap();
+        assert_eq!(entry, Some((&b"key3"[..], &b"val3"[..])));
+
+        cur.move_to_first().unwrap();
+        let entry = cur.move_to_prev().unwrap();
+        assert_eq!(entry, None);
+    }
+
+    #[test]
+    fn test_move_to_prev_dup() {
+        let (env, db) = get_filled_db_dup();
+
+        let txn = env.txn(false).unwrap();
+        let mut cur = db.cursor(&txn).unwrap();
+
+        cur.move_to(b"key2").unwrap();
+        let entry = cur.move_to_prev().unwrap();
+        assert_eq!(entry, Some((&b"key1"[..], &b"val1c"[..])));
+
+        let entry = cur.move_to_prev().unwrap();
+        assert_eq!(entry, Some((&b"key1"[..], &b"val1b"[..])));
+    }
+
+    #[test]
+    fn test_move_to_prev_empty() {
+        let (env, db) = get_empty_db();
+
+        let txn = env.txn(false).unwrap();
+        let mut cur = db.cursor(&txn).unwrap();
+
+        let entry = cur.move_to_prev().unwrap();
+        assert!(entry.is_none());
+
+        let entry = cur.move_to_last().unwrap();
+        assert!(entry.is_none());
+
+        let entry = cur.move_to_prev().unwrap();
+        assert!(entry.is_none());
+    }
+
+    #[test]
+    fn test_count_dup() {
+        let (env, db) = get_filled_db_dup();
+
+        let txn = env.txn(true).unwrap();
+        let mut cur = db.cursor(&txn).unwrap();
+
+        cur.move_to(b"key1").unwrap();
+        assert_eq!(cur.count_dup().unwrap(), 3);
+
+        db.put(&txn, b"key2", b"val2d").unwrap();
+        cur.move_to(b"key2").unwrap();
+        assert_eq!(cur.count_dup().unwrap(), 4);
+    }
+
+    #[test]
+    fn test_move_to_first_dup() {
+        let (env, db) = get_filled_db_dup();
+
+        let txn = env.txn(false).unwrap();
+        let mut cur = db.cursor(&txn).unwrap();
+
+        cur.move_to_last().unwrap();
+        let entry = cur.move_to_first_dup().unwrap();
+        assert_eq!(entry, Some((&b"key2"[..], &b"val2"[..])));
+    }
+
+    #[test]
+    fn test_move_to_next_dup_per_key() {
+        let (env, db) = get_filled_db_dup();
+
+        let txn = env.txn(false).unwrap();
+        let mut cur = db.cursor(&txn).unwrap();
+
+        for (key, values) in &[
+            (b"key1", [&b"val1"[..], b"val1b", b"val1c"]),
+            (b"key2", [&b"val2"[..], b"val2b", b"val2c"]),
+        ] {
+            let mut entry = cur.move_to(&key[..]).unwrap();
+            let mut dup_values = vec![];
+            while let Some((k, v)) = entry {
+                assert_eq!(k, &key[..]);
+                dup_values.push(v);
+                entry = cur.move_to_next_dup().unwrap();
+            }
+            assert_eq!(dup_values, values);
+        }
+    }
+
     #[test]
     fn test_delete_current() {
         let (env, db) = get_filled_db();
@@ -479,19 +687,21 @@ mod tests {
         let entries = Arc::new(Mutex::new(vec![(b"key1", b"val1"), (b"key2", b"val2")]));
 
         cur.move_to_first().unwrap();
-        cur.delete_while(
-            |k, v| {
-                let mut entries = entries.lock().unwrap();
-                if entries.is_empty() {
-                    return false;
-                }
-                let (rk, rv) = entries.remove(0);
-                assert_eq!((&rk[..], &rv[..]), (k, v));
-                true
-            },
-            false,
-        )
-        .unwrap();
+        let count = cur
+            .delete_while(
+                |k, v| {
+                    let mut entries = entries.lock().unwrap();
+                    if entries.is_empty() {
+                        return false;
+                    }
+                    let (rk, rv) = entries.remove(0);
+                    assert_eq!((&rk[..], &rv[..]), (k, v));
+                    true
+                },
+                false,
+            )
+            .unwrap();
+        assert_eq!(count, 2);
 
         let entry = cur.move_to_first().unwrap();
         assert_eq!(entry, Some((&b"key3"[..], &b"val3"[..])));
@@ -533,4 +743,106 @@ mod tests {
             .collect_vec();
         assert_eq!(vec![b"key2", b"key3", b"key4"], keys);
     }
+
+    #[test]
+    fn test_iter_prefix() {
+        let env = get_env();
+        let txn = env.txn(true).unwrap();
+        let db = Db::open(&txn, "test", false, false).unwrap();
+        for key in &[&b"a1"[..], b"b", b"b1", b"b2", b"ba", b"c1"] {
+            db.put(&txn, key, b"val").unwrap();
+        }
+        let mut cur = db.cursor(&txn).unwrap();
+
+        let mut keys = |prefix: &[u8]| {
+            cur.iter_prefix(prefix)
+                .map(|r| r.unwrap().0.to_vec())
+                .collect_vec()
+        };
+        assert_eq!(keys(b"b"), vec![&b"b"[..], b"b1", b"b2", b"ba"]);
+        assert_eq!(keys(b"b1"), vec![b"b1"]);
+        assert_eq!(keys(b"a"), vec![b"a1"]);
+        assert_eq!(keys(b"c1"), vec![b"c1"]);
+        assert!(keys(b"0").is_empty());
+        assert!(keys(b"bb").is_empty());
+        assert!(keys(b"d").is_empty());
+        assert_eq!(keys(b"").len(), 6);
+    }
+
+    #[test]
+    fn test_iter_prefix_delete() {
+        let (env, db) = get_filled_db_dup();
+        let txn = env.txn(true).unwrap();
+        let mut cur = db.cursor(&txn).unwrap();
+
+        let mut iter = cur.iter_prefix(b"key1");
+        let mut deleted = vec![];
+        while let Some(entry) = iter.next() {
+            deleted.push(entry.unwrap().1.to_vec());
+            iter.delete_current(false).unwrap();
+        }
+        assert_eq!(deleted, vec![&b"val1"[..], b"val1b", b"val1c"]);
+
+        let entries = cur.iter_prefix(b"key").map(Result::unwrap).collect_vec();
+        assert_eq!(
+            entries,
+            vec![
+                (&b"key2"[..], &b"val2"[..]),
+                (&b"key2"[..], &b"val2b"[..]),
+                (&b"key2"[..], &b"val2c"[..])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_iter_prev() {
+        let (env, db) = get_filled_db();
+
+        let txn = env.txn(false).unwrap();
+        let mut cur = db.cursor(&txn).unwrap();
+
+        cur.move_to_last().unwrap();
+        let entries = cur.iter_prev().map(Result::unwrap).collect_vec();
+        assert_eq!(
+            entries,
+            vec![
+                (&b"key4"[..], &b"val4"[..]),
+                (&b"key3"[..], &b"val3"[..]),
+                (&b"key2"[..], &b"val2"[..]),
+                (&b"key1"[..], &b"val1"[..])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_iter_prev_dup() {
+        let (env, db) = get_filled_db_dup();
+
+        let txn = env.txn(false).unwrap();
+        let mut cur = db.cursor(&txn).unwrap();
+
+        cur.move_to(b"key2").unwrap();
+        let entries = cur.iter_prev().map(Result::unwrap).collect_vec();
+        assert_eq!(
+            entries,
+            vec![
+                (&b"key2"[..], &b"val2"[..]),
+                (&b"key1"[..], &b"val1c"[..]),
+                (&b"key1"[..], &b"val1b"[..]),
+                (&b"key1"[..], &b"val1"[..])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_iter_prev_from_first() {
+        let (env, db) = get_filled_db();
+
+        let txn = env.txn(false).unwrap();
+        let mut cur = db.cursor(&txn).unwrap();
+
+        cur.move_to_first().unwrap();
+        let entries = cur.iter_prev().map(Result::unwrap).collect_vec();
+        assert_eq!(entries, vec![(&b"key1"[..], &b"val1"[..])]);
+    }
 }
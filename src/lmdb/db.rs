@@ -1,4 +1,4 @@
-use crate::error::Result;
+use crate::error::{IsarError, Result};
 use crate::lmdb::cursor::Cursor;
 use crate::lmdb::error::{lmdb_result, LmdbError};
 use crate::lmdb::txn::Txn;
@@ -15,8 +15,27 @@ pub struct Db {
 
 impl Db {
     pub fn open(txn: &Txn, name: &str, dup: bool, fixed_vals: bool) -> Result<Self> {
+        Db::open_internal(txn, name, dup, fixed_vals, ffi::MDB_CREATE)
+    }
+
+    /// Opens a db without creating it. Works with read transactions.
+    pub fn open_existing(txn: &Txn, name: &str, dup: bool, fixed_vals: bool) -> Result<Self> {
+        match Db::open_internal(txn, name, dup, fixed_vals, 0) {
+            Err(IsarError::LmdbError { code, .. }) if code == ffi::MDB_NOTFOUND => {
+                Err(IsarError::DbNotFound {})
+            }
+            result => result,
+        }
+    }
+
+    fn open_internal(
+        txn: &Txn,
+        name: &str,
+        dup: bool,
+        fixed_vals: bool,
+        mut flags: u32,
+    ) -> Result<Self> {
         let name = CString::new(name.as_bytes()).unwrap();
-        let mut flags = ffi::MDB_CREATE;
         if dup {
             flags |= ffi::MDB_DUPSORT;
             if fixed_vals {
@@ -103,13 +122,17 @@ impl Db {
         Ok(())
     }
 
-    pub fn delete_key_prefix(&self, txn: &Txn, key_prefix: &[u8]) -> Result<()> {
+    /// Returns the number of deleted keys.
+    pub fn delete_key_prefix(&self, txn: &Txn, key_prefix: &[u8]) -> Result<usize> {
         let mut cursor = self.cursor(txn)?;
-        let check_prefix = |key: &[u8], _: &[u8]| &key[0..key_prefix.len()] == key_prefix;
-        if cursor.move_to_gte(key_prefix)?.is_some() {
-            cursor.delete_while(check_prefix, self.dup)?;
+        let mut iter = cursor.iter_prefix(key_prefix);
+        let mut count = 0;
+        while let Some(entry) = iter.next() {
+            entry?;
+            iter.delete_current(self.dup)?;
+            count += 1;
         }
-        Ok(())
+        Ok(count)
     }
 
     pub fn clear(&self, txn: &Txn) -> Result<()> {
@@ -4,16 +4,92 @@ use crate::lmdb::txn::Txn;
 use core::ptr;
 use lmdb_sys as ffi;
 use std::ffi::CString;
+use std::mem::MaybeUninit;
+use std::sync::{PoisonError, RwLock};
 
 pub struct Env {
     env: *mut ffi::MDB_env,
+    /// Held for reading by every open transaction so the map cannot be resized under them.
+    txn_lock: RwLock<()>,
+}
+
+#[allow(dead_code)]
+pub struct EnvStat {
+    pub map_size: usize,
+    pub page_size: u32,
+    pub pages: usize,
+    pub used_pages: usize,
+    pub free_pages: usize,
+}
+
+/// Options that trade durability for write performance. The default flushes every commit to
+/// disk.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EnvOptions {
+    /// Commits are not flushed to disk until the environment is synced. A system crash may undo
+    /// the last committed transactions but the database stays consistent.
+    pub no_sync: bool,
+    /// Only the meta page is not flushed on commit. A system crash may undo the last committed
+    /// transaction.
+    pub no_meta_sync: bool,
+    /// Writes go directly to the memory map instead of using `write` calls. This is faster but
+    /// stray writes of the process can corrupt the database.
+    pub write_map: bool,
+    /// Flushes the memory map asynchronously. Only has an effect if `write_map` is enabled.
+    pub map_async: bool,
+}
+
+impl EnvOptions {
+    fn get_flags(&self) -> u32 {
+        let mut flags = 0;
+        if self.no_sync {
+            flags |= ffi::MDB_NOSYNC;
+        }
+        if self.no_meta_sync {
+            flags |= ffi::MDB_NOMETASYNC;
+        }
+        if self.write_map {
+            flags |= ffi::MDB_WRITEMAP;
+        }
+        if self.map_async {
+            flags |= ffi::MDB_MAPASYNC;
+        }
+        flags
+    }
 }
 
 unsafe impl Sync for Env {}
 unsafe impl Send for Env {}
 
 impl Env {
-    pub fn create(path: &str, max_dbs: u32, max_size: usize) -> Result<Env> {
+    pub fn create(
+        path: &str,
+        max_dbs: u32,
+        max_size: usize,
+        max_readers: u32,
+        options: EnvOptions,
+    ) -> Result<Env> {
+        let flags = options.get_flags();
+        Env::open_internal(path, max_dbs, max_size, max_readers, flags)
+    }
+
+    pub fn open_read_only(
+        path: &str,
+        max_dbs: u32,
+        max_size: usize,
+        max_readers: u32,
+    ) -> Result<Env> {
+        Env::open_internal(path, max_dbs, max_size, max_readers, ffi::MDB_RDONLY)
+    }
+
+    fn open_internal(
+        path: &str,
+        max_dbs: u32,
+        max_size: usize,
+        max_readers: u32,
+        flags: u32,
+    ) -> Result<Env> {
+        let read_only = flags & ffi::MDB_RDONLY != 0;
         let path = CString::new(path.as_bytes()).unwrap();
         let mut env: *mut ffi::MDB_env = ptr::null_mut();
         unsafe {
@@ -31,17 +107,28 @@ impl Env {
                 lmdb_result(err_code)?;
             }
 
-            let err_code = ffi::mdb_env_open(env, path.as_ptr(), 0, 0o600);
+            let err_code = ffi::mdb_env_set_maxreaders(env, max_readers);
+            if err_code != ffi::MDB_SUCCESS {
+                ffi::mdb_env_close(env);
+                lmdb_result(err_code)?;
+            }
+
+            let err_code = ffi::mdb_env_open(env, path.as_ptr(), flags, 0o600);
             if err_code != ffi::MDB_SUCCESS {
                 ffi::mdb_env_close(env);
-                if err_code == 2 {
+                if err_code == 2 && read_only {
+                    Err(IsarError::DbNotFound {})?;
+                } else if err_code == 2 {
                     Err(IsarError::PathError {})?;
                 } else {
                     lmdb_result(err_code)?;
                 }
             }
         }
-        Ok(Env { env })
+        Ok(Env {
+            env,
+            txn_lock: RwLock::new(()),
+        })
     }
 
     pub fn txn(&self, write: bool) -> Result<Txn> {
@@ -58,9 +145,78 @@ impl Env {
 
         let flags = if write { 0 } else { ffi::MDB_RDONLY };
 
+        let guard = self.txn_lock.read().unwrap_or_else(PoisonError::into_inner);
         unsafe { lmdb_result(ffi::mdb_txn_begin(self.env, parent, flags, &mut txn))? }
 
-        Ok(Txn::new(txn, self))
+        Ok(Txn::new(txn, self, guard))
+    }
+
+    pub fn stat(&self) -> Result<EnvStat> {
+        let mut stat = MaybeUninit::<ffi::MDB_stat>::uninit();
+        let mut info = MaybeUninit::<ffi::MDB_envinfo>::uninit();
+        let (stat, info) = unsafe {
+            lmdb_result(ffi::mdb_env_stat(self.env, stat.as_mut_ptr()))?;
+            lmdb_result(ffi::mdb_env_info(self.env, info.as_mut_ptr()))?;
+            (stat.assume_init(), info.assume_init())
+        };
+        let pages = info.me_mapsize / stat.ms_psize as usize;
+        let used_pages = info.me_last_pgno + 1;
+        Ok(EnvStat {
+            map_size: info.me_mapsize,
+            page_size: stat.ms_psize,
+            pages,
+            used_pages,
+            free_pages: pages.saturating_sub(used_pages),
+        })
+    }
+
+    /// Writes a compacted copy of the environment to the existing directory `path`. Free pages
+    /// are omitted from the copy.
+    pub fn copy_compact(&self, path: &str) -> Result<()> {
+        let path = CString::new(path.as_bytes()).unwrap();
+        unsafe {
+            lmdb_result(ffi::mdb_env_copy2(
+                self.env,
+                path.as_ptr(),
+                ffi::MDB_CP_COMPACT,
+            ))?
+        }
+        Ok(())
+    }
+
+    /// Clears reader slots of processes that no longer exist and returns the number of cleared
+    /// slots.
+    pub fn check_readers(&self) -> Result<i32> {
+        let mut dead = 0;
+        unsafe { lmdb_result(ffi::mdb_reader_check(self.env, &mut dead))? }
+        Ok(dead)
+    }
+
+    /// Flushes committed transactions to disk. With `force` the flush is synchronous even if
+    /// the environment was opened with [`EnvOptions::no_sync`].
+    pub fn sync(&self, force: bool) -> Result<()> {
+        unsafe { lmdb_result(ffi::mdb_env_sync(self.env, force as i32))? }
+        Ok(())
+    }
+
+    /// Nested transactions are not supported with [`EnvOptions::write_map`].
+    pub fn supports_nested_txns(&self) -> Result<bool> {
+        let mut flags = 0;
+        unsafe { lmdb_result(ffi::mdb_env_get_flags(self.env, &mut flags))? }
+        Ok(flags & ffi::MDB_WRITEMAP == 0)
+    }
+
+    /// Fails while a transaction is open because resizing moves the memory map that the data
+    /// returned by its reads points into.
+    pub fn set_map_size(&self, size: usize) -> Result<()> {
+        let _guard = self
+            .txn_lock
+            .try_write()
+            .map_err(|_| IsarError::IllegalState {
+                message: "The map size cannot be changed while a transaction is open.".to_string(),
+            })?;
+        unsafe { lmdb_result(ffi::mdb_env_set_mapsize(self.env, size))? }
+        Ok(())
     }
 }
 
@@ -84,8 +240,49 @@ pub mod tests {
         get_env();
     }
 
+    #[test]
+    fn test_stat() {
+        let env = get_env();
+        let stat = env.stat().unwrap();
+        assert!(stat.page_size > 0);
+        assert!(stat.used_pages > 0);
+        assert_eq!(stat.pages, stat.used_pages + stat.free_pages);
+    }
+
+    #[test]
+    fn test_set_map_size() {
+        let env = get_env();
+        let txn = env.txn(false).unwrap();
+        assert!(matches!(
+            env.set_map_size(200000),
+            Err(IsarError::IllegalState { .. })
+        ));
+        txn.abort();
+
+        env.set_map_size(200000).unwrap();
+        assert!(env.stat().unwrap().map_size >= 200000);
+    }
+
+    #[test]
+    fn test_check_readers() {
+        let env = get_env();
+        for _ in 0..3 {
+            let txn = env.txn(false).unwrap();
+            txn.abort();
+        }
+        let _txn = env.txn(false).unwrap();
+        assert!(env.check_readers().unwrap() >= 0);
+    }
+
     pub fn get_env() -> Env {
         let dir = tempdir().unwrap();
-        Env::create(dir.path().to_str().unwrap(), 50, 100000).unwrap()
+        Env::create(
+            dir.path().to_str().unwrap(),
+            50,
+            100000,
+            126,
+            EnvOptions::default(),
+        )
+        .unwrap()
     }
 }
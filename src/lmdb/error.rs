@@ -10,6 +10,7 @@ pub enum LmdbError {
     KeyExist {},
     NotFound {},
     MapFull {},
+    ReadersFull {},
     Other { code: i32, message: String },
 }
 
@@ -19,6 +20,7 @@ impl LmdbError {
             ffi::MDB_KEYEXIST => LmdbError::KeyExist {},
             ffi::MDB_NOTFOUND => LmdbError::NotFound {},
             ffi::MDB_MAP_FULL => LmdbError::MapFull {},
+            ffi::MDB_READERS_FULL => LmdbError::ReadersFull {},
             other => unsafe {
                 let err_raw = mdb_strerror(other);
                 let err = CStr::from_ptr(err_raw);
@@ -35,6 +37,7 @@ impl LmdbError {
             LmdbError::KeyExist {} => ffi::MDB_KEYEXIST,
             LmdbError::NotFound {} => ffi::MDB_NOTFOUND,
             LmdbError::MapFull {} => ffi::MDB_MAP_FULL,
+            LmdbError::ReadersFull {} => ffi::MDB_READERS_FULL,
             LmdbError::Other {
                 code: other,
                 message: _,
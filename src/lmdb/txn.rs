@@ -3,15 +3,25 @@ use crate::lmdb::env::Env;
 use crate::lmdb::error::lmdb_result;
 use core::ptr;
 use lmdb_sys as ffi;
+use std::sync::RwLockReadGuard;
 
 pub struct Txn<'env> {
     pub(crate) txn: *mut ffi::MDB_txn,
     env: &'env Env,
+    _resize_guard: RwLockReadGuard<'env, ()>,
 }
 
 impl<'env> Txn<'env> {
-    pub(crate) fn new(txn: *mut ffi::MDB_txn, env: &'env Env) -> Self {
-        Txn { txn, env }
+    pub(crate) fn new(
+        txn: *mut ffi::MDB_txn,
+        env: &'env Env,
+        resize_guard: RwLockReadGuard<'env, ()>,
+    ) -> Self {
+        Txn {
+            txn,
+            env,
+            _resize_guard: resize_guard,
+        }
     }
 
     pub fn commit(mut self) -> Result<()> {
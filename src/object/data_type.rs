@@ -32,6 +32,7 @@ pub enum DataType {
     // Offset List alignment 8
     // Element Alignment 1
     StringList = 11,
+    BytesList = 12,
 }
 
 impl DataType {
@@ -46,6 +47,10 @@ impl DataType {
         !self.is_static()
     }
 
+    pub fn is_nested_list(&self) -> bool {
+        matches!(&self, DataType::StringList | DataType::BytesList)
+    }
+
     pub fn get_static_size(&self) -> usize {
         match *self {
             DataType::Byte => 1,
@@ -56,10 +61,59 @@ impl DataType {
 
     pub fn get_element_size(&self) -> usize {
         match *self {
-            DataType::String | DataType::ByteList | DataType::StringList => 1,
+            DataType::String | DataType::ByteList | DataType::StringList | DataType::BytesList => 1,
             DataType::IntList | DataType::FloatList => 4,
             DataType::LongList | DataType::DoubleList => 8,
             _ => 0,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::DataType;
+
+    #[test]
+    fn test_get_static_size() {
+        let sizes = vec![
+            (DataType::Byte, 1),
+            (DataType::Int, 4),
+            (DataType::Float, 4),
+            (DataType::Long, 8),
+            (DataType::Double, 8),
+            (DataType::String, 8),
+            (DataType::ByteList, 8),
+            (DataType::IntList, 8),
+            (DataType::FloatList, 8),
+            (DataType::LongList, 8),
+            (DataType::DoubleList, 8),
+            (DataType::StringList, 8),
+            (DataType::BytesList, 8),
+        ];
+        for (data_type, size) in sizes {
+            assert_eq!(data_type.get_static_size(), size, "{:?}", data_type);
+        }
+    }
+
+    #[test]
+    fn test_get_element_size() {
+        let sizes = vec![
+            (DataType::Byte, 0),
+            (DataType::Int, 0),
+            (DataType::Float, 0),
+            (DataType::Long, 0),
+            (DataType::Double, 0),
+            (DataType::String, 1),
+            (DataType::ByteList, 1),
+            (DataType::IntList, 4),
+            (DataType::FloatList, 4),
+            (DataType::LongList, 8),
+            (DataType::DoubleList, 8),
+            (DataType::StringList, 1),
+            (DataType::BytesList, 1),
+        ];
+        for (data_type, size) in sizes {
+            assert_eq!(data_type.get_element_size(), size, "{:?}", data_type);
+        }
+    }
+}
@@ -3,4 +3,6 @@ pub mod object_builder;
 pub mod object_id;
 pub mod object_id_generator;
 pub mod object_info;
+pub mod object_reader;
 pub mod property;
+pub mod property_value;
@@ -1,7 +1,9 @@
+use crate::error::{illegal_arg, Result};
 use crate::object::data_type::DataType;
 use crate::object::object_id::ObjectId;
 use crate::object::object_info::ObjectInfo;
 use crate::object::property::Property;
+use crate::object::property_value::PropertyValue;
 use crate::utils::aligned_vec;
 use std::slice::from_raw_parts;
 
@@ -60,15 +62,52 @@ impl<'a> ObjectBuilder<'a> {
             DataType::LongList => self.write_long_list(None),
             DataType::DoubleList => self.write_double_list(None),
             DataType::StringList => self.write_string_list(None),
+            DataType::BytesList => self.write_bytes_list(None),
         }
     }
 
+    /// Writes `value` to the next property. Fails without writing if there is no next property
+    /// or `value` does not match its type. `Null` can be written to every property.
+    pub fn write_value(&mut self, value: &PropertyValue) -> Result<()> {
+        let property = self.object_info.get_properties().get(self.property_index);
+        let data_type = if let Some(property) = property {
+            property.data_type
+        } else {
+            return illegal_arg("All properties have already been written.");
+        };
+        if value.get_data_type().is_some_and(|t| t != data_type) {
+            return illegal_arg("The value does not match the property type.");
+        }
+        match value {
+            PropertyValue::Null => self.write_null(),
+            PropertyValue::Byte(value) => self.write_byte(*value),
+            PropertyValue::Int(value) => self.write_int(*value),
+            PropertyValue::Float(value) => self.write_float(*value),
+            PropertyValue::Long(value) => self.write_long(*value),
+            PropertyValue::Double(value) => self.write_double(*value),
+            PropertyValue::String(value) => self.write_string(Some(value)),
+            PropertyValue::ByteList(value) => self.write_byte_list(Some(value)),
+            PropertyValue::IntList(value) => self.write_int_list(Some(value)),
+            PropertyValue::FloatList(value) => self.write_float_list(Some(value)),
+            PropertyValue::LongList(value) => self.write_long_list(Some(value)),
+            PropertyValue::DoubleList(value) => self.write_double_list(Some(value)),
+            PropertyValue::StringList(value) => self.write_string_list(Some(value)),
+            PropertyValue::BytesList(value) => self.write_bytes_list(Some(value)),
+        }
+        Ok(())
+    }
+
     pub fn write_byte(&mut self, value: u8) {
         let (offset, data_type) = self.get_next_property();
         assert_eq!(data_type, DataType::Byte);
         self.write_at(offset, &[value]);
     }
 
+    /// Bools are stored in byte properties.
+    pub fn write_bool(&mut self, value: Option<bool>) {
+        self.write_byte(Property::bool_to_byte(value));
+    }
+
     pub fn write_int(&mut self, value: i32) {
         let (offset, data_type) = self.get_next_property();
         assert_eq!(data_type, DataType::Int);
@@ -132,34 +171,96 @@ impl<'a> ObjectBuilder<'a> {
     pub fn write_string_list(&mut self, value: Option<&[Option<&str>]>) {
         let (offset, data_type) = self.get_next_property();
         assert_eq!(data_type, DataType::StringList);
-        self.write_list::<u8>(offset, None);
+        let list = value.map(|list| {
+            list.iter()
+                .map(|str| str.map(|str| str.as_bytes()))
+                .collect::<Vec<_>>()
+        });
+        self.write_nested_list(offset, list.as_deref());
+    }
+
+    pub fn write_bytes_list(&mut self, value: Option<&[Option<&[u8]>]>) {
+        let (offset, data_type) = self.get_next_property();
+        assert_eq!(data_type, DataType::BytesList);
+        self.write_nested_list(offset, value);
+    }
+
+    /// Discards all written properties so the builder can be reused for the next object
+    /// without reallocating its buffer.
+    pub fn reset(&mut self) {
+        self.object.clear();
+        self.property_index = 0;
+        self.dynamic_offset = self.object_info.get_static_size();
     }
 
-    pub fn finish(self) -> ObjectBuilderResult {
-        let object = self.object;
+    /// Like `finish` but resets the builder afterwards instead of consuming it.
+    pub fn build(&mut self) -> ObjectBuilderResult {
+        let object = &self.object;
         let oid_padding = ObjectId::get_size() % 8;
         let end_padding = (8 - (oid_padding + object.len()) % 8) % 8;
 
         let mut aligned = aligned_vec(oid_padding + object.len() + end_padding);
         aligned.resize(oid_padding, 0);
-        aligned.extend_from_slice(&object);
+        aligned.extend_from_slice(object);
         aligned.resize(oid_padding + object.len() + end_padding, 0);
+        self.reset();
         ObjectBuilderResult { object: aligned }
     }
 
+    pub fn finish(mut self) -> ObjectBuilderResult {
+        self.build()
+    }
+
     fn write_list<T>(&mut self, offset: usize, list: Option<&[T]>) {
         if let Some(list) = list {
+            let type_size = std::mem::size_of::<T>();
+            let padding =
+                (type_size - (self.dynamic_offset + ObjectId::get_size()) % type_size) % type_size;
+            self.dynamic_offset += padding;
+            if self.object.len() < self.dynamic_offset {
+                self.object.resize(self.dynamic_offset, 0);
+            }
+
             self.write_at(offset, &(self.dynamic_offset as u32).to_le_bytes());
             self.write_at(offset + 4, &(list.len() as u32).to_le_bytes());
-            let type_size = std::mem::size_of::<T>();
-            let ptr = list.as_ptr() as *const T;
-            let bytes = unsafe { from_raw_parts::<u8>(ptr as *const u8, list.len() * type_size) };
+            let len = std::mem::size_of_val(list);
+            let bytes = unsafe { from_raw_parts::<u8>(list.as_ptr() as *const u8, len) };
             self.write_at(self.dynamic_offset, bytes);
             self.dynamic_offset += bytes.len();
         } else {
             self.write_at(offset, &0u64.to_le_bytes());
         }
     }
+
+    fn write_nested_list(&mut self, offset: usize, value: Option<&[Option<&[u8]>]>) {
+        if let Some(list) = value {
+            // the position table needs to be aligned
+            let padding = (8 - (self.dynamic_offset + ObjectId::get_size()) % 8) % 8;
+            self.dynamic_offset += padding;
+            if self.object.len() < self.dynamic_offset {
+                self.object.resize(self.dynamic_offset, 0);
+            }
+
+            let table_offset = self.dynamic_offset;
+            self.write_at(offset, &(table_offset as u32).to_le_bytes());
+            self.write_at(offset + 4, &(list.len() as u32).to_le_bytes());
+            self.dynamic_offset += list.len() * 8;
+
+            for (i, bytes) in list.iter().enumerate() {
+                let position_offset = table_offset + i * 8;
+                if let Some(bytes) = bytes {
+                    self.write_at(position_offset, &(self.dynamic_offset as u32).to_le_bytes());
+                    self.write_at(position_offset + 4, &(bytes.len() as u32).to_le_bytes());
+                    self.write_at(self.dynamic_offset, bytes);
+                    self.dynamic_offset += bytes.len();
+                } else {
+                    self.write_at(position_offset, &0u64.to_le_bytes());
+                }
+            }
+        } else {
+            self.write_at(offset, &0u64.to_le_bytes());
+        }
+    }
 }
 
 pub struct ObjectBuilderResult {
@@ -174,6 +275,9 @@ impl ObjectBuilderResult {
 
 #[cfg(test)]
 mod tests {
+    use super::ObjectBuilder;
+    use crate::object::property::Property;
+    use crate::object::property_value::PropertyValue;
     use crate::utils::debug::SlicePad;
     use crate::{col, isar};
 
@@ -190,7 +294,7 @@ mod tests {
         builder!(b, oi, Int);
         b.write_int(123);
         let result = b.finish();
-        oi.verify_object(result.as_bytes());
+        oi.verify_object(result.as_bytes()).unwrap();
         assert_eq!(result.as_bytes(), 123i32.to_le_bytes().pad(2, 4))
     }
 
@@ -206,13 +310,13 @@ mod tests {
         builder!(b, oi, Float);
         b.write_float(123.123);
         let result = b.finish();
-        oi.verify_object(result.as_bytes());
+        oi.verify_object(result.as_bytes()).unwrap();
         assert_eq!(result.as_bytes(), 123.123f32.to_le_bytes().pad(2, 4));
 
         builder!(b, oi, Float);
         b.write_float(f32::NAN);
         let result = b.finish();
-        oi.verify_object(result.as_bytes());
+        oi.verify_object(result.as_bytes()).unwrap();
         assert_eq!(result.as_bytes(), f32::NAN.to_le_bytes().pad(2, 4));
     }
 
@@ -228,7 +332,7 @@ mod tests {
         builder!(b, oi, Long);
         b.write_long(123123);
         let result = b.finish();
-        oi.verify_object(result.as_bytes());
+        oi.verify_object(result.as_bytes()).unwrap();
         assert_eq!(result.as_bytes(), 123123i64.to_le_bytes().pad(2, 0))
     }
 
@@ -244,13 +348,13 @@ mod tests {
         builder!(b, oi, Double);
         b.write_double(123.123);
         let result = b.finish();
-        oi.verify_object(result.as_bytes());
+        oi.verify_object(result.as_bytes()).unwrap();
         assert_eq!(result.as_bytes(), 123.123f64.to_le_bytes().pad(2, 0));
 
         builder!(b, oi, Double);
         b.write_double(f64::NAN);
         let result = b.finish();
-        oi.verify_object(result.as_bytes());
+        oi.verify_object(result.as_bytes()).unwrap();
         assert_eq!(result.as_bytes(), f64::NAN.to_le_bytes().pad(2, 0));
     }
 
@@ -267,22 +371,49 @@ mod tests {
         builder!(b, oi, Byte);
         b.write_byte(0);
         let result = b.finish();
-        oi.verify_object(result.as_bytes());
+        oi.verify_object(result.as_bytes()).unwrap();
         assert_eq!(result.as_bytes(), &[0, 0]);
 
         builder!(b, oi, Byte);
         b.write_byte(123);
         let result = b.finish();
-        oi.verify_object(result.as_bytes());
+        oi.verify_object(result.as_bytes()).unwrap();
         assert_eq!(result.as_bytes(), &[123, 0]);
 
         builder!(b, oi, Byte);
         b.write_byte(255);
         let result = b.finish();
-        oi.verify_object(result.as_bytes());
+        oi.verify_object(result.as_bytes()).unwrap();
         assert_eq!(result.as_bytes(), &[255, 0]);
     }
 
+    #[test]
+    pub fn test_write_bool() {
+        let values = [
+            (None, Property::NULL_BOOL),
+            (Some(false), Property::FALSE_BOOL),
+            (Some(true), Property::TRUE_BOOL),
+        ];
+        for (value, byte) in values.iter() {
+            builder!(b, oi, Byte);
+            b.write_bool(*value);
+            let result = b.finish();
+            oi.verify_object(result.as_bytes()).unwrap();
+            assert_eq!(result.as_bytes(), &[*byte, 0]);
+
+            let property = oi.get_properties().first().unwrap();
+            assert_eq!(property.is_null(result.as_bytes()), value.is_none());
+            assert_eq!(property.get_bool(result.as_bytes()), *value);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_write_bool_wrong_type() {
+        builder!(b, _oi, Int);
+        b.write_bool(Some(true));
+    }
+
     #[test]
     #[should_panic]
     pub fn test_write_byte_wrong_type() {
@@ -291,28 +422,279 @@ mod tests {
     }
 
     #[test]
-    pub fn test_write_multiple_static_types() {
-        /*builder!(
-            b,
-            Property::new( DataType::Int, 0),
-            Property::new( DataType::Int, 8),
-            Property::new( DataType::Double, 16),
-            Property::new( DataType::Bool, 24),
-            Property::new( DataType::Double, 25)
+    pub fn test_write_string_list() {
+        builder!(b, oi, StringList);
+        b.write_string_list(None);
+        let result = b.finish();
+        assert!(oi.verify_object(result.as_bytes()).is_ok());
+        assert_eq!(result.as_bytes(), &[0; 8].pad(2, 0));
+
+        builder!(b, oi, StringList);
+        b.write_string_list(Some(&[]));
+        let result = b.finish();
+        assert!(oi.verify_object(result.as_bytes()).is_ok());
+        let property = oi.get_properties().first().unwrap();
+        assert_eq!(property.get_string_list(result.as_bytes()), Some(vec![]));
+
+        builder!(b, oi, StringList);
+        b.write_string_list(Some(&[Some("hello"), None, Some(""), Some("isar")]));
+        let result = b.finish();
+        assert!(oi.verify_object(result.as_bytes()).is_ok());
+        let property = oi.get_properties().first().unwrap();
+        assert_eq!(
+            property.get_string_list(result.as_bytes()),
+            Some(vec![Some("hello"), None, Some(""), Some("isar")])
+        );
+
+        let mut bytes = vec![0, 0, 10, 0, 0, 0, 4, 0, 0, 0];
+        bytes.extend_from_slice(&[42, 0, 0, 0, 5, 0, 0, 0]);
+        bytes.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0]);
+        bytes.extend_from_slice(&[47, 0, 0, 0, 0, 0, 0, 0]);
+        bytes.extend_from_slice(&[47, 0, 0, 0, 4, 0, 0, 0]);
+        bytes.extend_from_slice(b"helloisar");
+        bytes.extend_from_slice(&[0; 7]);
+        assert_eq!(result.as_bytes(), &bytes[..]);
+    }
+
+    #[test]
+    pub fn test_write_string_list_after_string() {
+        isar!(isar, col => col!(f1 => String, f2 => StringList));
+        let mut b = col.get_object_builder();
+        b.write_string(Some("abc"));
+        b.write_string_list(Some(&[None, Some("d")]));
+        let result = b.finish();
+        let oi = col.debug_get_object_info();
+        assert!(oi.verify_object(result.as_bytes()).is_ok());
+
+        let mut reader = col.get_object_reader(result.as_bytes());
+        assert_eq!(reader.read_string(), Some("abc"));
+        assert_eq!(reader.read_string_list(), Some(vec![None, Some("d")]));
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_write_string_list_wrong_type() {
+        builder!(b, _oi, String);
+        b.write_string_list(None);
+    }
+
+    #[test]
+    pub fn test_write_bytes_list() {
+        builder!(b, oi, BytesList);
+        b.write_null();
+        let result = b.finish();
+        assert!(oi.verify_object(result.as_bytes()).is_ok());
+        let property = oi.get_properties().first().unwrap();
+        assert_eq!(property.get_bytes_list(result.as_bytes()), None);
+
+        builder!(b, oi, BytesList);
+        b.write_bytes_list(Some(&[Some(&[1, 2, 3]), None, Some(&[]), Some(&[4])]));
+        let result = b.finish();
+        assert!(oi.verify_object(result.as_bytes()).is_ok());
+        let property = oi.get_properties().first().unwrap();
+        assert_eq!(
+            property.get_bytes_list(result.as_bytes()),
+            Some(vec![
+                Some(&[1u8, 2, 3][..]),
+                None,
+                Some(&[][..]),
+                Some(&[4][..])
+            ])
+        );
+    }
+
+    #[test]
+    pub fn test_write_bytes_list_after_byte_list() {
+        isar!(isar, col => col!(f1 => ByteList, f2 => BytesList));
+        let mut b = col.get_object_builder();
+        b.write_byte_list(Some(&[1, 2, 3]));
+        b.write_bytes_list(Some(&[None, Some(&[4, 5])]));
+        let result = b.finish();
+        let oi = col.debug_get_object_info();
+        assert!(oi.verify_object(result.as_bytes()).is_ok());
+
+        let mut reader = col.get_object_reader(result.as_bytes());
+        assert_eq!(reader.read_byte_list(), Some(&[1u8, 2, 3][..]));
+        assert_eq!(
+            reader.read_bytes_list(),
+            Some(vec![None, Some(&[4u8, 5][..])])
         );
+    }
+
+    #[test]
+    pub fn test_write_int_list_after_byte_list() {
+        isar!(isar, col => col!(f1 => ByteList, f2 => IntList, f3 => LongList));
+        let mut b = col.get_object_builder();
+        b.write_byte_list(Some(&[1, 2, 3]));
+        b.write_int_list(Some(&[4, 5]));
+        b.write_long_list(Some(&[6]));
+        let result = b.finish();
+        let oi = col.debug_get_object_info();
+        assert!(oi.verify_object(result.as_bytes()).is_ok());
+
+        let mut reader = col.get_object_reader(result.as_bytes());
+        assert_eq!(reader.read_byte_list(), Some(&[1u8, 2, 3][..]));
+        assert_eq!(reader.read_int_list(), Some(&[4, 5][..]));
+        assert_eq!(reader.read_long_list(), Some(&[6i64][..]));
+    }
+
+    #[test]
+    pub fn test_write_value() {
+        isar!(isar, col => col!(f1 => Byte, f2 => Int, f3 => Float, f4 => Long, f5 => Double,
+            f6 => String, f7 => ByteList, f8 => IntList, f9 => FloatList, f10 => LongList,
+            f11 => DoubleList, f12 => StringList, f13 => BytesList));
+        let values = vec![
+            PropertyValue::Byte(1),
+            PropertyValue::Int(2),
+            PropertyValue::Float(3.5),
+            PropertyValue::Long(4),
+            PropertyValue::Double(5.5),
+            PropertyValue::String("six"),
+            PropertyValue::ByteList(&[7, 7]),
+            PropertyValue::IntList(&[8]),
+            PropertyValue::FloatList(&[9.5]),
+            PropertyValue::LongList(&[10, 10]),
+            PropertyValue::DoubleList(&[11.5]),
+            PropertyValue::StringList(vec![Some("twelve"), None]),
+            PropertyValue::Null,
+        ];
+        let mut b = col.get_object_builder();
+        for value in &values {
+            b.write_value(value).unwrap();
+        }
+        assert!(b.write_value(&PropertyValue::Null).is_err());
+        let result = b.finish();
+        assert!(col
+            .debug_get_object_info()
+            .verify_object(result.as_bytes())
+            .is_ok());
+
+        for (property, value) in col.get_properties().iter().zip(values) {
+            assert_eq!(PropertyValue::read(property, result.as_bytes()), value);
+        }
+    }
+
+    #[test]
+    pub fn test_write_value_wrong_type() {
+        isar!(isar, col => col!(f1 => Int, f2 => String));
+        let mut b = col.get_object_builder();
+        assert!(b.write_value(&PropertyValue::Long(1)).is_err());
+        b.write_value(&PropertyValue::Int(1)).unwrap();
+        assert!(b.write_value(&PropertyValue::IntList(&[1])).is_err());
+        b.write_value(&PropertyValue::Null).unwrap();
+
+        let result = b.finish();
+        let mut reader = col.get_object_reader(result.as_bytes());
+        assert_eq!(reader.read_int(), 1);
+        assert_eq!(reader.read_string(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_write_bytes_list_wrong_type() {
+        builder!(b, _oi, StringList);
+        b.write_bytes_list(None);
+    }
+
+    #[test]
+    pub fn test_reuse_builder() {
+        isar!(isar, col => col!(f1 => Int, f2 => String, f3 => IntList));
+        let objects = vec![
+            (1, Some("a long string value"), Some(vec![1, 2, 3])),
+            (2, Some("short"), None),
+            (3, None, Some(vec![4])),
+            (4, Some(""), Some(vec![])),
+        ];
+        let write = |b: &mut ObjectBuilder, (int, string, list): &(i32, _, Option<Vec<i32>>)| {
+            b.write_int(*int);
+            b.write_string(*string);
+            b.write_int_list(list.as_deref());
+        };
+
+        let mut reused = col.get_object_builder();
+        reused.write_int(5);
+        reused.reset();
+        for object in &objects {
+            write(&mut reused, object);
+            let result = reused.build();
+
+            let mut fresh = col.get_object_builder();
+            write(&mut fresh, object);
+            assert_eq!(result.as_bytes(), fresh.finish().as_bytes());
+        }
+    }
 
-        b.write_int(i64::MAX);
-        b.write_long(i64::MIN);
-        b.write_double(consts::PI);
-        b.write_bool(None);
-        b.write_float(consts::E);
+    #[test]
+    pub fn test_verify_list_objects() {
+        macro_rules! verify_list {
+            ($type:ident, $write:ident, $value:expr) => {
+                isar!(isar, col => col!(f1 => Byte, f2 => $type));
+                let oi = col.debug_get_object_info();
+                for value in vec![None, Some($value)] {
+                    let mut b = col.get_object_builder();
+                    b.write_byte(1);
+                    b.$write(value);
+                    let result = b.finish();
+                    let bytes = result.as_bytes();
+                    assert!(oi.verify_object(bytes).is_ok(), "{}", stringify!($type));
+
+                    let mut buffer = vec![0u8; bytes.len() + 8];
+                    let shift =
+                        (bytes.as_ptr() as usize + 1).wrapping_sub(buffer.as_ptr() as usize) % 8;
+                    buffer[shift..shift + bytes.len()].copy_from_slice(bytes);
+                    let misaligned = &buffer[shift..shift + bytes.len()];
+                    assert!(oi.verify_object(misaligned).is_err(), "{}", stringify!($type));
+                }
+            };
+        }
 
-        let mut bytes = i64::MAX.to_le_bytes().to_vec();
-        bytes.extend_from_slice(&i64::MIN.to_le_bytes());
-        bytes.extend_from_slice(&consts::PI.to_le_bytes());
-        bytes.push(Property::NULL_BOOL);
-        bytes.extend_from_slice(&consts::E.to_le_bytes());
+        verify_list!(ByteList, write_byte_list, &[1, 2, 3][..]);
+        verify_list!(IntList, write_int_list, &[1, -2, 3][..]);
+        verify_list!(FloatList, write_float_list, &[1.0, -2.5][..]);
+        verify_list!(LongList, write_long_list, &[1, i64::MIN, i64::MAX][..]);
+        verify_list!(DoubleList, write_double_list, &[1.0, -2.5][..]);
+        verify_list!(
+            StringList,
+            write_string_list,
+            &[Some("a"), None, Some("bcd")][..]
+        );
+        verify_list!(
+            BytesList,
+            write_bytes_list,
+            &[Some(&[1u8][..]), None, Some(&[])][..]
+        );
+    }
 
-        assert_eq!(b.to_bytes(), bytes);*/
+    #[test]
+    pub fn test_write_mixed_types() {
+        isar!(isar, col => col!(b => Byte, i => Int, l => Long, s => String, il => IntList));
+        let mut ob = col.get_object_builder();
+        ob.write_value(&PropertyValue::Byte(5)).unwrap();
+        ob.write_value(&PropertyValue::Int(123)).unwrap();
+        ob.write_value(&PropertyValue::Long(-1)).unwrap();
+        ob.write_value(&PropertyValue::String("ab")).unwrap();
+        ob.write_value(&PropertyValue::IntList(&[1, 2])).unwrap();
+        let result = ob.finish();
+
+        let mut expected = vec![5, 0];
+        expected.extend_from_slice(&123i32.to_le_bytes());
+        expected.extend_from_slice(&[0; 4]);
+        expected.extend_from_slice(&(-1i64).to_le_bytes());
+        expected.extend_from_slice(&34u32.to_le_bytes());
+        expected.extend_from_slice(&2u32.to_le_bytes());
+        expected.extend_from_slice(&38u32.to_le_bytes());
+        expected.extend_from_slice(&2u32.to_le_bytes());
+        expected.extend_from_slice(b"ab");
+        expected.extend_from_slice(&[0; 2]);
+        expected.extend_from_slice(&1i32.to_le_bytes());
+        expected.extend_from_slice(&2i32.to_le_bytes());
+        assert_eq!(result.as_bytes(), &expected.pad(0, 4)[..]);
+
+        let mut reader = col.get_object_reader(result.as_bytes());
+        assert_eq!(reader.read_byte(), 5);
+        assert_eq!(reader.read_int(), 123);
+        assert_eq!(reader.read_long(), -1);
+        assert_eq!(reader.read_string(), Some("ab"));
+        assert_eq!(reader.read_int_list(), Some(&[1, 2][..]));
     }
 }
@@ -1,4 +1,7 @@
+use crate::error::{IsarError, Result};
+use std::convert::TryInto;
 use std::mem;
+use std::str::FromStr;
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
 #[repr(packed)]
@@ -6,7 +9,7 @@ pub struct ObjectId {
     prefix: u16,
     time: u32,    // big endian
     counter: u32, // big endian
-    rand: u32,
+    rand: u32,    // little endian
 }
 
 impl ObjectId {
@@ -19,7 +22,7 @@ impl ObjectId {
             prefix,
             time: time.to_be(),
             counter: counter.to_be(),
-            rand,
+            rand: rand.to_le(),
         }
     }
 
@@ -41,7 +44,20 @@ impl ObjectId {
     }
 
     pub fn get_rand(&self) -> u32 {
-        self.rand
+        u32::from_le(self.rand)
+    }
+
+    /// Parses an id formatted by `to_string()` and sets the collection `prefix` that is not part
+    /// of the string.
+    pub fn from_str_for(s: &str, prefix: u16) -> Result<Self> {
+        let bytes = hex::decode(s).map_err(|_| IsarError::InvalidObjectId {})?;
+        if bytes.len() != ObjectId::get_size() - 2 {
+            return Err(IsarError::InvalidObjectId {});
+        }
+        let time = u32::from_be_bytes(bytes[0..4].try_into().unwrap());
+        let counter = u32::from_be_bytes(bytes[4..8].try_into().unwrap());
+        let rand = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+        Ok(ObjectId::new(prefix, time, counter, rand))
     }
 
     #[inline]
@@ -55,6 +71,16 @@ impl ObjectId {
         &bytes
     }
 
+    /// Formats the id stored in `bytes` like `to_string()`. Bytes of the wrong length are hex
+    /// encoded as a whole.
+    pub(crate) fn format_bytes(bytes: &[u8]) -> String {
+        if bytes.len() == Self::get_size() {
+            Self::from_bytes(bytes).to_string()
+        } else {
+            hex::encode(bytes)
+        }
+    }
+
     #[inline]
     pub(crate) fn as_bytes_without_prefix(&self) -> &[u8] {
         &self.as_bytes()[2..]
@@ -67,8 +93,20 @@ impl ToString for ObjectId {
     }
 }
 
+impl FromStr for ObjectId {
+    type Err = IsarError;
+
+    /// Parses an id formatted by `to_string()`. The prefix is not part of the string so the
+    /// returned id has the prefix `0` and cannot be used with a collection. Use `from_str_for()`
+    /// to parse the id of a collection.
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_str_for(s, 0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
 
     #[test]
     fn test_as_bytes() {
@@ -78,4 +116,45 @@ mod tests {
             &[99, 0, 0, 0, 0, 123, 222, 0, 0, 0, 0, 0, 0, 0]
         )*/
     }
+
+    #[test]
+    fn test_byte_order() {
+        let oid = ObjectId::new(0x0102, 0x03040506, 0x0708090a, 0x0b0c0d0e);
+        assert_eq!(
+            oid.as_bytes(),
+            &[2, 1, 3, 4, 5, 6, 7, 8, 9, 10, 14, 13, 12, 11]
+        );
+        assert_eq!(oid.get_prefix(), 0x0102);
+        assert_eq!(oid.get_time(), 0x03040506);
+        assert_eq!(oid.get_counter(), 0x0708090a);
+        assert_eq!(oid.get_rand(), 0x0b0c0d0e);
+    }
+
+    #[test]
+    fn test_from_str() {
+        let oid = ObjectId::new(0, 123456, 42, 987654321);
+        assert_eq!(ObjectId::from_str(&oid.to_string()).unwrap(), oid);
+
+        let oid = ObjectId::new(0, u32::MAX, 0, u32::MAX);
+        assert_eq!(ObjectId::from_str(&oid.to_string()).unwrap(), oid);
+    }
+
+    #[test]
+    fn test_from_str_for() {
+        let oid = ObjectId::new(5, 123456, 42, 987654321);
+        let parsed = ObjectId::from_str_for(&oid.to_string(), 5).unwrap();
+        assert_eq!(parsed, oid);
+        assert_eq!(parsed.get_prefix(), 5);
+    }
+
+    #[test]
+    fn test_from_str_invalid() {
+        let oid = ObjectId::new(0, 1, 2, 3).to_string();
+        for invalid in &["", "xyz", &oid[1..], &oid[2..], &format!("{}00", oid)] {
+            assert!(matches!(
+                ObjectId::from_str(invalid),
+                Err(IsarError::InvalidObjectId {})
+            ));
+        }
+    }
 }
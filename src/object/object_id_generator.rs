@@ -3,6 +3,12 @@ use crate::utils::seconds_since_epoch;
 use rand::random;
 use std::sync::atomic::{AtomicU32, Ordering};
 
+/// Generates ids from the current time in seconds, a counter and a random value.
+///
+/// The counter is seeded randomly with at least 2^31 increments left before it wraps around, so
+/// ids generated by one generator are unique and increasing unless it creates more than 2^31
+/// ids. Ids of different generators or process restarts only collide if time, counter and the
+/// random value all match.
 pub struct ObjectIdGenerator {
     prefix: u16,
     counter: AtomicU32,
@@ -14,7 +20,7 @@ impl ObjectIdGenerator {
     pub fn new(prefix: u16) -> Self {
         ObjectIdGenerator {
             prefix,
-            counter: AtomicU32::new(random()),
+            counter: AtomicU32::new(random::<u32>() >> 1),
             time: seconds_since_epoch,
             random: rand::random,
         }
@@ -65,4 +71,17 @@ mod tests {
         assert_eq!(oid.get_counter(), 102);
         assert_eq!(oid.get_rand(), 100);
     }
+
+    #[test]
+    fn test_generate_unique_and_increasing() {
+        let oidg = ObjectIdGenerator::new(1);
+        let oids = (0..100000).map(|_| oidg.generate()).collect::<Vec<_>>();
+
+        for (oid1, oid2) in oids.iter().zip(oids.iter().skip(1)) {
+            assert!(oid1.as_bytes() < oid2.as_bytes());
+            if oid1.get_time() == oid2.get_time() {
+                assert_eq!(oid1.get_counter() + 1, oid2.get_counter());
+            }
+        }
+    }
 }
@@ -1,3 +1,4 @@
+use crate::error::{illegal_arg, invalid_object, IsarError, Result};
 use crate::object::data_type::DataType;
 use crate::object::object_id::ObjectId;
 use crate::object::property::Property;
@@ -31,110 +32,186 @@ impl ObjectInfo {
         &self.properties
     }
 
-    pub fn entry_to_json(&self, key: &[u8], object: &[u8], primitive_null: bool) -> Value {
+    pub fn get_property_names(&self) -> Vec<&str> {
+        self.properties.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    pub fn get_property_types(&self) -> Vec<DataType> {
+        self.properties.iter().map(|p| p.data_type).collect()
+    }
+
+    /// Null strings, including null elements of string lists, are written as
+    /// `emit_null_strings_as` if it is set. Fails with `IllegalArg` if a non-null string is equal
+    /// to `emit_null_strings_as` because it would be imported as null and with `DbCorrupted` if
+    /// a list or string of the object is invalid.
+    pub fn entry_to_json(
+        &self,
+        key: &[u8],
+        object: &[u8],
+        primitive_null: bool,
+        emit_null_strings_as: Option<&str>,
+    ) -> Result<Value> {
         let mut object_map = Map::new();
 
         let oid = ObjectId::from_bytes(key);
         object_map.insert("id".to_string(), json!(oid.to_string()));
 
         for property in &self.properties {
-            let value =
-                if primitive_null && property.data_type.is_static() && property.is_null(object) {
-                    Value::Null
-                } else {
-                    match property.data_type {
-                        DataType::Byte => json!(property.get_byte(object)),
-                        DataType::Int => json!(property.get_int(object)),
-                        DataType::Float => json!(property.get_float(object)),
-                        DataType::Long => json!(property.get_long(object)),
-                        DataType::Double => json!(property.get_double(object)),
-                        DataType::String => json!(property.get_string(object)),
-                        DataType::ByteList => json!(property.get_byte_list(object)),
-                        DataType::IntList => json!(property.get_int_list(object)),
-                        DataType::FloatList => json!(property.get_float_list(object)),
-                        DataType::LongList => json!(property.get_float_list(object)),
-                        DataType::DoubleList => json!(property.get_double_list(object)),
-                        DataType::StringList => json!(property.get_string_list(object)),
+            let value = if primitive_null
+                && property.data_type == DataType::Byte
+                && property.is_null(object)
+            {
+                Value::Null
+            } else {
+                match property.data_type {
+                    DataType::Byte => json!(property.get_byte(object)),
+                    DataType::Int if primitive_null => json!(property.get_int_opt(object)),
+                    DataType::Int => json!(property.get_int(object)),
+                    DataType::Float if primitive_null => json!(property.get_float_opt(object)),
+                    DataType::Float => json!(property.get_float(object)),
+                    DataType::Long if primitive_null => json!(property.get_long_opt(object)),
+                    DataType::Long => json!(property.get_long(object)),
+                    DataType::Double if primitive_null => {
+                        json!(property.get_double_opt(object))
                     }
-                };
+                    DataType::Double => json!(property.get_double(object)),
+                    DataType::String => {
+                        let value = property.try_get_string(object)?;
+                        Self::check_null_string(value, emit_null_strings_as)?;
+                        json!(value.or(emit_null_strings_as))
+                    }
+                    DataType::ByteList => json!(property.try_get_byte_list(object)?),
+                    DataType::IntList => json!(property.try_get_int_list(object)?),
+                    DataType::FloatList => json!(property.try_get_float_list(object)?),
+                    DataType::LongList => json!(property.try_get_long_list(object)?),
+                    DataType::DoubleList => json!(property.try_get_double_list(object)?),
+                    DataType::StringList => {
+                        let list = property
+                            .try_get_string_list(object)?
+                            .map(|list| {
+                                list.into_iter()
+                                    .map(|s| {
+                                        Self::check_null_string(s, emit_null_strings_as)?;
+                                        Ok(s.or(emit_null_strings_as))
+                                    })
+                                    .collect::<Result<Vec<_>>>()
+                            })
+                            .transpose()?;
+                        json!(list)
+                    }
+                    DataType::BytesList => json!(property.try_get_bytes_list(object)?),
+                }
+            };
             object_map.insert(property.name.clone(), value);
         }
-        json!(object_map)
+        Ok(json!(object_map))
     }
 
-    pub fn verify_object(&self, object: &[u8]) -> bool {
+    fn check_null_string(value: Option<&str>, emit_null_strings_as: Option<&str>) -> Result<()> {
+        if value.is_some() && value == emit_null_strings_as {
+            illegal_arg("A string is equal to emit_null_strings_as and would be imported as null.")
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Verifies an object that was read from the database. Invalid objects are reported as
+    /// `DbCorrupted` instead of `InvalidObject`.
+    pub(crate) fn verify_stored_object(&self, object: &[u8]) -> Result<()> {
+        self.verify_object(object)
+            .map_err(|e| IsarError::DbCorrupted {
+                message: e.to_string(),
+                source: Some(Box::new(e)),
+            })
+    }
+
+    /// Checks that `object` is aligned and laid out like the builder would write it. The error
+    /// describes the first problem found.
+    pub fn verify_object(&self, object: &[u8]) -> Result<()> {
         let alignment = object.as_ref().as_ptr() as usize - ObjectId::get_size();
         if alignment % 8 != 0 {
-            return false;
+            return invalid_object("The object is not aligned.");
         }
-        let check_padding = |index: usize, count: usize| -> bool {
+        let check_padding = |index: usize, count: usize| -> Result<()> {
             if object.len() < index + count {
-                return false;
+                return invalid_object("The object is too short.");
             }
-            for padding_byte in &object[index..index + count] {
-                if *padding_byte != 0 {
-                    return false;
-                }
+            if object[index..index + count].iter().any(|b| *b != 0) {
+                return invalid_object("The padding contains non-zero bytes.");
             }
-            true
+            Ok(())
         };
 
         if (ObjectId::get_size() + object.len()) % 8 != 0 {
-            return false;
+            return invalid_object("The object length is not aligned.");
         }
 
         let mut static_offset = 0;
         let mut dynamic_offset = self.static_size;
         for property in &self.properties {
             let required_padding = property.offset - static_offset;
-            if !check_padding(static_offset, required_padding) {
-                return false;
+            check_padding(static_offset, required_padding)?;
+            static_offset += required_padding + property.data_type.get_static_size();
+            if object.len() < static_offset {
+                return invalid_object("The object is too short.");
             }
-            static_offset += required_padding;
 
-            if property.offset != static_offset {
-                return false;
-            }
-            static_offset += property.data_type.get_static_size();
-
-            if property.data_type.is_dynamic() && !property.is_null(object) {
+            let hole = || {
+                let message = format!("Hole in the dynamic data of property {}.", property.name);
+                invalid_object(&message)
+            };
+            if property.data_type.is_nested_list() && !property.is_null(object) {
                 let pos = property.get_dynamic_position(object).unwrap();
-                let alignment_wrong = (dynamic_offset + ObjectId::get_size())
-                    % property.data_type.get_element_size()
-                    != 0;
-                if pos.offset as usize != dynamic_offset || alignment_wrong {
-                    return false;
+                let table_padding = (8 - (dynamic_offset + ObjectId::get_size()) % 8) % 8;
+                check_padding(dynamic_offset, table_padding)?;
+                dynamic_offset += table_padding;
+                if pos.offset as usize != dynamic_offset {
+                    return hole();
+                }
+                dynamic_offset += pos.length as usize * 8;
+                if object.len() < dynamic_offset {
+                    return invalid_object("The object is too short.");
                 }
 
-                if property.data_type == DataType::StringList {
-                    let list_positions = property.get_dynamic_positions(object).unwrap();
-                    let last_with_length = list_positions.iter().rev().find(|p| p.length != 0);
-                    if let Some(last_pos) = last_with_length {
-                        dynamic_offset += last_pos.length as usize;
+                let list_positions = property.get_dynamic_positions(object).unwrap();
+                for list_pos in list_positions.iter().filter(|p| !p.is_null()) {
+                    if list_pos.offset as usize != dynamic_offset {
+                        return hole();
                     }
-                } else {
-                    dynamic_offset += pos.length as usize * property.data_type.get_element_size();
+                    dynamic_offset += list_pos.length as usize;
                 }
+            } else if property.data_type.is_dynamic() && !property.is_null(object) {
+                let pos = property.get_dynamic_position(object).unwrap();
+                let element_size = property.data_type.get_element_size();
+                let padding = (element_size
+                    - (dynamic_offset + ObjectId::get_size()) % element_size)
+                    % element_size;
+                check_padding(dynamic_offset, padding)?;
+                dynamic_offset += padding;
+                if pos.offset as usize != dynamic_offset {
+                    return hole();
+                }
+                dynamic_offset += pos.length as usize * element_size;
             }
         }
 
-        if static_offset != self.static_size {
-            return false;
-        }
-
         let required_padding = (8 - (dynamic_offset + ObjectId::get_size()) % 8) % 8;
-        if !check_padding(dynamic_offset, required_padding as usize) {
-            return false;
-        }
+        check_padding(dynamic_offset, required_padding)?;
 
-        dynamic_offset + required_padding == object.len()
+        if dynamic_offset + required_padding != object.len() {
+            return invalid_object("The object is too long.");
+        }
+        Ok(())
     }
 }
+
 #[cfg(test)]
 mod tests {
+    use crate::error::IsarError;
     use crate::object::data_type::DataType;
     use crate::object::object_info::ObjectInfo;
     use crate::object::property::Property;
+    use crate::utils::debug::align;
 
     #[test]
     fn test_calculate_static_size() {
@@ -153,6 +230,15 @@ mod tests {
         assert_eq!(ObjectInfo::calculate_static_size(&properties2), 17);
     }
 
+    #[test]
+    fn test_verify_stored_object_misaligned() {
+        let oi = ObjectInfo::new(vec![Property::new_debug(DataType::IntList, 0)]);
+        // the object is not preceded by an ObjectId so the list is misaligned
+        let bytes = align(&[8, 0, 0, 0, 1, 0, 0, 0, 5, 0, 0, 0, 0, 0, 0, 0]);
+        let result = oi.verify_stored_object(&bytes);
+        assert!(matches!(result, Err(IsarError::DbCorrupted { .. })));
+    }
+
     #[test]
     fn test_verify_object() {
         /*let oi = ObjectInfo::new(vec![Property::new(DataType::Bool, 0)]);
@@ -0,0 +1,188 @@
+use crate::object::object_info::ObjectInfo;
+use crate::object::property::Property;
+
+pub struct ObjectReader<'a> {
+    object: &'a [u8],
+    object_info: &'a ObjectInfo,
+    property_index: usize,
+}
+
+impl<'a> ObjectReader<'a> {
+    pub(crate) fn new(object_info: &'a ObjectInfo, object: &'a [u8]) -> ObjectReader<'a> {
+        ObjectReader {
+            object,
+            object_info,
+            property_index: 0,
+        }
+    }
+
+    fn get_next_property(&mut self) -> &'a Property {
+        let property = self
+            .object_info
+            .get_properties()
+            .get(self.property_index)
+            .unwrap();
+        self.property_index += 1;
+        property
+    }
+
+    /// Checks whether the next property is null without advancing the reader.
+    pub fn is_null(&self) -> bool {
+        let property = self
+            .object_info
+            .get_properties()
+            .get(self.property_index)
+            .unwrap();
+        property.is_null(self.object)
+    }
+
+    pub fn read_byte(&mut self) -> u8 {
+        self.get_next_property().get_byte(self.object)
+    }
+
+    pub fn read_bool(&mut self) -> Option<bool> {
+        self.get_next_property().get_bool(self.object)
+    }
+
+    pub fn read_int(&mut self) -> i32 {
+        self.get_next_property().get_int(self.object)
+    }
+
+    pub fn read_float(&mut self) -> f32 {
+        self.get_next_property().get_float(self.object)
+    }
+
+    pub fn read_long(&mut self) -> i64 {
+        self.get_next_property().get_long(self.object)
+    }
+
+    pub fn read_double(&mut self) -> f64 {
+        self.get_next_property().get_double(self.object)
+    }
+
+    pub fn read_string(&mut self) -> Option<&'a str> {
+        self.get_next_property().get_string(self.object)
+    }
+
+    pub fn read_byte_list(&mut self) -> Option<&'a [u8]> {
+        self.get_next_property().get_byte_list(self.object)
+    }
+
+    pub fn read_int_list(&mut self) -> Option<&'a [i32]> {
+        self.get_next_property().get_int_list(self.object)
+    }
+
+    pub fn read_float_list(&mut self) -> Option<&'a [f32]> {
+        self.get_next_property().get_float_list(self.object)
+    }
+
+    pub fn read_long_list(&mut self) -> Option<&'a [i64]> {
+        self.get_next_property().get_long_list(self.object)
+    }
+
+    pub fn read_double_list(&mut self) -> Option<&'a [f64]> {
+        self.get_next_property().get_double_list(self.object)
+    }
+
+    pub fn read_string_list(&mut self) -> Option<Vec<Option<&'a str>>> {
+        self.get_next_property().get_string_list(self.object)
+    }
+
+    pub fn read_bytes_list(&mut self) -> Option<Vec<Option<&'a [u8]>>> {
+        self.get_next_property().get_bytes_list(self.object)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object::property::Property;
+    use crate::{col, isar};
+
+    macro_rules! round_trip {
+        ($type:ident, $write:ident, $read:ident, $value:expr) => {
+            isar!(isar, col => col!(field => $type));
+            let mut builder = col.get_object_builder();
+            builder.$write($value);
+            let object = builder.finish();
+            let mut reader = col.get_object_reader(object.as_bytes());
+            assert_eq!(reader.$read(), $value);
+        };
+    }
+
+    #[test]
+    fn test_read_static_types() {
+        round_trip!(Byte, write_byte, read_byte, 123);
+        round_trip!(Byte, write_bool, read_bool, Some(true));
+        round_trip!(Byte, write_bool, read_bool, None);
+        round_trip!(Int, write_int, read_int, -123456);
+        round_trip!(Float, write_float, read_float, 123.456f32);
+        round_trip!(Long, write_long, read_long, i64::MAX);
+        round_trip!(Double, write_double, read_double, -123.456f64);
+    }
+
+    #[test]
+    fn test_read_dynamic_types() {
+        round_trip!(String, write_string, read_string, Some("hello"));
+        round_trip!(String, write_string, read_string, None);
+        round_trip!(
+            ByteList,
+            write_byte_list,
+            read_byte_list,
+            Some(&[1u8, 2, 3][..])
+        );
+        round_trip!(
+            IntList,
+            write_int_list,
+            read_int_list,
+            Some(&[1, -2, 3][..])
+        );
+        round_trip!(
+            FloatList,
+            write_float_list,
+            read_float_list,
+            Some(&[1.5f32][..])
+        );
+        round_trip!(
+            LongList,
+            write_long_list,
+            read_long_list,
+            Some(&[i64::MIN][..])
+        );
+        round_trip!(
+            DoubleList,
+            write_double_list,
+            read_double_list,
+            Some(&[0.5, 1.5][..])
+        );
+        round_trip!(DoubleList, write_double_list, read_double_list, None);
+    }
+
+    #[test]
+    fn test_read_multiple_properties() {
+        isar!(isar, col => col!(f1 => Byte, f2 => Int, f3 => Long, f4 => String));
+        let mut builder = col.get_object_builder();
+        builder.write_byte(5);
+        builder.write_null();
+        builder.write_long(123);
+        builder.write_string(Some("isar"));
+        let object = builder.finish();
+
+        let mut reader = col.get_object_reader(object.as_bytes());
+        assert_eq!(reader.read_byte(), 5);
+        assert!(reader.is_null());
+        assert_eq!(reader.read_int(), Property::NULL_INT);
+        assert!(!reader.is_null());
+        assert_eq!(reader.read_long(), 123);
+        assert_eq!(reader.read_string(), Some("isar"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_read_wrong_type() {
+        isar!(isar, col => col!(field => Int));
+        let mut builder = col.get_object_builder();
+        builder.write_int(123);
+        let object = builder.finish();
+        col.get_object_reader(object.as_bytes()).read_long();
+    }
+}
@@ -1,5 +1,7 @@
+use crate::error::{IsarError, Result};
 use crate::object::data_type::DataType;
-use itertools::Itertools;
+use crate::option;
+use std::cmp::Ordering;
 use std::convert::TryInto;
 use std::hash::Hasher;
 use std::{mem, slice};
@@ -44,6 +46,15 @@ string_length: u32 number of BYTES OR 0 for null string
 bytes_offset: u32 (relative to beginning) OR 0 for null bytes
 bytes_length: u32 number of bytes OR 0 for null bytes
 
+string_list_offset: u32 (relative to beginning) OR 0 for null list
+string_list_length: u32 number of strings OR 0 for null list
+
+The string list offset points to a table of (offset, length) string positions (aligned to 8)
+followed by the string bytes. Null strings have the position (0, 0).
+
+The data of each list is aligned to its element size (relative to the ObjectId prefix) and the
+gap is filled with zero bytes.
+
 padding: -(len(bool_lists) + len(string lists) + len(bytes_lists)) % 4
  */
 
@@ -73,6 +84,9 @@ impl Property {
     pub const NULL_LONG: i64 = i64::MIN;
     pub const NULL_FLOAT: f32 = f32::NAN;
     pub const NULL_DOUBLE: f64 = f64::NAN;
+    pub const NULL_BOOL: u8 = Self::NULL_BYTE;
+    pub const FALSE_BOOL: u8 = 1;
+    pub const TRUE_BOOL: u8 = 2;
 
     pub fn new(name: String, data_type: DataType, offset: usize) -> Self {
         Property {
@@ -82,6 +96,14 @@ impl Property {
         }
     }
 
+    pub fn bool_to_byte(value: Option<bool>) -> u8 {
+        match value {
+            None => Self::NULL_BOOL,
+            Some(false) => Self::FALSE_BOOL,
+            Some(true) => Self::TRUE_BOOL,
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn new_debug(data_type: DataType, offset: usize) -> Self {
         Property {
@@ -109,6 +131,14 @@ impl Property {
         object[self.offset]
     }
 
+    pub fn get_bool(&self, object: &[u8]) -> Option<bool> {
+        match self.get_byte(object) {
+            Self::NULL_BOOL => None,
+            Self::FALSE_BOOL => Some(false),
+            _ => Some(true),
+        }
+    }
+
     #[inline]
     pub fn get_int(&self, object: &[u8]) -> i32 {
         assert_eq!(self.data_type, DataType::Int);
@@ -137,6 +167,30 @@ impl Property {
         f64::from_le_bytes(bytes)
     }
 
+    pub fn get_bool_opt(&self, object: &[u8]) -> Option<bool> {
+        self.get_bool(object)
+    }
+
+    pub fn get_int_opt(&self, object: &[u8]) -> Option<i32> {
+        let value = self.get_int(object);
+        option!(value != Self::NULL_INT, value)
+    }
+
+    pub fn get_long_opt(&self, object: &[u8]) -> Option<i64> {
+        let value = self.get_long(object);
+        option!(value != Self::NULL_LONG, value)
+    }
+
+    pub fn get_float_opt(&self, object: &[u8]) -> Option<f32> {
+        let value = self.get_float(object);
+        option!(!value.is_nan(), value)
+    }
+
+    pub fn get_double_opt(&self, object: &[u8]) -> Option<f64> {
+        let value = self.get_double(object);
+        option!(!value.is_nan(), value)
+    }
+
     pub(crate) fn get_dynamic_position(&self, object: &[u8]) -> Option<DynamicPosition> {
         let list_offset_bytes: [u8; 4] = object[self.offset..self.offset + 4].try_into().unwrap();
         let list_offset = u32::from_le_bytes(list_offset_bytes);
@@ -157,8 +211,18 @@ impl Property {
         &self,
         object: &'a [u8],
     ) -> Option<&'a [DynamicPosition]> {
-        let position = self.get_dynamic_position(object)?;
-        Some(self.get_list(object, position))
+        self.try_get_dynamic_positions(object).unwrap()
+    }
+
+    fn try_get_dynamic_positions<'a>(
+        &self,
+        object: &'a [u8],
+    ) -> Result<Option<&'a [DynamicPosition]>> {
+        if let Some(position) = self.get_dynamic_position(object) {
+            Ok(Some(self.try_get_list(object, position)?))
+        } else {
+            Ok(None)
+        }
     }
 
     #[inline]
@@ -170,122 +234,279 @@ impl Property {
 
     #[inline]
     pub fn get_string<'a>(&self, object: &'a [u8]) -> Option<&'a str> {
+        self.try_get_string(object).unwrap()
+    }
+
+    /// Like `get_string()` but reports invalid lists or strings of corrupted objects.
+    pub(crate) fn try_get_string<'a>(&self, object: &'a [u8]) -> Result<Option<&'a str>> {
         assert_eq!(self.data_type, DataType::String);
-        let position = self.get_dynamic_position(object)?;
-        let bytes = self.get_list(object, position);
-        Some(std::str::from_utf8(bytes).unwrap())
+        if let Some(position) = self.get_dynamic_position(object) {
+            let bytes = self.try_get_list(object, position)?;
+            let string = std::str::from_utf8(bytes).map_err(|_| corrupted("Invalid string."))?;
+            Ok(Some(string))
+        } else {
+            Ok(None)
+        }
     }
 
     #[inline]
     pub fn get_byte_list<'a>(&self, object: &'a [u8]) -> Option<&'a [u8]> {
+        self.try_get_byte_list(object).unwrap()
+    }
+
+    pub(crate) fn try_get_byte_list<'a>(&self, object: &'a [u8]) -> Result<Option<&'a [u8]>> {
         assert_eq!(self.data_type, DataType::ByteList);
-        let position = self.get_dynamic_position(object)?;
-        Some(self.get_list(object, position))
+        self.try_get_optional_list(object)
     }
 
     pub fn get_int_list<'a>(&self, object: &'a [u8]) -> Option<&'a [i32]> {
+        self.try_get_int_list(object).unwrap()
+    }
+
+    pub(crate) fn try_get_int_list<'a>(&self, object: &'a [u8]) -> Result<Option<&'a [i32]>> {
         assert_eq!(self.data_type, DataType::IntList);
-        let position = self.get_dynamic_position(object)?;
-        Some(self.get_list(object, position))
+        self.try_get_optional_list(object)
     }
 
     pub fn get_long_list<'a>(&self, object: &'a [u8]) -> Option<&'a [i64]> {
+        self.try_get_long_list(object).unwrap()
+    }
+
+    pub(crate) fn try_get_long_list<'a>(&self, object: &'a [u8]) -> Result<Option<&'a [i64]>> {
         assert_eq!(self.data_type, DataType::LongList);
-        let position = self.get_dynamic_position(object)?;
-        Some(self.get_list(object, position))
+        self.try_get_optional_list(object)
     }
 
     pub fn get_float_list<'a>(&self, object: &'a [u8]) -> Option<&'a [f32]> {
+        self.try_get_float_list(object).unwrap()
+    }
+
+    pub(crate) fn try_get_float_list<'a>(&self, object: &'a [u8]) -> Result<Option<&'a [f32]>> {
         assert_eq!(self.data_type, DataType::FloatList);
-        let position = self.get_dynamic_position(object)?;
-        Some(self.get_list(object, position))
+        self.try_get_optional_list(object)
     }
 
     pub fn get_double_list<'a>(&self, object: &'a [u8]) -> Option<&'a [f64]> {
+        self.try_get_double_list(object).unwrap()
+    }
+
+    pub(crate) fn try_get_double_list<'a>(&self, object: &'a [u8]) -> Result<Option<&'a [f64]>> {
         assert_eq!(self.data_type, DataType::DoubleList);
-        let position = self.get_dynamic_position(object)?;
-        Some(self.get_list(object, position))
+        self.try_get_optional_list(object)
     }
 
     pub fn get_string_list<'a>(&self, object: &'a [u8]) -> Option<Vec<Option<&'a str>>> {
+        self.try_get_string_list(object).unwrap()
+    }
+
+    pub(crate) fn try_get_string_list<'a>(
+        &self,
+        object: &'a [u8],
+    ) -> Result<Option<Vec<Option<&'a str>>>> {
         assert_eq!(self.data_type, DataType::StringList);
-        let positions = self.get_dynamic_positions(object)?;
+        let positions = if let Some(positions) = self.try_get_dynamic_positions(object)? {
+            positions
+        } else {
+            return Ok(None);
+        };
         let string_list = positions
             .iter()
             .map(|position| {
                 if position.is_null() {
-                    None
+                    Ok(None)
+                } else {
+                    let bytes = self.try_get_list(object, *position)?;
+                    let string =
+                        std::str::from_utf8(bytes).map_err(|_| corrupted("Invalid string."))?;
+                    Ok(Some(string))
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Some(string_list))
+    }
+
+    pub fn get_bytes_list<'a>(&self, object: &'a [u8]) -> Option<Vec<Option<&'a [u8]>>> {
+        self.try_get_bytes_list(object).unwrap()
+    }
+
+    pub(crate) fn try_get_bytes_list<'a>(
+        &self,
+        object: &'a [u8],
+    ) -> Result<Option<Vec<Option<&'a [u8]>>>> {
+        assert_eq!(self.data_type, DataType::BytesList);
+        let positions = if let Some(positions) = self.try_get_dynamic_positions(object)? {
+            positions
+        } else {
+            return Ok(None);
+        };
+        let bytes_list = positions
+            .iter()
+            .map(|position| {
+                if position.is_null() {
+                    Ok(None)
                 } else {
-                    let bytes = self.get_list(object, *position);
-                    Some(std::str::from_utf8(bytes).unwrap())
+                    Ok(Some(self.try_get_list(object, *position)?))
                 }
             })
-            .collect_vec();
-        Some(string_list)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Some(bytes_list))
+    }
+
+    fn try_get_optional_list<'a, T>(&self, object: &'a [u8]) -> Result<Option<&'a [T]>> {
+        if let Some(position) = self.get_dynamic_position(object) {
+            Ok(Some(self.try_get_list(object, position)?))
+        } else {
+            Ok(None)
+        }
     }
 
-    fn get_list<'a, T>(&self, object: &'a [u8], data_position: DynamicPosition) -> &'a [T] {
+    pub(crate) fn try_get_list<'a, T>(
+        &self,
+        object: &'a [u8],
+        data_position: DynamicPosition,
+    ) -> Result<&'a [T]> {
         let list_length = data_position.length as usize;
         let list_offset = data_position.offset as usize;
 
         let type_size = mem::size_of::<T>();
         let len_in_bytes = list_length * type_size;
-        let list_bytes = &object[list_offset..list_offset + len_in_bytes];
+        let list_bytes = object
+            .get(list_offset..list_offset + len_in_bytes)
+            .ok_or_else(|| corrupted("List out of bounds."))?;
 
-        let alignment = list_bytes.as_ref().as_ptr() as usize;
-        assert_eq!(alignment % type_size, 0, "Wrong alignment.");
-        let ptr = list_bytes.as_ptr() as *const u8;
-        unsafe { slice::from_raw_parts::<T>(ptr as *const T, list_length) }
+        let ptr = list_bytes.as_ptr() as *const T;
+        if ptr.align_offset(mem::align_of::<T>()) != 0 {
+            return Err(corrupted("Wrong alignment."));
+        }
+        Ok(unsafe { slice::from_raw_parts::<T>(ptr, list_length) })
     }
 
-    fn get_raw<'a>(&self, object: &'a [u8]) -> &'a [u8] {
-        match self.data_type {
-            DataType::Byte => &object[self.offset..self.offset],
-            DataType::Int | DataType::Float => &object[self.offset..self.offset + 4],
-            DataType::Long | DataType::Double => &object[self.offset..self.offset + 8],
+    fn try_get_raw<'a>(&self, object: &'a [u8]) -> Result<&'a [u8]> {
+        let range = match self.data_type {
+            DataType::Byte => self.offset..self.offset + 1,
+            DataType::Int | DataType::Float => self.offset..self.offset + 4,
+            DataType::Long | DataType::Double => self.offset..self.offset + 8,
             _ => {
                 let pos = self.get_dynamic_position(object);
                 if let Some(pos) = pos {
-                    match self.data_type {
-                        DataType::StringList => &[],
-                        _ => {
-                            let offset = pos.offset as usize;
-                            let len_in_bytes =
-                                pos.length as usize * self.data_type.get_element_size();
-                            &object[offset..offset + len_in_bytes]
-                        }
+                    if self.data_type.is_nested_list() {
+                        return Ok(&[]);
+                    } else {
+                        let offset = pos.offset as usize;
+                        let len_in_bytes = pos.length as usize * self.data_type.get_element_size();
+                        offset..offset + len_in_bytes
                     }
                 } else {
-                    &[]
+                    return Ok(&[]);
                 }
             }
-        }
+        };
+        object
+            .get(range)
+            .ok_or_else(|| corrupted("Value out of bounds."))
+    }
+
+    /// Compares the values of this property. Null values are the smallest values. Lists cannot
+    /// be compared and have to be rejected by the caller, see `QueryBuilder::add_sort()`.
+    pub(crate) fn compare(&self, object1: &[u8], object2: &[u8]) -> Result<Ordering> {
+        let ordering = match self.data_type {
+            DataType::Byte => self.get_byte(object1).cmp(&self.get_byte(object2)),
+            DataType::Int => self.get_int(object1).cmp(&self.get_int(object2)),
+            DataType::Long => self.get_long(object1).cmp(&self.get_long(object2)),
+            DataType::Float => {
+                Self::compare_float(self.get_float(object1), self.get_float(object2))
+            }
+            DataType::Double => {
+                Self::compare_float(self.get_double(object1), self.get_double(object2))
+            }
+            DataType::String => self
+                .try_get_string(object1)?
+                .cmp(&self.try_get_string(object2)?),
+            _ => unreachable!("Lists cannot be compared."),
+        };
+        Ok(ordering)
+    }
+
+    fn compare_float<T: PartialOrd>(value1: T, value2: T) -> Ordering {
+        // NaN is null and only equal to itself
+        value1.partial_cmp(&value2).unwrap_or_else(|| {
+            let is_null1 = value1.partial_cmp(&value1).is_none();
+            let is_null2 = value2.partial_cmp(&value2).is_none();
+            is_null2.cmp(&is_null1)
+        })
     }
 
-    pub fn hash_value<H: Hasher>(&self, object: &[u8], hasher: &mut H) {
-        if self.data_type.is_dynamic() {
+    /// Hashes the value of this property. Strings and string lists are lowercased before
+    /// hashing if `case_sensitive` is `false`. Invalid lists are reported as `DbCorrupted`.
+    pub fn hash_value<H: Hasher>(
+        &self,
+        object: &[u8],
+        case_sensitive: bool,
+        hasher: &mut H,
+    ) -> Result<()> {
+        let lowercase = !case_sensitive
+            && (self.data_type == DataType::String || self.data_type == DataType::StringList);
+        if self.data_type.is_nested_list() {
             let len = self.get_length(object).map_or(0, |len| (len + 1) as u32);
             hasher.write_u32(len);
-            if self.data_type == DataType::StringList {
-                if let Some(positions) = self.get_dynamic_positions(object) {
-                    for pos in positions {
-                        if pos.is_null() {
-                            hasher.write_u32(0);
-                        } else {
-                            hasher.write_u32(pos.length + 1);
-                        }
+            if let Some(positions) = self.try_get_dynamic_positions(object)? {
+                for pos in positions {
+                    if pos.is_null() {
+                        hasher.write_u32(0);
+                    } else {
+                        let element = self.try_get_list::<u8>(object, *pos)?;
+                        Self::hash_bytes(element, lowercase, hasher);
                     }
                 }
             }
+        } else if lowercase {
+            if let Some(value) = self.try_get_string(object)? {
+                Self::hash_bytes(value.as_bytes(), true, hasher);
+            } else {
+                hasher.write_u32(0);
+            }
+        } else if (self.data_type == DataType::Float || self.data_type == DataType::Double)
+            && self.is_null(object)
+        {
+            // NaN has many bit patterns
+            hasher.write(&Self::NULL_DOUBLE.to_le_bytes());
+        } else {
+            if self.data_type.is_dynamic() {
+                let len = self.get_length(object).map_or(0, |len| (len + 1) as u32);
+                hasher.write_u32(len);
+            }
+            hasher.write(self.try_get_raw(object)?);
+        }
+        Ok(())
+    }
+
+    fn hash_bytes<H: Hasher>(bytes: &[u8], lowercase: bool, hasher: &mut H) {
+        if lowercase {
+            let lowercase = String::from_utf8_lossy(bytes).to_lowercase();
+            hasher.write_u32(lowercase.len() as u32 + 1);
+            hasher.write(lowercase.as_bytes());
+        } else {
+            hasher.write_u32(bytes.len() as u32 + 1);
+            hasher.write(bytes);
         }
-        hasher.write(self.get_raw(object));
+    }
+}
+
+fn corrupted(message: &str) -> IsarError {
+    IsarError::DbCorrupted {
+        source: None,
+        message: message.to_string(),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::object::property::{DataType, Property};
+    use crate::error::IsarError;
+    use crate::object::property::{DataType, DynamicPosition, Property};
     use crate::utils::debug::align;
+    use std::cmp::Ordering;
+    use std::hash::Hasher;
+    use wyhash::WyHash;
 
     #[test]
     fn test_get_byte() {
@@ -301,6 +522,116 @@ mod tests {
         assert_eq!(property.get_byte(&null_bytes), 255);
     }
 
+    #[test]
+    fn test_hash_value_nan() {
+        let hash = |property: &Property, bytes: &[u8]| {
+            let mut hasher = WyHash::default();
+            property.hash_value(bytes, true, &mut hasher).unwrap();
+            hasher.finish()
+        };
+
+        let property = Property::new_debug(DataType::Float, 0);
+        let nan1 = f32::NAN.to_le_bytes();
+        let nan2 = f32::from_bits(f32::NAN.to_bits() ^ 0x80000001).to_le_bytes();
+        assert_ne!(nan1, nan2);
+        assert_eq!(hash(&property, &nan1), hash(&property, &nan2));
+        assert_ne!(
+            hash(&property, &nan1),
+            hash(&property, &1.0f32.to_le_bytes())
+        );
+
+        let property = Property::new_debug(DataType::Double, 0);
+        let nan1 = f64::NAN.to_le_bytes();
+        let nan2 = f64::from_bits(f64::NAN.to_bits() | 1).to_le_bytes();
+        assert_ne!(nan1, nan2);
+        assert_eq!(hash(&property, &nan1), hash(&property, &nan2));
+    }
+
+    #[test]
+    fn test_compare_float() {
+        let property = Property::new_debug(DataType::Float, 0);
+        let bytes = |value: f32| value.to_le_bytes();
+
+        assert_eq!(
+            property.compare(&bytes(1.0), &bytes(2.0)).unwrap(),
+            Ordering::Less
+        );
+        assert_eq!(
+            property.compare(&bytes(2.0), &bytes(-1.0)).unwrap(),
+            Ordering::Greater
+        );
+        assert_eq!(
+            property.compare(&bytes(1.0), &bytes(1.0)).unwrap(),
+            Ordering::Equal
+        );
+        let null = bytes(Property::NULL_FLOAT);
+        assert_eq!(
+            property.compare(&null, &bytes(f32::MIN)).unwrap(),
+            Ordering::Less
+        );
+        assert_eq!(
+            property.compare(&bytes(f32::MIN), &null).unwrap(),
+            Ordering::Greater
+        );
+        assert_eq!(property.compare(&null, &null).unwrap(), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_compare_corrupted_string() {
+        let property = Property::new_debug(DataType::String, 0);
+        let valid = align(&[8, 0, 0, 0, 1, 0, 0, 0, b'a']);
+        let invalid = align(&[8, 0, 0, 0, 2, 0, 0, 0, 0xff, 0xfe]);
+        assert_eq!(property.try_get_string(&valid).unwrap(), Some("a"));
+
+        let result = property.compare(&valid, &invalid);
+        assert!(matches!(result, Err(IsarError::DbCorrupted { .. })));
+        let result = property.compare(&invalid, &valid);
+        assert!(matches!(result, Err(IsarError::DbCorrupted { .. })));
+    }
+
+    #[test]
+    fn test_get_opt() {
+        let property = Property::new_debug(DataType::Byte, 0);
+        assert_eq!(property.get_bool_opt(&[Property::NULL_BOOL]), None);
+        assert_eq!(property.get_bool_opt(&[Property::FALSE_BOOL]), Some(false));
+        assert_eq!(property.get_bool_opt(&[Property::TRUE_BOOL]), Some(true));
+
+        let property = Property::new_debug(DataType::Int, 0);
+        assert_eq!(
+            property.get_int_opt(&Property::NULL_INT.to_le_bytes()),
+            None
+        );
+        assert_eq!(property.get_int_opt(&0i32.to_le_bytes()), Some(0));
+        assert_eq!(property.get_int_opt(&(-5i32).to_le_bytes()), Some(-5));
+
+        let property = Property::new_debug(DataType::Long, 0);
+        assert_eq!(
+            property.get_long_opt(&Property::NULL_LONG.to_le_bytes()),
+            None
+        );
+        assert_eq!(
+            property.get_long_opt(&i64::MAX.to_le_bytes()),
+            Some(i64::MAX)
+        );
+
+        let property = Property::new_debug(DataType::Float, 0);
+        assert_eq!(
+            property.get_float_opt(&Property::NULL_FLOAT.to_le_bytes()),
+            None
+        );
+        assert_eq!(property.get_float_opt(&1.5f32.to_le_bytes()), Some(1.5));
+
+        let property = Property::new_debug(DataType::Double, 0);
+        assert_eq!(
+            property.get_double_opt(&Property::NULL_DOUBLE.to_le_bytes()),
+            None
+        );
+        assert_eq!(
+            property.get_double_opt(&(-2.5f64).to_le_bytes()),
+            Some(-2.5)
+        );
+    }
+
     #[test]
     fn test_byte_is_null() {
         let property = Property::new_debug(DataType::Byte, 0);
@@ -556,4 +887,84 @@ mod tests {
         let bytes = [0, 0, 0, 0, 0, 0, 0, 0];
         assert_eq!(property.get_string_list(&bytes), None);
     }
+
+    #[test]
+    fn test_get_bytes_list() {
+        let property = Property::new_debug(DataType::BytesList, 0);
+
+        let mut bytes = vec![
+            8, 0, 0, 0, 3, 0, 0, 0, 32, 0, 0, 0, 2, 0, 0, 0, 34, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0,
+        ];
+        bytes.extend_from_slice(&[1, 2]);
+        assert_eq!(
+            property.get_bytes_list(&bytes),
+            Some(vec![Some(&[1u8, 2][..]), Some(&[][..]), None])
+        );
+
+        let bytes = [8, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(property.get_bytes_list(&bytes), Some(vec![]));
+
+        let bytes = [0, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(property.get_bytes_list(&bytes), None);
+    }
+
+    #[test]
+    fn test_try_get_list_misaligned() {
+        let property = Property::new_debug(DataType::IntList, 0);
+        let bytes = align(&[8, 0, 0, 0, 1, 0, 0, 0, 0, 5, 0, 0, 0, 0, 0, 0]);
+        let position = DynamicPosition {
+            offset: 9,
+            length: 1,
+        };
+        let result = property.try_get_list::<i32>(&bytes, position);
+        assert!(matches!(result, Err(IsarError::DbCorrupted { .. })));
+
+        let position = DynamicPosition {
+            offset: 8,
+            length: 1,
+        };
+        assert_eq!(
+            property.try_get_list::<i32>(&bytes, position).unwrap(),
+            &[5 << 8]
+        );
+    }
+
+    #[test]
+    fn test_try_get_list_out_of_bounds() {
+        let property = Property::new_debug(DataType::IntList, 0);
+        let bytes = align(&[8, 0, 0, 0, 3, 0, 0, 0, 5, 0, 0, 0, 6, 0, 0, 0]);
+        let position = property.get_dynamic_position(&bytes).unwrap();
+        let result = property.try_get_list::<i32>(&bytes, position);
+        assert!(matches!(result, Err(IsarError::DbCorrupted { .. })));
+    }
+
+    #[test]
+    fn test_try_get_lists_corrupted() {
+        let bytes = align(&[8, 0, 0, 0, 3, 0, 0, 0, 5, 0, 0, 0, 6, 0, 0, 0]);
+        let is_corrupted =
+            |result: Result<(), IsarError>| matches!(result, Err(IsarError::DbCorrupted { .. }));
+
+        let property = Property::new_debug(DataType::LongList, 0);
+        assert!(is_corrupted(property.try_get_long_list(&bytes).map(|_| ())));
+        let property = Property::new_debug(DataType::FloatList, 0);
+        assert!(is_corrupted(
+            property.try_get_float_list(&bytes).map(|_| ())
+        ));
+        let property = Property::new_debug(DataType::ByteList, 0);
+        let bytes_too_long = align(&[8, 0, 0, 0, 16, 0, 0, 0]);
+        assert!(is_corrupted(
+            property.try_get_byte_list(&bytes_too_long).map(|_| ())
+        ));
+        let property = Property::new_debug(DataType::BytesList, 0);
+        assert!(is_corrupted(
+            property.try_get_bytes_list(&bytes).map(|_| ())
+        ));
+
+        let mut hasher = WyHash::default();
+        let property = Property::new_debug(DataType::LongList, 0);
+        assert!(is_corrupted(property.hash_value(&bytes, true, &mut hasher)));
+        let property = Property::new_debug(DataType::BytesList, 0);
+        assert!(is_corrupted(property.hash_value(&bytes, true, &mut hasher)));
+    }
 }
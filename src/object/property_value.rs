@@ -0,0 +1,102 @@
+use crate::object::data_type::DataType;
+use crate::object::property::Property;
+use serde_json::{json, Value};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PropertyValue<'a> {
+    Null,
+    Byte(u8),
+    Int(i32),
+    Float(f32),
+    Long(i64),
+    Double(f64),
+    String(&'a str),
+    ByteList(&'a [u8]),
+    IntList(&'a [i32]),
+    FloatList(&'a [f32]),
+    LongList(&'a [i64]),
+    DoubleList(&'a [f64]),
+    StringList(Vec<Option<&'a str>>),
+    BytesList(Vec<Option<&'a [u8]>>),
+}
+
+impl<'a> PropertyValue<'a> {
+    pub(crate) fn read(property: &Property, object: &'a [u8]) -> PropertyValue<'a> {
+        match property.data_type {
+            DataType::Byte => PropertyValue::Byte(property.get_byte(object)),
+            DataType::Int => PropertyValue::Int(property.get_int(object)),
+            DataType::Float => PropertyValue::Float(property.get_float(object)),
+            DataType::Long => PropertyValue::Long(property.get_long(object)),
+            DataType::Double => PropertyValue::Double(property.get_double(object)),
+            DataType::String => property
+                .get_string(object)
+                .map_or(PropertyValue::Null, PropertyValue::String),
+            DataType::ByteList => property
+                .get_byte_list(object)
+                .map_or(PropertyValue::Null, PropertyValue::ByteList),
+            DataType::IntList => property
+                .get_int_list(object)
+                .map_or(PropertyValue::Null, PropertyValue::IntList),
+            DataType::FloatList => property
+                .get_float_list(object)
+                .map_or(PropertyValue::Null, PropertyValue::FloatList),
+            DataType::LongList => property
+                .get_long_list(object)
+                .map_or(PropertyValue::Null, PropertyValue::LongList),
+            DataType::DoubleList => property
+                .get_double_list(object)
+                .map_or(PropertyValue::Null, PropertyValue::DoubleList),
+            DataType::StringList => property
+                .get_string_list(object)
+                .map_or(PropertyValue::Null, PropertyValue::StringList),
+            DataType::BytesList => property
+                .get_bytes_list(object)
+                .map_or(PropertyValue::Null, PropertyValue::BytesList),
+        }
+    }
+
+    /// Returns the data type of this value or `None` for `Null` which fits any property.
+    pub fn get_data_type(&self) -> Option<DataType> {
+        let data_type = match self {
+            PropertyValue::Null => return None,
+            PropertyValue::Byte(_) => DataType::Byte,
+            PropertyValue::Int(_) => DataType::Int,
+            PropertyValue::Float(_) => DataType::Float,
+            PropertyValue::Long(_) => DataType::Long,
+            PropertyValue::Double(_) => DataType::Double,
+            PropertyValue::String(_) => DataType::String,
+            PropertyValue::ByteList(_) => DataType::ByteList,
+            PropertyValue::IntList(_) => DataType::IntList,
+            PropertyValue::FloatList(_) => DataType::FloatList,
+            PropertyValue::LongList(_) => DataType::LongList,
+            PropertyValue::DoubleList(_) => DataType::DoubleList,
+            PropertyValue::StringList(_) => DataType::StringList,
+            PropertyValue::BytesList(_) => DataType::BytesList,
+        };
+        Some(data_type)
+    }
+}
+
+/// Formats the value like JSON, e.g. `1`, `"a"` or `[1, 2]`.
+impl<'a> fmt::Display for PropertyValue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = match self {
+            PropertyValue::Null => Value::Null,
+            PropertyValue::Byte(value) => json!(value),
+            PropertyValue::Int(value) => json!(value),
+            PropertyValue::Float(value) => json!(value),
+            PropertyValue::Long(value) => json!(value),
+            PropertyValue::Double(value) => json!(value),
+            PropertyValue::String(value) => json!(value),
+            PropertyValue::ByteList(value) => json!(value),
+            PropertyValue::IntList(value) => json!(value),
+            PropertyValue::FloatList(value) => json!(value),
+            PropertyValue::LongList(value) => json!(value),
+            PropertyValue::DoubleList(value) => json!(value),
+            PropertyValue::StringList(value) => json!(value),
+            PropertyValue::BytesList(value) => json!(value),
+        };
+        write!(f, "{}", json)
+    }
+}
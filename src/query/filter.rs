@@ -1,4 +1,5 @@
 use crate::error::{illegal_arg, Result};
+use crate::object::data_type::DataType;
 use crate::object::property::Property;
 use enum_dispatch::enum_dispatch;
 
@@ -13,24 +14,44 @@ pub enum Filter<'col> {
     IsNull(IsNull<'col>),
     ByteBetween(ByteBetween<'col>),
     ByteNotEqual(ByteNotEqual<'col>),
+    BoolEqual(BoolEqual<'col>),
     IntBetween(IntBetween<'col>),
     IntNotEqual(IntNotEqual<'col>),
     LongBetween(LongBetween<'col>),
     LongNotEqual(LongNotEqual<'col>),
     FloatBetween(FloatBetween<'col>),
+    FloatEqual(FloatEqual<'col>),
+    FloatNotEqual(FloatNotEqual<'col>),
     DoubleBetween(DoubleBetween<'col>),
-    /*StrAnyOf(StrAnyOf),
-    StrStartsWith(),
-    StrEndsWith(),
-    StrContains(),*/
+    DoubleEqual(DoubleEqual<'col>),
+    DoubleNotEqual(DoubleNotEqual<'col>),
+    IntListAnyBetween(IntListAnyBetween<'col>),
+    IntListAllBetween(IntListAllBetween<'col>),
+    DoubleListAnyBetween(DoubleListAnyBetween<'col>),
+    DoubleListAllBetween(DoubleListAllBetween<'col>),
+    StrEqual(StrEqual<'col>),
+    ListLength(ListLength<'col>),
+    StrAnyOf(StrAnyOf<'col>),
+    StrStartsWith(StrStartsWith<'col>),
+    StrEndsWith(StrEndsWith<'col>),
+    StrContains(StrContains<'col>),
+    StringListContains(StringListContains<'col>),
     And(And<'col>),
     Or(Or<'col>),
     Not(Not<'col>),
+    #[cfg(test)]
+    Counting(Counting<'col>),
 }
 
 #[enum_dispatch(Filter)]
 pub trait Condition {
-    fn evaluate(&self, object: &[u8]) -> bool;
+    fn evaluate(&self, object: &[u8]) -> Result<bool>;
+
+    /// Rough relative cost of evaluating this condition. `And` and `Or` evaluate cheaper
+    /// conditions first.
+    fn estimated_cost(&self) -> u32 {
+        1
+    }
 }
 
 pub struct IsNull<'col> {
@@ -39,8 +60,8 @@ pub struct IsNull<'col> {
 }
 
 impl<'col> Condition for IsNull<'col> {
-    fn evaluate(&self, object: &[u8]) -> bool {
-        self.property.is_null(object) == self.is_null
+    fn evaluate(&self, object: &[u8]) -> Result<bool> {
+        Ok(self.property.is_null(object) == self.is_null)
     }
 }
 
@@ -85,9 +106,9 @@ macro_rules! primitive_filter_between {
         filter_between!($name, $data_type, $type);
 
         impl<'col> Condition for $name<'col> {
-            fn evaluate(&self, object: &[u8]) -> bool {
+            fn evaluate(&self, object: &[u8]) -> Result<bool> {
                 let val = self.property.$prop_accessor(object);
-                self.lower <= val && self.upper >= val
+                Ok(self.lower <= val && self.upper >= val)
             }
         }
     };
@@ -99,15 +120,16 @@ macro_rules! float_filter_between {
         filter_between!($name, $data_type, $type);
 
         impl<'col> Condition for $name<'col> {
-            fn evaluate(&self, object: &[u8]) -> bool {
+            fn evaluate(&self, object: &[u8]) -> Result<bool> {
                 let val = self.property.$prop_accessor(object);
-                if self.upper.is_nan() {
+                let result = if self.upper.is_nan() {
                     self.lower.is_nan() && val.is_nan()
                 } else if self.lower.is_nan() {
                     self.upper >= val
                 } else {
                     self.lower <= val && self.upper >= val
-                }
+                };
+                Ok(result)
             }
         }
     };
@@ -119,6 +141,137 @@ primitive_filter_between!(LongBetween, Long, i64, get_long);
 float_filter_between!(FloatBetween, Float, f32, get_float);
 float_filter_between!(DoubleBetween, Double, f64, get_double);
 
+/// Creates the between filter matching the type of a byte, int, long, float or double property.
+/// The bounds are inclusive and rounded inwards for integer properties.
+pub struct NumericBetween;
+
+impl NumericBetween {
+    pub fn filter<'col>(property: &'col Property, lower: f64, upper: f64) -> Result<Filter<'col>> {
+        match property.data_type {
+            DataType::Byte => {
+                let (lower, upper) = Self::integer_bounds(lower, upper, 0.0, u8::MAX as f64)?;
+                ByteBetween::filter(property, lower as u8, upper as u8)
+            }
+            DataType::Int => {
+                let (lower, upper) =
+                    Self::integer_bounds(lower, upper, i32::MIN as f64, i32::MAX as f64)?;
+                IntBetween::filter(property, lower as i32, upper as i32)
+            }
+            DataType::Long => {
+                let (lower, upper) =
+                    Self::integer_bounds(lower, upper, i64::MIN as f64, i64::MAX as f64)?;
+                LongBetween::filter(property, lower as i64, upper as i64)
+            }
+            DataType::Float => FloatBetween::filter(property, lower as f32, upper as f32),
+            DataType::Double => DoubleBetween::filter(property, lower, upper),
+            _ => illegal_arg("Property does not support this filter."),
+        }
+    }
+
+    /// Returns an empty range `(max, min)` if no integer is within the bounds.
+    fn integer_bounds(lower: f64, upper: f64, min: f64, max: f64) -> Result<(f64, f64)> {
+        if lower.is_nan() || upper.is_nan() {
+            return illegal_arg("Bounds of integer properties must not be NaN.");
+        }
+        let lower = lower.ceil().max(min);
+        let upper = upper.floor().min(max);
+        if lower <= upper {
+            Ok((lower, upper))
+        } else {
+            Ok((max, min))
+        }
+    }
+}
+
+/// Matches if any (or all) elements of the list are in range. Null lists never match, empty lists
+/// only match `all`.
+#[macro_export]
+macro_rules! list_filter_between {
+    ($name:ident, $data_type:ident, $type:ty, $prop_accessor:ident, $method:ident) => {
+        filter_between!($name, $data_type, $type);
+
+        impl<'col> Condition for $name<'col> {
+            fn evaluate(&self, object: &[u8]) -> Result<bool> {
+                if let Some(list) = self.property.$prop_accessor(object)? {
+                    Ok(list
+                        .iter()
+                        .$method(|val| self.lower <= *val && self.upper >= *val))
+                } else {
+                    Ok(false)
+                }
+            }
+
+            fn estimated_cost(&self) -> u32 {
+                3
+            }
+        }
+    };
+}
+
+list_filter_between!(IntListAnyBetween, IntList, i32, try_get_int_list, any);
+list_filter_between!(IntListAllBetween, IntList, i32, try_get_int_list, all);
+list_filter_between!(
+    DoubleListAnyBetween,
+    DoubleList,
+    f64,
+    try_get_double_list,
+    any
+);
+list_filter_between!(
+    DoubleListAllBetween,
+    DoubleList,
+    f64,
+    try_get_double_list,
+    all
+);
+
+#[macro_export]
+macro_rules! float_filter_equal {
+    ($name:ident, $data_type:ident, $type:ty, $prop_accessor:ident) => {
+        pub struct $name<'col> {
+            value: $type,
+            epsilon: $type,
+            property: &'col Property,
+        }
+
+        impl<'col> $name<'col> {
+            /// Matches values that differ at most by `epsilon` from `value`. A NaN `value`
+            /// matches null values.
+            pub fn filter(
+                property: &'col Property,
+                value: $type,
+                epsilon: $type,
+            ) -> Result<Filter<'col>> {
+                if property.data_type != $crate::object::data_type::DataType::$data_type {
+                    illegal_arg("Property does not support this filter.")
+                } else if epsilon.is_nan() || epsilon < 0.0 {
+                    illegal_arg("Epsilon must not be negative.")
+                } else {
+                    Ok(Filter::$name(Self {
+                        property,
+                        value,
+                        epsilon,
+                    }))
+                }
+            }
+        }
+
+        impl<'col> Condition for $name<'col> {
+            fn evaluate(&self, object: &[u8]) -> Result<bool> {
+                let val = self.property.$prop_accessor(object);
+                if self.value.is_nan() {
+                    Ok(val.is_nan())
+                } else {
+                    Ok((self.value - val).abs() <= self.epsilon)
+                }
+            }
+        }
+    };
+}
+
+float_filter_equal!(FloatEqual, Float, f32, get_float);
+float_filter_equal!(DoubleEqual, Double, f64, get_double);
+
 #[macro_export]
 macro_rules! filter_not_equal {
     ($name:ident, $data_type:ident, $type:ty) => {
@@ -145,9 +298,9 @@ macro_rules! primitive_filter_not_equal {
         filter_not_equal!($not_equal_name, $data_type, $type);
 
         impl<'col> Condition for $not_equal_name<'col> {
-            fn evaluate(&self, object: &[u8]) -> bool {
+            fn evaluate(&self, object: &[u8]) -> Result<bool> {
                 let val = self.property.$prop_accessor(object);
-                self.value != val
+                Ok(self.value != val)
             }
         }
     };
@@ -157,76 +310,310 @@ primitive_filter_not_equal!(ByteNotEqual, Byte, u8, get_byte);
 primitive_filter_not_equal!(IntNotEqual, Int, i32, get_int);
 primitive_filter_not_equal!(LongNotEqual, Long, i64, get_long);
 
-/*pub struct StrAnyOf {
-    property: Property,
-    values: Vec<Option<Vec<u8>>>,
+#[macro_export]
+macro_rules! float_filter_not_equal {
+    ($not_equal_name:ident, $data_type:ident, $type:ty, $prop_accessor:ident) => {
+        filter_not_equal!($not_equal_name, $data_type, $type);
+
+        impl<'col> Condition for $not_equal_name<'col> {
+            fn evaluate(&self, object: &[u8]) -> Result<bool> {
+                let val = self.property.$prop_accessor(object);
+                if self.value.is_nan() {
+                    Ok(!val.is_nan())
+                } else {
+                    Ok(self.value != val)
+                }
+            }
+        }
+    };
+}
+
+float_filter_not_equal!(FloatNotEqual, Float, f32, get_float);
+float_filter_not_equal!(DoubleNotEqual, Double, f64, get_double);
+
+#[macro_export]
+macro_rules! filter_string {
+    ($name:ident) => {
+        pub struct $name<'col> {
+            property: &'col Property,
+            value: String,
+            case: Case,
+        }
+
+        impl<'col> $name<'col> {
+            pub fn filter(
+                property: &'col Property,
+                value: &str,
+                case: Case,
+            ) -> Result<Filter<'col>> {
+                if property.data_type == DataType::String {
+                    let value = if case == Case::Insensitive {
+                        value.to_lowercase()
+                    } else {
+                        value.to_string()
+                    };
+                    Ok(Filter::$name(Self {
+                        property,
+                        value,
+                        case,
+                    }))
+                } else {
+                    illegal_arg("Property does not support this filter.")
+                }
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! string_filter {
+    ($name:ident, $str_method:ident, $cost:expr) => {
+        filter_string!($name);
+
+        impl<'col> Condition for $name<'col> {
+            fn evaluate(&self, object: &[u8]) -> Result<bool> {
+                if let Some(other) = self.property.try_get_string(object)? {
+                    if self.case == Case::Insensitive {
+                        Ok(other.to_lowercase().$str_method(&self.value))
+                    } else {
+                        Ok(other.$str_method(&self.value))
+                    }
+                } else {
+                    Ok(false)
+                }
+            }
+
+            fn estimated_cost(&self) -> u32 {
+                $cost
+            }
+        }
+    };
+}
+
+string_filter!(StrStartsWith, starts_with, 2);
+string_filter!(StrEndsWith, ends_with, 2);
+string_filter!(StrContains, contains, 4);
+
+pub struct BoolEqual<'col> {
+    property: &'col Property,
+    value: u8,
+}
+
+impl<'col> Condition for BoolEqual<'col> {
+    fn evaluate(&self, object: &[u8]) -> Result<bool> {
+        Ok(self.property.get_byte(object) == self.value)
+    }
+}
+
+impl<'col> BoolEqual<'col> {
+    pub fn filter(property: &'col Property, value: Option<bool>) -> Result<Filter<'col>> {
+        if property.data_type == DataType::Byte {
+            Ok(Filter::BoolEqual(BoolEqual {
+                property,
+                value: Property::bool_to_byte(value),
+            }))
+        } else {
+            illegal_arg("Property does not support this filter.")
+        }
+    }
+}
+
+pub struct StrEqual<'col> {
+    property: &'col Property,
+    value: Option<String>,
     case: Case,
 }
 
-impl StrAnyOf {
-    pub fn new(property: Property, values: &[Option<&str>], case: Case) -> StrAnyOf {
-        let values = if case == Case::Insensitive {
-            values
-                .iter()
-                .map(|s| s.to_lowercase().into_bytes())
-                .collect_vec()
+impl<'col> Condition for StrEqual<'col> {
+    fn evaluate(&self, object: &[u8]) -> Result<bool> {
+        let value = self.property.try_get_string(object)?;
+        if self.case == Case::Insensitive {
+            Ok(value.map(|s| s.to_lowercase()) == self.value)
         } else {
-            values.iter().map(|s| s.as_bytes().to_vec()).collect_vec()
-        };
-        StrAnyOf {
-            property,
-            values,
-            case,
+            Ok(value == self.value.as_deref())
+        }
+    }
+
+    fn estimated_cost(&self) -> u32 {
+        2
+    }
+}
+
+impl<'col> StrEqual<'col> {
+    pub fn filter(
+        property: &'col Property,
+        value: Option<&str>,
+        case: Case,
+    ) -> Result<Filter<'col>> {
+        if property.data_type == DataType::String {
+            let value = if case == Case::Insensitive {
+                value.map(|s| s.to_lowercase())
+            } else {
+                value.map(|s| s.to_string())
+            };
+            Ok(Filter::StrEqual(StrEqual {
+                property,
+                value,
+                case,
+            }))
+        } else {
+            illegal_arg("Property does not support this filter.")
+        }
+    }
+}
+
+pub struct StrAnyOf<'col> {
+    property: &'col Property,
+    values: Vec<Option<String>>,
+    case: Case,
+}
+
+impl<'col> Condition for StrAnyOf<'col> {
+    fn evaluate(&self, object: &[u8]) -> Result<bool> {
+        let value = self.property.try_get_string(object)?;
+        if self.case == Case::Insensitive {
+            let lowercase = value.map(|s| s.to_lowercase());
+            Ok(self.values.iter().any(|v| v == &lowercase))
+        } else {
+            Ok(self.values.iter().any(|v| v.as_deref() == value))
         }
     }
+
+    fn estimated_cost(&self) -> u32 {
+        3
+    }
 }
 
-impl Condition for StrAnyOf {
-    fn evaluate(&self, object: &[u8]) -> bool {
-        let string_bytes = self.property.get_bytes(object);
-        match self.case {
-            Case::Sensitive => self
-                .values
+impl<'col> StrAnyOf<'col> {
+    pub fn filter(
+        property: &'col Property,
+        values: &[Option<&str>],
+        case: Case,
+    ) -> Result<Filter<'col>> {
+        if property.data_type == DataType::String {
+            let values = values
                 .iter()
-                .any(|item| item.as_slice() == string_bytes),
-            Case::Insensitive => unsafe {
-                let lowercase_string = std::str::from_utf8_unchecked(object).to_lowercase();
-                let lowercase_bytes = lowercase_string.as_bytes();
-                self.values
+                .map(|v| {
+                    if case == Case::Insensitive {
+                        v.map(|s| s.to_lowercase())
+                    } else {
+                        v.map(|s| s.to_string())
+                    }
+                })
+                .collect();
+            Ok(Filter::StrAnyOf(StrAnyOf {
+                property,
+                values,
+                case,
+            }))
+        } else {
+            illegal_arg("Property does not support this filter.")
+        }
+    }
+}
+
+pub struct StringListContains<'col> {
+    property: &'col Property,
+    value: Option<String>,
+    case: Case,
+}
+
+impl<'col> Condition for StringListContains<'col> {
+    fn evaluate(&self, object: &[u8]) -> Result<bool> {
+        if let Some(list) = self.property.try_get_string_list(object)? {
+            if self.case == Case::Insensitive {
+                Ok(list
                     .iter()
-                    .any(|item| item.as_slice() == lowercase_bytes)
-            },
+                    .any(|s| s.map(|s| s.to_lowercase()) == self.value))
+            } else {
+                Ok(list.contains(&self.value.as_deref()))
+            }
+        } else {
+            Ok(false)
         }
     }
+
+    fn estimated_cost(&self) -> u32 {
+        5
+    }
 }
 
-impl StrAnyOf {
-    pub fn filter(property: Property, values: Vec<Vec<u8>>, case: Case) -> Filter {
-        Filter::StrAnyOf(StrAnyOf {
-            property,
-            values,
-            case,
-        })
+impl<'col> StringListContains<'col> {
+    /// Matches lists that contain `value`. A `None` value matches lists with a null element.
+    pub fn filter(
+        property: &'col Property,
+        value: Option<&str>,
+        case: Case,
+    ) -> Result<Filter<'col>> {
+        if property.data_type == DataType::StringList {
+            let value = if case == Case::Insensitive {
+                value.map(|s| s.to_lowercase())
+            } else {
+                value.map(|s| s.to_string())
+            };
+            Ok(Filter::StringListContains(StringListContains {
+                property,
+                value,
+                case,
+            }))
+        } else {
+            illegal_arg("Property does not support this filter.")
+        }
     }
-}*/
+}
+
+pub struct ListLength<'col> {
+    property: &'col Property,
+    lower: usize,
+    upper: usize,
+}
+
+impl<'col> Condition for ListLength<'col> {
+    fn evaluate(&self, object: &[u8]) -> Result<bool> {
+        if let Some(len) = self.property.get_length(object) {
+            Ok(self.lower <= len && self.upper >= len)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+impl<'col> ListLength<'col> {
+    pub fn filter(property: &'col Property, lower: usize, upper: usize) -> Result<Filter<'col>> {
+        if property.data_type.is_dynamic() {
+            Ok(Filter::ListLength(ListLength {
+                property,
+                lower,
+                upper,
+            }))
+        } else {
+            illegal_arg("Property does not support this filter.")
+        }
+    }
+}
 
 pub struct And<'col> {
     filters: Vec<Filter<'col>>,
 }
 
 impl<'col> Condition for And<'col> {
-    fn evaluate(&self, object: &[u8]) -> bool {
+    fn evaluate(&self, object: &[u8]) -> Result<bool> {
         for filter in &self.filters {
-            if !filter.evaluate(object) {
-                return false;
+            if !filter.evaluate(object)? {
+                return Ok(false);
             }
         }
-        true
+        Ok(true)
+    }
+
+    fn estimated_cost(&self) -> u32 {
+        self.filters.iter().map(|f| f.estimated_cost()).sum()
     }
 }
 
 impl<'col> And<'col> {
-    pub fn filter(filters: Vec<Filter<'col>>) -> Filter<'col> {
+    pub fn filter(mut filters: Vec<Filter<'col>>) -> Filter<'col> {
+        filters.sort_by_key(|f| f.estimated_cost());
         Filter::And(And { filters })
     }
 }
@@ -236,18 +623,23 @@ pub struct Or<'col> {
 }
 
 impl<'col> Condition for Or<'col> {
-    fn evaluate(&self, object: &[u8]) -> bool {
+    fn evaluate(&self, object: &[u8]) -> Result<bool> {
         for filter in &self.filters {
-            if filter.evaluate(object) {
-                return true;
+            if filter.evaluate(object)? {
+                return Ok(true);
             }
         }
-        false
+        Ok(false)
+    }
+
+    fn estimated_cost(&self) -> u32 {
+        self.filters.iter().map(|f| f.estimated_cost()).sum()
     }
 }
 
 impl<'col> Or<'col> {
-    pub fn filter(filters: Vec<Filter<'col>>) -> Filter<'col> {
+    pub fn filter(mut filters: Vec<Filter<'col>>) -> Filter<'col> {
+        filters.sort_by_key(|f| f.estimated_cost());
         Filter::Or(Or { filters })
     }
 }
@@ -257,8 +649,12 @@ pub struct Not<'col> {
 }
 
 impl<'col> Condition for Not<'col> {
-    fn evaluate(&self, object: &[u8]) -> bool {
-        self.filter.evaluate(object)
+    fn evaluate(&self, object: &[u8]) -> Result<bool> {
+        Ok(!self.filter.evaluate(object)?)
+    }
+
+    fn estimated_cost(&self) -> u32 {
+        self.filter.estimated_cost()
     }
 }
 
@@ -269,3 +665,532 @@ impl<'col> Not<'col> {
         })
     }
 }
+
+/// Wraps a filter and counts how often it is evaluated.
+#[cfg(test)]
+pub struct Counting<'col> {
+    filter: Box<Filter<'col>>,
+    count: &'col std::cell::Cell<u32>,
+}
+
+#[cfg(test)]
+impl<'col> Condition for Counting<'col> {
+    fn evaluate(&self, object: &[u8]) -> Result<bool> {
+        self.count.set(self.count.get() + 1);
+        self.filter.evaluate(object)
+    }
+
+    fn estimated_cost(&self) -> u32 {
+        self.filter.estimated_cost()
+    }
+}
+
+#[cfg(test)]
+impl<'col> Counting<'col> {
+    pub fn filter(filter: Filter<'col>, count: &'col std::cell::Cell<u32>) -> Filter<'col> {
+        Filter::Counting(Counting {
+            filter: Box::new(filter),
+            count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::IsarCollection;
+    use crate::error::IsarError;
+    use crate::instance::IsarInstance;
+    use crate::txn::IsarTxn;
+    use crate::utils::debug::align;
+    use crate::{col, isar};
+    use std::cell::Cell;
+
+    fn count_filter<'col>(
+        isar: &IsarInstance,
+        col: &'col IsarCollection,
+        txn: &IsarTxn,
+        filter: Filter<'col>,
+    ) -> u32 {
+        let mut qb = isar.create_query_builder(col);
+        qb.set_filter(filter);
+        qb.build().count(txn).unwrap()
+    }
+
+    fn fill_strings(col: &IsarCollection, txn: &IsarTxn, values: &[Option<&str>]) {
+        for value in values {
+            let mut builder = col.get_object_builder();
+            builder.write_string(*value);
+            col.put(txn, None, builder.finish().as_bytes()).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_int_list_between() {
+        isar!(isar, col => col!(field => IntList));
+        let property = col.get_properties().first().unwrap();
+
+        let get_object = |value: Option<&[i32]>| {
+            let mut builder = col.get_object_builder();
+            builder.write_int_list(value);
+            builder.finish()
+        };
+        let null = get_object(None);
+        let empty = get_object(Some(&[]));
+        let mixed = get_object(Some(&[1, 5, 20]));
+        let in_range = get_object(Some(&[5, 10]));
+
+        let any = IntListAnyBetween::filter(property, 5, 10).unwrap();
+        assert!(!any.evaluate(null.as_bytes()).unwrap());
+        assert!(!any.evaluate(empty.as_bytes()).unwrap());
+        assert!(any.evaluate(mixed.as_bytes()).unwrap());
+        assert!(any.evaluate(in_range.as_bytes()).unwrap());
+        assert!(!any.evaluate(get_object(Some(&[4, 11])).as_bytes()).unwrap());
+
+        let all = IntListAllBetween::filter(property, 5, 10).unwrap();
+        assert!(!all.evaluate(null.as_bytes()).unwrap());
+        assert!(all.evaluate(empty.as_bytes()).unwrap());
+        assert!(!all.evaluate(mixed.as_bytes()).unwrap());
+        assert!(all.evaluate(in_range.as_bytes()).unwrap());
+
+        assert!(DoubleListAnyBetween::filter(property, 5.0, 10.0).is_err());
+    }
+
+    #[test]
+    fn test_double_list_between() {
+        isar!(isar, col => col!(field => DoubleList));
+        let property = col.get_properties().first().unwrap();
+
+        let get_object = |value: Option<&[f64]>| {
+            let mut builder = col.get_object_builder();
+            builder.write_double_list(value);
+            builder.finish()
+        };
+
+        let any = DoubleListAnyBetween::filter(property, 1.0, 2.0).unwrap();
+        assert!(!any.evaluate(get_object(None).as_bytes()).unwrap());
+        assert!(!any.evaluate(get_object(Some(&[])).as_bytes()).unwrap());
+        assert!(any
+            .evaluate(get_object(Some(&[0.5, 1.5])).as_bytes())
+            .unwrap());
+        assert!(!any
+            .evaluate(get_object(Some(&[0.5, f64::NAN])).as_bytes())
+            .unwrap());
+
+        let all = DoubleListAllBetween::filter(property, 1.0, 2.0).unwrap();
+        assert!(!all.evaluate(get_object(None).as_bytes()).unwrap());
+        assert!(all.evaluate(get_object(Some(&[])).as_bytes()).unwrap());
+        assert!(!all
+            .evaluate(get_object(Some(&[0.5, 1.5])).as_bytes())
+            .unwrap());
+        assert!(all
+            .evaluate(get_object(Some(&[1.0, 2.0])).as_bytes())
+            .unwrap());
+
+        assert!(IntListAllBetween::filter(property, 0, 1).is_err());
+    }
+
+    #[test]
+    fn test_list_filter_corrupted() {
+        let property = Property::new_debug(DataType::IntList, 0);
+        let object = align(&[8, 0, 0, 0, 3, 0, 0, 0, 5, 0, 0, 0, 6, 0, 0, 0]);
+
+        let filter = IntListAnyBetween::filter(&property, 5, 10).unwrap();
+        let result = filter.evaluate(&object);
+        assert!(matches!(result, Err(IsarError::DbCorrupted { .. })));
+
+        let filter = Not::filter(filter);
+        let result = filter.evaluate(&object);
+        assert!(matches!(result, Err(IsarError::DbCorrupted { .. })));
+    }
+
+    #[test]
+    fn test_not() {
+        isar!(isar, col => col!(field => Int));
+        let property = col.get_properties().first().unwrap();
+
+        let get_object = |value: i32| {
+            let mut builder = col.get_object_builder();
+            builder.write_int(value);
+            builder.finish()
+        };
+
+        let filter = Not::filter(IntBetween::filter(property, 5, 10).unwrap());
+        assert!(!filter.evaluate(get_object(5).as_bytes()).unwrap());
+        assert!(!filter.evaluate(get_object(7).as_bytes()).unwrap());
+        assert!(!filter.evaluate(get_object(10).as_bytes()).unwrap());
+        assert!(filter.evaluate(get_object(4).as_bytes()).unwrap());
+        assert!(filter.evaluate(get_object(11).as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_and_or_evaluate_cheap_filters_first() {
+        let int_count = Cell::new(0);
+        let str_count = Cell::new(0);
+        isar!(isar, col => col!(f1 => Int, f2 => String));
+        let txn = isar.begin_txn(true).unwrap();
+        for i in 0..10 {
+            let mut builder = col.get_object_builder();
+            builder.write_int(i);
+            builder.write_string(Some(&format!("value {}", i)));
+            col.put(&txn, None, builder.finish().as_bytes()).unwrap();
+        }
+        let p1 = &col.get_properties()[0];
+        let p2 = &col.get_properties()[1];
+        let filters = || {
+            int_count.set(0);
+            str_count.set(0);
+            let contains = StrContains::filter(p2, "value", Case::Sensitive).unwrap();
+            let between = IntBetween::filter(p1, 2, 4).unwrap();
+            vec![
+                Counting::filter(contains, &str_count),
+                Counting::filter(between, &int_count),
+            ]
+        };
+
+        let and = And::filter(filters());
+        assert_eq!(and.estimated_cost(), 5);
+        assert_eq!(count_filter(&isar, col, &txn, and), 3);
+        assert_eq!(int_count.get(), 10);
+        assert_eq!(str_count.get(), 3);
+
+        let or = Or::filter(filters());
+        assert_eq!(count_filter(&isar, col, &txn, or), 10);
+        assert_eq!(int_count.get(), 10);
+        assert_eq!(str_count.get(), 7);
+
+        let not = Not::filter(StrContains::filter(p2, "1", Case::Sensitive).unwrap());
+        assert_eq!(not.estimated_cost(), 4);
+    }
+
+    #[test]
+    fn test_numeric_between() {
+        isar!(isar, col => col!(f1 => Byte, f2 => Int, f3 => Float, f4 => Long, f5 => Double, f6 => String));
+        let txn = isar.begin_txn(true).unwrap();
+        for value in &[-5.5, -1.0, 0.0, 1.0, 2.5, 3.0, 200.0, f64::NAN] {
+            let mut builder = col.get_object_builder();
+            builder.write_byte(value.abs() as u8);
+            builder.write_int(*value as i32);
+            builder.write_float(*value as f32);
+            builder.write_long(*value as i64);
+            builder.write_double(*value);
+            builder.write_string(None);
+            col.put(&txn, None, builder.finish().as_bytes()).unwrap();
+        }
+        let p = col.get_properties();
+
+        let bounds: [(f64, f64); 5] = [
+            (-1.0, 3.0),
+            (1.0, 1.0),
+            (3.0, -1.0),
+            (-100.0, 1e3),
+            (0.0, 250.0),
+        ];
+        for (lower, upper) in bounds.iter().copied() {
+            let count = |typed: Result<Filter>, numeric: Result<Filter>| {
+                let typed = count_filter(&isar, col, &txn, typed.unwrap());
+                let numeric = count_filter(&isar, col, &txn, numeric.unwrap());
+                assert_eq!(typed, numeric, "{} {}", lower, upper);
+            };
+            let byte_bounds = (lower.clamp(0.0, 255.0) as u8, upper.clamp(0.0, 255.0) as u8);
+            if upper >= 0.0 && lower <= 255.0 {
+                let typed = ByteBetween::filter(&p[0], byte_bounds.0, byte_bounds.1);
+                count(typed, NumericBetween::filter(&p[0], lower, upper));
+            }
+            let typed = IntBetween::filter(&p[1], lower as i32, upper as i32);
+            count(typed, NumericBetween::filter(&p[1], lower, upper));
+            let typed = FloatBetween::filter(&p[2], lower as f32, upper as f32);
+            count(typed, NumericBetween::filter(&p[2], lower, upper));
+            let typed = LongBetween::filter(&p[3], lower as i64, upper as i64);
+            count(typed, NumericBetween::filter(&p[3], lower, upper));
+            let typed = DoubleBetween::filter(&p[4], lower, upper);
+            count(typed, NumericBetween::filter(&p[4], lower, upper));
+        }
+
+        let f = NumericBetween::filter(&p[1], 1.2, 1.8).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 0);
+        let f = NumericBetween::filter(&p[1], -5.5, 0.5).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 4);
+        let f = NumericBetween::filter(&p[0], 300.0, 400.0).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 0);
+        let f = NumericBetween::filter(&p[4], f64::NAN, f64::NAN).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 1);
+
+        assert!(NumericBetween::filter(&p[1], f64::NAN, 1.0).is_err());
+        assert!(NumericBetween::filter(&p[5], 0.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_float_equal() {
+        isar!(isar, col => col!(f1 => Float, f2 => Double));
+        let txn = isar.begin_txn(true).unwrap();
+        for value in &[1.0, 1.05, 1.2, -1.0, f64::NAN] {
+            let mut builder = col.get_object_builder();
+            builder.write_float(*value as f32);
+            builder.write_double(*value);
+            col.put(&txn, None, builder.finish().as_bytes()).unwrap();
+        }
+        let p1 = &col.get_properties()[0];
+        let p2 = &col.get_properties()[1];
+
+        let f = FloatEqual::filter(p1, 1.0, 0.1).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 2);
+        let f = FloatEqual::filter(p1, 1.0, 0.0).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 1);
+        let f = FloatEqual::filter(p1, f32::NAN, 0.1).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 1);
+
+        let f = DoubleEqual::filter(p2, 1.1, 0.11).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 3);
+        let f = DoubleEqual::filter(p2, -1.0000001, 0.000001).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 1);
+
+        assert!(FloatEqual::filter(p1, 1.0, -0.1).is_err());
+        assert!(FloatEqual::filter(p1, 1.0, f32::NAN).is_err());
+        assert!(DoubleEqual::filter(p1, 1.0, 0.1).is_err());
+    }
+
+    #[test]
+    fn test_string_filters() {
+        isar!(isar, col => col!(field => String));
+        let txn = isar.begin_txn(true).unwrap();
+        fill_strings(
+            col,
+            &txn,
+            &[Some("Hello World"), Some("hello"), Some("world"), None],
+        );
+        let p = col.get_properties().first().unwrap();
+
+        let f = StrStartsWith::filter(p, "hello", Case::Sensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 1);
+        let f = StrStartsWith::filter(p, "hello", Case::Insensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 2);
+
+        let f = StrEndsWith::filter(p, "world", Case::Sensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 1);
+        let f = StrEndsWith::filter(p, "WORLD", Case::Insensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 2);
+
+        let f = StrContains::filter(p, "o w", Case::Sensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 0);
+        let f = StrContains::filter(p, "o w", Case::Insensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 1);
+        let f = StrContains::filter(p, "l", Case::Sensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 3);
+
+        let f = StrStartsWith::filter(p, "", Case::Sensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 3);
+    }
+
+    #[test]
+    fn test_string_filters_wrong_type() {
+        isar!(isar, col => col!(field => Int));
+        let p = col.get_properties().first().unwrap();
+
+        assert!(StrStartsWith::filter(p, "a", Case::Sensitive).is_err());
+        assert!(StrEndsWith::filter(p, "a", Case::Sensitive).is_err());
+        assert!(StrContains::filter(p, "a", Case::Sensitive).is_err());
+    }
+
+    #[test]
+    fn test_str_any_of() {
+        isar!(isar, col => col!(field => String));
+        let txn = isar.begin_txn(true).unwrap();
+        fill_strings(col, &txn, &[Some("a"), Some("B"), Some("c"), None]);
+        let p = col.get_properties().first().unwrap();
+
+        let f = StrAnyOf::filter(p, &[Some("a"), Some("b")], Case::Sensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 1);
+
+        let f = StrAnyOf::filter(p, &[Some("A"), Some("b")], Case::Insensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 2);
+
+        let f = StrAnyOf::filter(p, &[None, Some("c")], Case::Sensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 2);
+
+        let f = StrAnyOf::filter(p, &[None], Case::Insensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 1);
+
+        let f = StrAnyOf::filter(p, &[], Case::Sensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 0);
+    }
+
+    #[test]
+    fn test_str_any_of_wrong_type() {
+        isar!(isar, col => col!(field => Int));
+        let p = col.get_properties().first().unwrap();
+
+        assert!(StrAnyOf::filter(p, &[Some("a")], Case::Sensitive).is_err());
+    }
+
+    #[test]
+    fn test_string_list_contains() {
+        isar!(isar, col => col!(field => StringList));
+        let txn = isar.begin_txn(true).unwrap();
+        let lists: Vec<Option<&[Option<&str>]>> = vec![
+            Some(&[Some("rust"), Some("dart")]),
+            Some(&[Some("Rust"), None]),
+            Some(&[Some("go")]),
+            Some(&[]),
+            None,
+        ];
+        for list in lists {
+            let mut builder = col.get_object_builder();
+            builder.write_string_list(list);
+            col.put(&txn, None, builder.finish().as_bytes()).unwrap();
+        }
+        let p = col.get_properties().first().unwrap();
+
+        let f = StringListContains::filter(p, Some("rust"), Case::Sensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 1);
+
+        let f = StringListContains::filter(p, Some("RUST"), Case::Insensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 2);
+
+        let f = StringListContains::filter(p, Some("java"), Case::Insensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 0);
+
+        let f = StringListContains::filter(p, None, Case::Sensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 1);
+    }
+
+    #[test]
+    fn test_string_list_contains_wrong_type() {
+        isar!(isar, col => col!(field => String));
+        let p = col.get_properties().first().unwrap();
+
+        assert!(StringListContains::filter(p, Some("a"), Case::Sensitive).is_err());
+    }
+
+    #[test]
+    fn test_bool_equal() {
+        isar!(isar, col => col!(field => Byte));
+        let txn = isar.begin_txn(true).unwrap();
+        for value in &[Some(true), Some(false), Some(true), None] {
+            let mut builder = col.get_object_builder();
+            builder.write_byte(Property::bool_to_byte(*value));
+            col.put(&txn, None, builder.finish().as_bytes()).unwrap();
+        }
+        let p = col.get_properties().first().unwrap();
+
+        let f = BoolEqual::filter(p, Some(true)).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 2);
+
+        let f = BoolEqual::filter(p, Some(false)).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 1);
+
+        let f = BoolEqual::filter(p, None).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 1);
+    }
+
+    #[test]
+    fn test_bool_equal_wrong_type() {
+        isar!(isar, col => col!(field => Int));
+        let p = col.get_properties().first().unwrap();
+        assert!(BoolEqual::filter(p, Some(true)).is_err());
+    }
+
+    #[test]
+    fn test_str_equal() {
+        isar!(isar, col => col!(field => String));
+        let txn = isar.begin_txn(true).unwrap();
+        fill_strings(col, &txn, &[Some("hello"), Some("Hello"), Some(""), None]);
+        let p = col.get_properties().first().unwrap();
+
+        let f = StrEqual::filter(p, Some("hello"), Case::Sensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 1);
+
+        let f = StrEqual::filter(p, Some("HELLO"), Case::Insensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 2);
+
+        let f = StrEqual::filter(p, Some("hell"), Case::Sensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 0);
+
+        let f = StrEqual::filter(p, Some(""), Case::Sensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 1);
+
+        let f = StrEqual::filter(p, None, Case::Sensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 1);
+
+        let f = StrEqual::filter(p, None, Case::Insensitive).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 1);
+    }
+
+    #[test]
+    fn test_float_not_equal() {
+        isar!(isar, col => col!(field => Float));
+        let p = col.get_properties().first().unwrap();
+
+        let get_object = |value: f32| {
+            let mut builder = col.get_object_builder();
+            builder.write_float(value);
+            builder.finish()
+        };
+
+        let filter = FloatNotEqual::filter(p, 5.5).unwrap();
+        assert!(!filter.evaluate(get_object(5.5).as_bytes()).unwrap());
+        assert!(filter.evaluate(get_object(5.6).as_bytes()).unwrap());
+        assert!(filter.evaluate(get_object(f32::NAN).as_bytes()).unwrap());
+
+        let filter = FloatNotEqual::filter(p, f32::NAN).unwrap();
+        assert!(!filter.evaluate(get_object(f32::NAN).as_bytes()).unwrap());
+        assert!(filter.evaluate(get_object(5.5).as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_double_not_equal() {
+        isar!(isar, col => col!(field => Double));
+        let p = col.get_properties().first().unwrap();
+
+        let get_object = |value: f64| {
+            let mut builder = col.get_object_builder();
+            builder.write_double(value);
+            builder.finish()
+        };
+
+        let filter = DoubleNotEqual::filter(p, 5.5).unwrap();
+        assert!(!filter.evaluate(get_object(5.5).as_bytes()).unwrap());
+        assert!(filter.evaluate(get_object(5.6).as_bytes()).unwrap());
+        assert!(filter.evaluate(get_object(f64::NAN).as_bytes()).unwrap());
+
+        let filter = DoubleNotEqual::filter(p, f64::NAN).unwrap();
+        assert!(!filter.evaluate(get_object(f64::NAN).as_bytes()).unwrap());
+        assert!(filter.evaluate(get_object(5.5).as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_list_length() {
+        isar!(isar, col => col!(field => IntList));
+        let txn = isar.begin_txn(true).unwrap();
+        let lists: &[Option<&[i32]>] = &[None, Some(&[]), Some(&[1]), Some(&[1, 2, 3])];
+        for list in lists {
+            let mut builder = col.get_object_builder();
+            builder.write_int_list(*list);
+            col.put(&txn, None, builder.finish().as_bytes()).unwrap();
+        }
+        let p = col.get_properties().first().unwrap();
+
+        let f = ListLength::filter(p, 0, 0).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 1);
+
+        let f = ListLength::filter(p, 1, 3).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 2);
+
+        let f = ListLength::filter(p, 2, 3).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 1);
+
+        let f = ListLength::filter(p, 0, usize::MAX).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 3);
+
+        let f = ListLength::filter(p, 4, usize::MAX).unwrap();
+        assert_eq!(count_filter(&isar, col, &txn, f), 0);
+    }
+
+    #[test]
+    fn test_list_length_wrong_type() {
+        isar!(isar, col => col!(field => Int));
+        let p = col.get_properties().first().unwrap();
+
+        assert!(ListLength::filter(p, 0, 1).is_err());
+    }
+}
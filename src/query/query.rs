@@ -1,16 +1,23 @@
-use crate::error::Result;
+use crate::collection::IsarCollection;
+use crate::error::{illegal_arg, IsarError, Result};
+use crate::index::IndexType;
 use crate::lmdb::db::Db;
 use crate::map_option;
+use crate::object::data_type::DataType;
 use crate::object::object_id::ObjectId;
 use crate::object::property::Property;
+use crate::object::property_value::PropertyValue;
 use crate::query::filter::*;
 use crate::query::where_clause::WhereClause;
-use crate::query::where_executor::WhereExecutor;
+use crate::query::where_executor::{WhereExecutor, WhereExecutorIter};
 use crate::txn::IsarTxn;
+use enum_ordinalize::Ordinalize;
 use hashbrown::HashSet;
+use std::cmp::Ordering;
 use std::hash::Hasher;
 use wyhash::WyHash;
 
+#[derive(Copy, Clone, Eq, PartialEq)]
 pub enum Sort {
     Ascending,
     Descending,
@@ -21,6 +28,42 @@ pub enum Case {
     Insensitive,
 }
 
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Ordinalize)]
+#[repr(u8)]
+pub enum AggregationOp {
+    Min,
+    Max,
+    Sum,
+    Average,
+    Count,
+}
+
+/// Describes how a query is executed. Returned by `Query::explain()`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct QueryPlan {
+    pub where_clauses: Vec<WhereClausePlan>,
+    /// The filter is evaluated for every object found by the where clauses.
+    pub post_filter: bool,
+    /// All results are collected and sorted in memory.
+    pub in_memory_sort: bool,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct WhereClausePlan {
+    pub index_type: IndexType,
+    /// The where clause iterates the whole index.
+    pub unbounded: bool,
+}
+
+impl QueryPlan {
+    /// Returns true if the query iterates all objects of the collection without using an index.
+    pub fn is_full_scan(&self) -> bool {
+        self.where_clauses
+            .iter()
+            .any(|wc| wc.index_type == IndexType::Primary && wc.unbounded)
+    }
+}
+
 pub struct Query<'col> {
     where_clauses: Vec<WhereClause>,
     where_clauses_overlapping: bool,
@@ -29,25 +72,28 @@ pub struct Query<'col> {
     secondary_dup_db: Option<Db>,
     filter: Option<Filter<'col>>,
     sort: Vec<(Property, Sort)>,
-    distinct: Option<Vec<Property>>,
+    distinct: Option<(Vec<Property>, bool)>,
     offset_limit: Option<(usize, usize)>,
+    projection: Option<(&'col IsarCollection, Vec<Property>)>,
 }
 
 impl<'col> Query<'col> {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         where_clauses: Vec<WhereClause>,
+        where_clauses_overlapping: bool,
         primary_db: Db,
         secondary_db: Option<Db>,
         secondary_dup_db: Option<Db>,
         filter: Option<Filter<'col>>,
         sort: Vec<(Property, Sort)>,
-        distinct: Option<Vec<Property>>,
+        distinct: Option<(Vec<Property>, bool)>,
         offset_limit: Option<(usize, usize)>,
+        projection: Option<(&'col IsarCollection, Vec<Property>)>,
     ) -> Self {
         Query {
             where_clauses,
-            where_clauses_overlapping: true,
+            where_clauses_overlapping,
             primary_db,
             secondary_db,
             secondary_dup_db,
@@ -55,85 +101,202 @@ impl<'col> Query<'col> {
             sort,
             distinct,
             offset_limit,
+            projection,
         }
     }
 
-    fn execute_raw<'txn, F>(&self, txn: &'txn IsarTxn, mut callback: F) -> Result<()>
-    where
-        F: FnMut(&'txn ObjectId, &'txn [u8]) -> bool,
-    {
-        let lmdb_txn = txn.get_txn();
+    pub fn explain(&self) -> QueryPlan {
+        let where_clauses = self
+            .where_clauses
+            .iter()
+            .map(|wc| WhereClausePlan {
+                index_type: wc.index_type,
+                unbounded: wc.is_unbounded(),
+            })
+            .collect();
+        QueryPlan {
+            where_clauses,
+            post_filter: self.filter.is_some(),
+            in_memory_sort: !self.sort.is_empty(),
+        }
+    }
+
+    fn create_executor<'txn>(&self, txn: &'txn IsarTxn) -> Result<WhereExecutor<'_, 'txn>> {
+        let lmdb_txn = txn.get_txn()?;
         let primary_cursor = self.primary_db.cursor(lmdb_txn)?;
         let secondary_cursor = map_option!(self.secondary_db, db, db.cursor(lmdb_txn)?);
         let secondary_dup_cursor = map_option!(self.secondary_dup_db, db, db.cursor(lmdb_txn)?);
-        let mut executor = WhereExecutor::new(
+        Ok(WhereExecutor::new(
             primary_cursor,
             secondary_cursor,
             secondary_dup_cursor,
             &self.where_clauses,
             self.where_clauses_overlapping,
-        );
+        ))
+    }
+
+    fn execute_raw<'txn, F>(&self, txn: &'txn IsarTxn, mut callback: F) -> Result<()>
+    where
+        F: FnMut(&'txn ObjectId, &'txn [u8]) -> bool,
+    {
+        let mut executor = self.create_executor(txn)?;
         if let Some(filter) = &self.filter {
-            executor.run(|oid, val| {
-                if filter.evaluate(val) {
-                    callback(oid, val)
-                } else {
-                    true
+            let mut error = None;
+            executor.run(|oid, val| match filter.evaluate(val) {
+                Ok(true) => callback(oid, val),
+                Ok(false) => true,
+                Err(e) => {
+                    error = Some(e);
+                    false
                 }
-            })
+            })?;
+            error.map_or(Ok(()), Err)
         } else {
             executor.run(callback)
         }
     }
 
-    fn execute_unsorted<'txn, F>(&self, txn: &'txn IsarTxn, callback: F) -> Result<()>
+    /// Executes the query with `offset_limit` instead of the offset and limit of the query.
+    fn execute<'txn, F>(
+        &self,
+        txn: &'txn IsarTxn,
+        offset_limit: Option<(usize, usize)>,
+        callback: F,
+    ) -> Result<()>
+    where
+        F: FnMut(&'txn ObjectId, &'txn [u8]) -> bool,
+    {
+        if self.sort.is_empty() {
+            self.execute_unsorted(txn, offset_limit, callback)
+        } else {
+            self.execute_sorted(txn, offset_limit, callback)
+        }
+    }
+
+    fn execute_unsorted<'txn, F>(
+        &self,
+        txn: &'txn IsarTxn,
+        offset_limit: Option<(usize, usize)>,
+        callback: F,
+    ) -> Result<()>
     where
         F: FnMut(&'txn ObjectId, &'txn [u8]) -> bool,
     {
+        if let Some((_, 0)) = offset_limit {
+            return Ok(());
+        }
+
+        // offset and limit only count objects that passed distinct
         if self.distinct.is_some() {
-            let callback = self.add_distinct(callback);
-            if self.offset_limit.is_some() {
-                let callback = self.add_offset_limit(callback);
-                self.execute_raw(txn, callback)
+            let mut error = None;
+            if let Some(offset_limit) = offset_limit {
+                let callback = Self::add_offset_limit(offset_limit, callback);
+                let callback = self.add_distinct(&mut error, callback);
+                self.execute_raw(txn, callback)?;
             } else {
-                self.execute_raw(txn, callback)
+                let callback = self.add_distinct(&mut error, callback);
+                self.execute_raw(txn, callback)?;
             }
-        } else if self.offset_limit.is_some() {
-            let callback = self.add_offset_limit(callback);
+            error.map_or(Ok(()), Err)
+        } else if let Some(offset_limit) = offset_limit {
+            let callback = Self::add_offset_limit(offset_limit, callback);
             self.execute_raw(txn, callback)
         } else {
             self.execute_raw(txn, callback)
         }
     }
 
-    fn execute_sorted<'txn, F>(&self, _txn: &'txn IsarTxn, _callback: F) -> Result<()>
+    fn execute_sorted<'txn, F>(
+        &self,
+        txn: &'txn IsarTxn,
+        offset_limit: Option<(usize, usize)>,
+        callback: F,
+    ) -> Result<()>
     where
         F: FnMut(&'txn ObjectId, &'txn [u8]) -> bool,
     {
-        /*let mut result = vec![];
-        self.execute_raw(txn, |key,val| {
-            result.push((key,val));
+        if let Some((_, 0)) = offset_limit {
+            return Ok(());
+        }
+
+        let mut results = vec![];
+        self.execute_raw(txn, |oid, val| {
+            results.push((oid, val));
             true
+        })?;
+        let mut error = None;
+        results.sort_by(|(_, object1), (_, object2)| {
+            for (property, sort) in &self.sort {
+                let ordering = match property.compare(object1, object2) {
+                    Ok(ordering) => ordering,
+                    Err(e) => {
+                        error.get_or_insert(e);
+                        Ordering::Equal
+                    }
+                };
+                let ordering = if *sort == Sort::Descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            Ordering::Equal
         });
-        result.sort_by()
-        let callback = self.add_distinct(callback);
-        let callback = self.add_offset_limit(callback);*/
+        if let Some(e) = error {
+            return Err(e);
+        }
+
+        // distinct keeps the first object in sort order
+        if self.distinct.is_some() {
+            let mut error = None;
+            if let Some(offset_limit) = offset_limit {
+                let callback = Self::add_offset_limit(offset_limit, callback);
+                Self::call_for_all(results, self.add_distinct(&mut error, callback));
+            } else {
+                Self::call_for_all(results, self.add_distinct(&mut error, callback));
+            }
+            return error.map_or(Ok(()), Err);
+        } else if let Some(offset_limit) = offset_limit {
+            Self::call_for_all(results, Self::add_offset_limit(offset_limit, callback));
+        } else {
+            Self::call_for_all(results, callback);
+        }
         Ok(())
     }
 
-    fn add_distinct<'txn, F>(
+    fn call_for_all<'txn, F>(results: Vec<(&'txn ObjectId, &'txn [u8])>, mut callback: F)
+    where
+        F: FnMut(&'txn ObjectId, &'txn [u8]) -> bool,
+    {
+        for (oid, val) in results {
+            if !callback(oid, val) {
+                break;
+            }
+        }
+    }
+
+    /// Stops at the first object whose distinct properties cannot be hashed and stores the error
+    /// in `error`.
+    fn add_distinct<'txn, 'e, F>(
         &self,
+        error: &'e mut Option<IsarError>,
         mut callback: F,
-    ) -> impl FnMut(&'txn ObjectId, &'txn [u8]) -> bool
+    ) -> impl FnMut(&'txn ObjectId, &'txn [u8]) -> bool + 'e
     where
-        F: FnMut(&'txn ObjectId, &'txn [u8]) -> bool,
+        F: FnMut(&'txn ObjectId, &'txn [u8]) -> bool + 'e,
     {
-        let properties = self.distinct.as_ref().unwrap().clone();
+        let (properties, case_sensitive) = self.distinct.clone().unwrap();
         let mut hashes = HashSet::new();
         move |key, val| {
             let mut hasher = WyHash::default();
             for property in &properties {
-                property.hash_value(val, &mut hasher);
+                if let Err(e) = property.hash_value(val, case_sensitive, &mut hasher) {
+                    *error = Some(e);
+                    return false;
+                }
             }
             let hash = hasher.finish();
             if hashes.insert(hash) {
@@ -145,13 +308,12 @@ impl<'col> Query<'col> {
     }
 
     fn add_offset_limit<'txn, F>(
-        &self,
+        (offset, limit): (usize, usize),
         mut callback: F,
     ) -> impl FnMut(&'txn ObjectId, &'txn [u8]) -> bool
     where
         F: FnMut(&'txn ObjectId, &'txn [u8]) -> bool,
     {
-        let (offset, limit) = self.offset_limit.unwrap();
         let mut count = 0;
         move |key, value| {
             let result = if count >= offset {
@@ -168,11 +330,50 @@ impl<'col> Query<'col> {
     where
         F: FnMut(&'txn ObjectId, &'txn [u8]) -> bool,
     {
-        if self.sort.is_empty() {
-            self.execute_unsorted(txn, callback)
+        self.execute(txn, self.offset_limit, callback)
+    }
+
+    /// Like `find_all` but calls `callback` with a new object that only contains the values of
+    /// the projected properties. All other properties are null. The object is only valid
+    /// during the callback.
+    pub fn find_all_projected<F>(&self, txn: &IsarTxn, mut callback: F) -> Result<()>
+    where
+        F: FnMut(&ObjectId, &[u8]) -> bool,
+    {
+        let (collection, projection) = if let Some(projection) = &self.projection {
+            projection
         } else {
-            self.execute_sorted(txn, callback)
-        }
+            return illegal_arg("The query has no projection.");
+        };
+        self.find_all(txn, |oid, object| {
+            let mut builder = collection.get_object_builder();
+            for property in collection.get_properties() {
+                if projection.contains(property) {
+                    // the value is read from the same property so it always matches
+                    builder
+                        .write_value(&PropertyValue::read(property, object))
+                        .unwrap();
+                } else {
+                    builder.write_null();
+                }
+            }
+            callback(oid, builder.finish().as_bytes())
+        })
+    }
+
+    /// Returns a lazy iterator over the matching objects. Sorted queries are executed eagerly.
+    pub fn iter<'a, 'txn>(&'a self, txn: &'txn IsarTxn) -> Result<QueryIter<'a, 'txn>> {
+        let source = if self.sort.is_empty() {
+            QueryIterSource::Executor(self.create_executor(txn)?.into_iter())
+        } else {
+            QueryIterSource::Buffer(self.find_all_vec(txn)?.into_iter())
+        };
+        Ok(QueryIter {
+            query: self,
+            source,
+            hashes: HashSet::new(),
+            count: 0,
+        })
     }
 
     pub fn find_all_vec<'txn>(
@@ -187,6 +388,40 @@ impl<'col> Query<'col> {
         Ok(results)
     }
 
+    /// Returns the first matching object. Unsorted queries stop scanning after the first match.
+    pub fn find_first<'txn>(
+        &self,
+        txn: &'txn IsarTxn,
+    ) -> Result<Option<(&'txn ObjectId, &'txn [u8])>> {
+        let mut first = None;
+        self.find_all(txn, |key, value| {
+            first = Some((key, value));
+            false
+        })?;
+        Ok(first)
+    }
+
+    /// Returns the objects from `offset` to `offset + limit` and the number of all matching
+    /// objects in a single scan. The offset and limit of the query are ignored.
+    #[allow(clippy::type_complexity)]
+    pub fn find_page<'txn>(
+        &self,
+        txn: &'txn IsarTxn,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<(&'txn ObjectId, &'txn [u8])>, u32)> {
+        let mut page = vec![];
+        let mut total = 0;
+        self.execute(txn, None, |oid, object| {
+            if total >= offset && page.len() < limit {
+                page.push((oid, object));
+            }
+            total += 1;
+            true
+        })?;
+        Ok((page, total as u32))
+    }
+
     pub fn count(&self, txn: &IsarTxn) -> Result<u32> {
         let mut counter = 0;
         self.find_all(txn, &mut |_, _| {
@@ -195,6 +430,123 @@ impl<'col> Query<'col> {
         })?;
         Ok(counter)
     }
+
+    /// Counts the matching objects using only the where clause cursors. Falls back to `count`
+    /// if the query has a filter, sort, distinct or offset / limit.
+    pub fn count_fast(&self, txn: &IsarTxn) -> Result<u32> {
+        if self.filter.is_some()
+            || !self.sort.is_empty()
+            || self.distinct.is_some()
+            || self.offset_limit.is_some()
+        {
+            return self.count(txn);
+        }
+        self.create_executor(txn)?.count()
+    }
+
+    /// Aggregates the non-null values of a numeric property of all matching objects. Min, max
+    /// and average return `None` if there are no values.
+    pub fn aggregate(
+        &self,
+        txn: &IsarTxn,
+        property: Property,
+        op: AggregationOp,
+    ) -> Result<Option<f64>> {
+        let get_value: fn(&Property, &[u8]) -> f64 = match property.data_type {
+            DataType::Int => |p, o| p.get_int(o) as f64,
+            DataType::Long => |p, o| p.get_long(o) as f64,
+            DataType::Float => |p, o| p.get_float(o) as f64,
+            DataType::Double => |p, o| p.get_double(o),
+            _ => return illegal_arg("Only numeric properties can be aggregated."),
+        };
+
+        let mut count = 0u32;
+        let mut result: Option<f64> = None;
+        self.execute_unsorted(txn, self.offset_limit, |_, object| {
+            if property.is_null(object) {
+                return true;
+            }
+            let value = get_value(&property, object);
+            count += 1;
+            result = Some(match (&op, result) {
+                (_, None) => value,
+                (AggregationOp::Min, Some(min)) => min.min(value),
+                (AggregationOp::Max, Some(max)) => max.max(value),
+                (_, Some(sum)) => sum + value,
+            });
+            true
+        })?;
+
+        let aggregate = match op {
+            AggregationOp::Min | AggregationOp::Max => result,
+            AggregationOp::Sum => Some(result.unwrap_or(0.0)),
+            AggregationOp::Average => result.map(|sum| sum / count as f64),
+            AggregationOp::Count => Some(count as f64),
+        };
+        Ok(aggregate)
+    }
+}
+
+enum QueryIterSource<'a, 'txn> {
+    Executor(WhereExecutorIter<'a, 'txn>),
+    Buffer(std::vec::IntoIter<(&'txn ObjectId, &'txn [u8])>),
+}
+
+pub struct QueryIter<'a, 'txn> {
+    query: &'a Query<'a>,
+    source: QueryIterSource<'a, 'txn>,
+    hashes: HashSet<u64>,
+    count: usize,
+}
+
+impl<'a, 'txn> QueryIter<'a, 'txn> {
+    fn is_distinct(&mut self, object: &[u8]) -> Result<bool> {
+        if let Some((properties, case_sensitive)) = &self.query.distinct {
+            let mut hasher = WyHash::default();
+            for property in properties {
+                property.hash_value(object, *case_sensitive, &mut hasher)?;
+            }
+            Ok(self.hashes.insert(hasher.finish()))
+        } else {
+            Ok(true)
+        }
+    }
+}
+
+impl<'a, 'txn> Iterator for QueryIter<'a, 'txn> {
+    type Item = Result<(&'txn ObjectId, &'txn [u8])>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (offset, limit) = self.query.offset_limit.unwrap_or((0, usize::MAX));
+        loop {
+            if self.count >= offset.saturating_add(limit) {
+                return None;
+            }
+            let (oid, object) = match &mut self.source {
+                QueryIterSource::Executor(iter) => match iter.next()? {
+                    Ok(entry) => entry,
+                    Err(e) => return Some(Err(e)),
+                },
+                QueryIterSource::Buffer(iter) => return iter.next().map(Ok),
+            };
+            if let Some(filter) = &self.query.filter {
+                match filter.evaluate(object) {
+                    Ok(true) => {}
+                    Ok(false) => continue,
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+            match self.is_distinct(object) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+            self.count += 1;
+            if self.count > offset {
+                return Some(Ok((oid, object)));
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -203,19 +555,23 @@ mod tests {
     use crate::instance::IsarInstance;
     use crate::object::object_id::ObjectId;
     use crate::{col, ind, isar, set};
+    use itertools::Itertools;
+    use std::cell::Cell;
 
     fn get_col(data: Vec<(i32, String)>) -> (IsarInstance, Vec<ObjectId>) {
         isar!(isar, col => col!(field1 => Int, field2 => String; ind!(field1, field2; true), ind!(field2)));
-        let mut txn = isar.begin_txn(true).unwrap();
         let mut ids = vec![];
-        for (f1, f2) in data {
-            let mut o = col.get_object_builder();
-            o.write_int(f1);
-            o.write_string(Some(&f2));
-            let bytes = o.finish();
-            ids.push(col.put(&mut txn, None, bytes.as_bytes()).unwrap());
+        {
+            let mut txn = isar.begin_txn(true).unwrap();
+            for (f1, f2) in data {
+                let mut o = col.get_object_builder();
+                o.write_int(f1);
+                o.write_string(Some(&f2));
+                let bytes = o.finish();
+                ids.push(col.put(&txn, None, bytes.as_bytes()).unwrap());
+            }
+            txn.commit().unwrap();
         }
-        txn.commit().unwrap();
         (isar, ids)
     }
 
@@ -235,6 +591,423 @@ mod tests {
         assert_eq!(keys(results), vec![ids[0], ids[1]]);
     }
 
+    #[test]
+    fn test_explain() {
+        let (isar, _) = get_col(vec![(1, "a".to_string())]);
+        let col = isar.get_collection(0).unwrap();
+        let property = &col.get_properties()[0];
+        let wc_plan = |index_type, unbounded| WhereClausePlan {
+            index_type,
+            unbounded,
+        };
+
+        let plan = isar.create_query_builder(col).build().explain();
+        assert_eq!(plan.where_clauses, vec![wc_plan(IndexType::Primary, true)]);
+        assert!(plan.is_full_scan());
+        assert!(!plan.post_filter);
+        assert!(!plan.in_memory_sort);
+
+        let mut qb = isar.create_query_builder(col);
+        let mut wc = col.create_secondary_where_clause(0).unwrap();
+        wc.add_int(1, 2);
+        qb.add_where_clause(wc, true, true);
+        qb.add_where_clause(col.create_secondary_where_clause(1).unwrap(), true, true);
+        qb.set_filter(IntBetween::filter(property, 1, 1).unwrap());
+        qb.add_sort(property.clone(), Sort::Ascending).unwrap();
+        let plan = qb.build().explain();
+        assert_eq!(
+            plan.where_clauses,
+            vec![
+                wc_plan(IndexType::Secondary, false),
+                wc_plan(IndexType::SecondaryDup, true)
+            ]
+        );
+        assert!(!plan.is_full_scan());
+        assert!(plan.post_filter);
+        assert!(plan.in_memory_sort);
+
+        let mut qb = isar.create_query_builder(col);
+        let mut wc = col.create_primary_where_clause();
+        wc.add_oid(ObjectId::new(0, 0, 0, 0));
+        qb.add_where_clause(wc, true, true);
+        let plan = qb.build().explain();
+        assert_eq!(plan.where_clauses, vec![wc_plan(IndexType::Primary, false)]);
+        assert!(!plan.is_full_scan());
+    }
+
+    #[test]
+    fn test_iter() {
+        let (isar, ids) = get_col(vec![
+            (1, "a".to_string()),
+            (2, "b".to_string()),
+            (2, "c".to_string()),
+            (3, "d".to_string()),
+            (4, "e".to_string()),
+            (5, "f".to_string()),
+        ]);
+        let col = isar.get_collection(0).unwrap();
+        let txn = isar.begin_txn(false).unwrap();
+
+        let q = isar.create_query_builder(col).build();
+        let results = q
+            .iter(&txn)
+            .unwrap()
+            .take(2)
+            .map(Result::unwrap)
+            .collect_vec();
+        assert_eq!(keys(results), vec![ids[0], ids[1]]);
+
+        let property = col.get_properties().first().unwrap();
+        let mut qb = isar.create_query_builder(col);
+        qb.set_filter(IntBetween::filter(property, 2, 4).unwrap());
+        qb.set_distinct(std::slice::from_ref(property), true);
+        qb.add_offset_limit(Some(1), Some(2)).unwrap();
+        let q = qb.build();
+        let results = q.iter(&txn).unwrap().map(Result::unwrap).collect_vec();
+        assert_eq!(keys(results), vec![ids[3], ids[4]]);
+    }
+
+    #[test]
+    fn test_add_filter() {
+        let (isar, ids) = get_col(vec![
+            (1, "a".to_string()),
+            (2, "b".to_string()),
+            (3, "c".to_string()),
+            (4, "d".to_string()),
+            (5, "e".to_string()),
+        ]);
+        let col = isar.get_collection(0).unwrap();
+        let txn = isar.begin_txn(false).unwrap();
+        let property = col.get_properties().first().unwrap();
+
+        let mut qb = isar.create_query_builder(col);
+        qb.add_filter(IntBetween::filter(property, 2, 4).unwrap());
+        let results = qb.build().find_all_vec(&txn).unwrap();
+        assert_eq!(keys(results), vec![ids[1], ids[2], ids[3]]);
+
+        let mut qb = isar.create_query_builder(col);
+        qb.add_filter(IntBetween::filter(property, 2, 4).unwrap());
+        qb.add_filter(IntBetween::filter(property, 3, 5).unwrap());
+        let results = qb.build().find_all_vec(&txn).unwrap();
+        assert_eq!(keys(results), vec![ids[2], ids[3]]);
+
+        let mut qb = isar.create_query_builder(col);
+        qb.add_filter(IntBetween::filter(property, 2, 4).unwrap());
+        qb.set_filter(IntBetween::filter(property, 5, 5).unwrap());
+        let results = qb.build().find_all_vec(&txn).unwrap();
+        assert_eq!(keys(results), vec![ids[4]]);
+    }
+
+    #[test]
+    fn test_distinct_offset_limit() {
+        let (isar, ids) = get_col(vec![
+            (1, "a".to_string()),
+            (1, "b".to_string()),
+            (2, "c".to_string()),
+            (2, "d".to_string()),
+            (3, "e".to_string()),
+            (4, "f".to_string()),
+        ]);
+        let col = isar.get_collection(0).unwrap();
+        let txn = isar.begin_txn(false).unwrap();
+        let property = col.get_properties().first().unwrap();
+
+        let mut qb = isar.create_query_builder(col);
+        qb.set_distinct(std::slice::from_ref(property), true);
+        qb.set_limit(3);
+        let q = qb.build();
+        let expected = vec![ids[0], ids[2], ids[4]];
+        assert_eq!(keys(q.find_all_vec(&txn).unwrap()), expected);
+        let results = q.iter(&txn).unwrap().map(Result::unwrap).collect_vec();
+        assert_eq!(keys(results), expected);
+        assert_eq!(q.count(&txn).unwrap(), 3);
+
+        let mut qb = isar.create_query_builder(col);
+        qb.set_distinct(std::slice::from_ref(property), true);
+        qb.add_offset_limit(Some(1), Some(2)).unwrap();
+        let q = qb.build();
+        let expected = vec![ids[2], ids[4]];
+        assert_eq!(keys(q.find_all_vec(&txn).unwrap()), expected);
+        let results = q.iter(&txn).unwrap().map(Result::unwrap).collect_vec();
+        assert_eq!(keys(results), expected);
+    }
+
+    #[test]
+    fn test_sort() {
+        let (isar, ids) = get_col(vec![
+            (2, "b".to_string()),
+            (1, "c".to_string()),
+            (2, "a".to_string()),
+            (1, "a".to_string()),
+        ]);
+        let col = isar.get_collection(0).unwrap();
+        let txn = isar.begin_txn(false).unwrap();
+        let properties = col.get_properties();
+
+        let mut qb = isar.create_query_builder(col);
+        qb.add_sort(properties[0].clone(), Sort::Ascending).unwrap();
+        qb.add_sort(properties[1].clone(), Sort::Descending)
+            .unwrap();
+        let q = qb.build();
+        let expected = vec![ids[1], ids[3], ids[0], ids[2]];
+        assert_eq!(keys(q.find_all_vec(&txn).unwrap()), expected);
+        let results = q.iter(&txn).unwrap().map(Result::unwrap).collect_vec();
+        assert_eq!(keys(results), expected);
+
+        isar!(isar, col => col!(field1 => IntList));
+        let mut qb = isar.create_query_builder(col);
+        let property = col.get_properties()[0].clone();
+        assert!(qb.add_sort(property, Sort::Ascending).is_err());
+    }
+
+    #[test]
+    fn test_offset_limit_window() {
+        let (isar, ids) = get_col((0..6).map(|i| (i, i.to_string())).collect());
+        let col = isar.get_collection(0).unwrap();
+        let txn = isar.begin_txn(false).unwrap();
+        let property = col.get_properties().first().unwrap();
+
+        let find = |offset: Option<usize>, limit: Option<usize>| {
+            let evaluated = Cell::new(0);
+            let mut qb = isar.create_query_builder(col);
+            let filter = IntBetween::filter(property, 0, 5).unwrap();
+            qb.set_filter(Counting::filter(filter, &evaluated));
+            qb.add_offset_limit(offset, limit).unwrap();
+            let results = keys(qb.build().find_all_vec(&txn).unwrap());
+            (results, evaluated.get())
+        };
+
+        assert_eq!(find(Some(1), Some(2)), (ids[1..3].to_vec(), 3));
+        assert_eq!(find(Some(2), Some(3)), (ids[2..5].to_vec(), 5));
+        assert_eq!(find(None, Some(1)), (ids[..1].to_vec(), 1));
+        assert_eq!(find(Some(0), Some(0)), (vec![], 0));
+        assert_eq!(find(Some(4), None), (ids[4..].to_vec(), 6));
+    }
+
+    #[test]
+    fn test_find_first() {
+        let (isar, ids) = get_col(vec![
+            (3, "a".to_string()),
+            (1, "b".to_string()),
+            (2, "c".to_string()),
+            (2, "d".to_string()),
+            (5, "e".to_string()),
+        ]);
+        let col = isar.get_collection(0).unwrap();
+        let txn = isar.begin_txn(false).unwrap();
+        let property = col.get_properties().first().unwrap();
+        let evaluated = Cell::new(0);
+
+        let mut qb = isar.create_query_builder(col);
+        qb.add_where_clause(col.create_secondary_where_clause(0).unwrap(), true, true);
+        let filter = IntBetween::filter(property, 2, 3).unwrap();
+        qb.set_filter(Counting::filter(filter, &evaluated));
+        let q = qb.build();
+        let (oid, _) = q.find_first(&txn).unwrap().unwrap();
+        assert_eq!(*oid, ids[2]);
+        assert_eq!(evaluated.get(), 2);
+
+        let mut qb = isar.create_query_builder(col);
+        qb.set_filter(IntBetween::filter(property, 2, 3).unwrap());
+        qb.add_sort(property.clone(), Sort::Descending).unwrap();
+        let (oid, _) = qb.build().find_first(&txn).unwrap().unwrap();
+        assert_eq!(*oid, ids[0]);
+
+        let mut qb = isar.create_query_builder(col);
+        qb.set_filter(IntBetween::filter(property, 6, 10).unwrap());
+        assert!(qb.build().find_first(&txn).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_find_page() {
+        let (isar, ids) = get_col(vec![
+            (3, "a".to_string()),
+            (1, "b".to_string()),
+            (2, "c".to_string()),
+            (2, "d".to_string()),
+            (5, "e".to_string()),
+        ]);
+        let col = isar.get_collection(0).unwrap();
+        let txn = isar.begin_txn(false).unwrap();
+        let property = col.get_properties().first().unwrap();
+
+        let mut qb = isar.create_query_builder(col);
+        qb.set_filter(IntBetween::filter(property, 1, 3).unwrap());
+        qb.add_sort(property.clone(), Sort::Ascending).unwrap();
+        let q = qb.build();
+        let total = q.count(&txn).unwrap();
+
+        let (page, page_total) = q.find_page(&txn, 1, 2).unwrap();
+        assert_eq!(keys(page), vec![ids[2], ids[3]]);
+        assert_eq!(page_total, total);
+
+        let (page, page_total) = q.find_page(&txn, 3, 2).unwrap();
+        assert_eq!(keys(page), vec![ids[0]]);
+        assert_eq!(page_total, total);
+
+        let (page, page_total) = q.find_page(&txn, 10, 2).unwrap();
+        assert!(page.is_empty());
+        assert_eq!(page_total, total);
+
+        // offset and limit of the query are ignored
+        let mut qb = isar.create_query_builder(col);
+        qb.set_distinct(std::slice::from_ref(property), true);
+        qb.add_offset_limit(Some(1), Some(1)).unwrap();
+        let q = qb.build();
+        let (page, page_total) = q.find_page(&txn, 0, 2).unwrap();
+        assert_eq!(keys(page), vec![ids[0], ids[1]]);
+        assert_eq!(page_total, 4);
+    }
+
+    #[test]
+    fn test_find_all_projected() {
+        let (isar, ids) = get_col(vec![(1, "a".to_string()), (2, "b".to_string())]);
+        let col = isar.get_collection(0).unwrap();
+        let txn = isar.begin_txn(false).unwrap();
+        let properties = col.get_properties();
+
+        let mut qb = isar.create_query_builder(col);
+        qb.add_projection(&properties[1..]).unwrap();
+        let q = qb.build();
+
+        let mut results = vec![];
+        q.find_all_projected(&txn, |oid, object| {
+            assert!(col.debug_get_object_info().verify_object(object).is_ok());
+            results.push((
+                *oid,
+                properties[0].is_null(object),
+                properties[1].get_string(object).unwrap().to_string(),
+            ));
+            true
+        })
+        .unwrap();
+        assert_eq!(
+            results,
+            vec![
+                (ids[0], true, "a".to_string()),
+                (ids[1], true, "b".to_string())
+            ]
+        );
+
+        let q = isar.create_query_builder(col).build();
+        assert!(q.find_all_projected(&txn, |_, _| true).is_err());
+    }
+
+    #[test]
+    fn test_add_projection_invalid() {
+        let (isar, _) = get_col(vec![]);
+        let col = isar.get_collection(0).unwrap();
+        isar!(other_isar, other_col => col!(field1 => Long));
+
+        let mut qb = isar.create_query_builder(col);
+        assert!(qb.add_projection(other_col.get_properties()).is_err());
+    }
+
+    #[test]
+    fn test_distinct_sorted() {
+        let (isar, ids) = get_col(vec![
+            (1, "b".to_string()),
+            (2, "a".to_string()),
+            (1, "a".to_string()),
+            (2, "c".to_string()),
+        ]);
+        let col = isar.get_collection(0).unwrap();
+        let txn = isar.begin_txn(false).unwrap();
+        let properties = col.get_properties();
+
+        let distinct_sorted = |sort: Sort, limit: Option<usize>| {
+            let mut qb = isar.create_query_builder(col);
+            qb.add_sort(properties[1].clone(), sort).unwrap();
+            qb.set_distinct(&properties[0..1], true);
+            if let Some(limit) = limit {
+                qb.set_limit(limit);
+            }
+            keys(qb.build().find_all_vec(&txn).unwrap())
+        };
+        assert_eq!(distinct_sorted(Sort::Ascending, None), vec![ids[1], ids[2]]);
+        assert_eq!(
+            distinct_sorted(Sort::Descending, None),
+            vec![ids[3], ids[0]]
+        );
+        assert_eq!(distinct_sorted(Sort::Ascending, Some(1)), vec![ids[1]]);
+    }
+
+    #[test]
+    fn test_distinct_case_insensitive() {
+        let (isar, ids) = get_col(vec![
+            (1, "abc".to_string()),
+            (2, "ABC".to_string()),
+            (3, "Abc".to_string()),
+            (4, "b".to_string()),
+        ]);
+        let col = isar.get_collection(0).unwrap();
+        let txn = isar.begin_txn(false).unwrap();
+        let properties = col.get_properties();
+
+        let distinct = |case_sensitive: bool| {
+            let mut qb = isar.create_query_builder(col);
+            qb.set_distinct(&properties[1..2], case_sensitive);
+            keys(qb.build().find_all_vec(&txn).unwrap())
+        };
+        assert_eq!(distinct(true), ids);
+        assert_eq!(distinct(false), vec![ids[0], ids[3]]);
+    }
+
+    #[test]
+    fn test_distinct_string_list_case_insensitive() {
+        isar!(isar, col => col!(field1 => StringList));
+        let txn = isar.begin_txn(true).unwrap();
+        let mut ids = vec![];
+        for list in &[
+            vec![Some("a"), None],
+            vec![Some("A"), None],
+            vec![Some("a")],
+            vec![Some("b"), None],
+        ] {
+            let mut builder = col.get_object_builder();
+            builder.write_string_list(Some(list));
+            ids.push(col.put(&txn, None, builder.finish().as_bytes()).unwrap());
+        }
+
+        let distinct = |case_sensitive: bool| {
+            let mut qb = isar.create_query_builder(col);
+            qb.set_distinct(col.get_properties(), case_sensitive);
+            keys(qb.build().find_all_vec(&txn).unwrap())
+        };
+        assert_eq!(distinct(true), ids);
+        assert_eq!(distinct(false), vec![ids[0], ids[2], ids[3]]);
+    }
+
+    #[test]
+    fn test_distinct_corrupted() {
+        isar!(isar, col => col!(field1 => Long, field2 => LongList));
+        let txn = isar.begin_txn(true).unwrap();
+        let properties = col.get_properties();
+        let mut builder = col.get_object_builder();
+        builder.write_long(1);
+        builder.write_long_list(Some(&[1, 2]));
+        let mut object = builder.finish().as_bytes().to_vec();
+        object[properties[1].offset + 4] = 100;
+        let oid = col.get_object_id(1, 2, 3);
+        col.debug_get_db()
+            .put(txn.get_txn().unwrap(), oid.as_bytes(), &object)
+            .unwrap();
+
+        let mut qb = isar.create_query_builder(col);
+        qb.set_distinct(&properties[1..2], true);
+        let q = qb.build();
+        let result = q.find_all_vec(&txn);
+        assert!(matches!(result, Err(IsarError::DbCorrupted { .. })));
+        let result = q.iter(&txn).unwrap().next().unwrap();
+        assert!(matches!(result, Err(IsarError::DbCorrupted { .. })));
+
+        let mut qb = isar.create_query_builder(col);
+        qb.add_sort(properties[0].clone(), Sort::Ascending).unwrap();
+        qb.set_distinct(&properties[1..2], true);
+        let result = qb.build().find_all_vec(&txn);
+        assert!(matches!(result, Err(IsarError::DbCorrupted { .. })));
+    }
+
     #[test]
     fn test_single_primary_where_clause() {}
 
@@ -301,6 +1074,39 @@ mod tests {
         assert_eq!(keys(results), vec![ids[1], ids[3]]);
     }
 
+    #[test]
+    fn test_read_own_writes() {
+        let (isar, ids) = get_col(vec![(1, "a".to_string()), (2, "b".to_string())]);
+        let col = isar.get_collection(0).unwrap();
+        let mut wc = col.create_secondary_where_clause(1).unwrap();
+        wc.add_string_value(Some("a"), Some("c"));
+        let mut qb = isar.create_query_builder(col);
+        qb.add_where_clause(wc, true, true);
+        let secondary_query = qb.build();
+        let primary_query = isar.create_query_builder(col).build();
+
+        let mut txn = isar.begin_txn(true).unwrap();
+        let mut o = col.get_object_builder();
+        o.write_int(3);
+        o.write_string(Some("c"));
+        let new_id = col.put(&txn, None, o.finish().as_bytes()).unwrap();
+
+        let results = primary_query.find_all_vec(&txn).unwrap();
+        assert_eq!(keys(results), vec![ids[0], ids[1], new_id]);
+        let results = secondary_query.find_all_vec(&txn).unwrap();
+        assert_eq!(keys(results), vec![ids[0], ids[1], new_id]);
+        assert_eq!(secondary_query.count(&txn).unwrap(), 3);
+
+        col.delete(&txn, ids[0]).unwrap();
+        let results = secondary_query.find_all_vec(&txn).unwrap();
+        assert_eq!(keys(results), vec![ids[1], new_id]);
+        txn.abort();
+
+        let txn = isar.begin_txn(false).unwrap();
+        let results = primary_query.find_all_vec(&txn).unwrap();
+        assert_eq!(keys(results), vec![ids[0], ids[1]]);
+    }
+
     #[test]
     fn test_multiple_where_clauses() {
         let (isar, ids) = get_col(vec![
@@ -333,4 +1139,351 @@ mod tests {
         let set: HashSet<ObjectId> = keys(results).into_iter().collect();
         assert_eq!(set, set!(ids[0], ids[2], ids[4], ids[5]));
     }
+
+    #[test]
+    fn test_null_string_where_clause() {
+        isar!(isar, col => col!(field1 => String; ind!(field1)));
+        let mut txn = isar.begin_txn(true).unwrap();
+        let mut ids = vec![];
+        for value in &[None, Some(""), Some("a"), Some("b")] {
+            let mut o = col.get_object_builder();
+            o.write_string(*value);
+            ids.push(col.put(&txn, None, o.finish().as_bytes()).unwrap());
+        }
+        txn.commit().unwrap();
+
+        let txn = isar.begin_txn(false).unwrap();
+        let find = |lower: Option<&str>, upper: Option<&str>, include_lower, include_upper| {
+            let mut wc = col.create_secondary_where_clause(0).unwrap();
+            wc.add_string_value(lower, upper);
+            let mut qb = isar.create_query_builder(col);
+            qb.add_where_clause(wc, include_lower, include_upper);
+            keys(qb.build().find_all_vec(&txn).unwrap())
+        };
+
+        assert_eq!(
+            find(None, Some("a"), true, true),
+            vec![ids[0], ids[1], ids[2]]
+        );
+        assert_eq!(find(None, Some("a"), false, true), vec![ids[1], ids[2]]);
+        assert_eq!(find(None, Some("a"), true, false), vec![ids[0], ids[1]]);
+        assert_eq!(find(None, None, true, true), vec![ids[0]]);
+        assert_eq!(find(None, None, false, true), vec![]);
+        assert_eq!(find(None, None, true, false), vec![]);
+        assert_eq!(
+            find(Some(""), Some("b"), true, true),
+            vec![ids[1], ids[2], ids[3]]
+        );
+        assert_eq!(find(Some(""), Some("b"), false, true), vec![ids[2], ids[3]]);
+    }
+
+    #[test]
+    fn test_byte_where_clause_bounds() {
+        isar!(isar, col => col!(field1 => Byte; ind!(field1)));
+        let mut txn = isar.begin_txn(true).unwrap();
+        let mut ids = vec![];
+        for value in &[0, 1, 254, 255] {
+            let mut o = col.get_object_builder();
+            o.write_byte(*value);
+            ids.push(col.put(&txn, None, o.finish().as_bytes()).unwrap());
+        }
+        txn.commit().unwrap();
+
+        let txn = isar.begin_txn(false).unwrap();
+        let find = |lower: u8, upper: u8, include_lower, include_upper| {
+            let mut wc = col.create_secondary_where_clause(0).unwrap();
+            wc.add_byte(lower, upper);
+            let mut qb = isar.create_query_builder(col);
+            qb.add_where_clause(wc, include_lower, include_upper);
+            keys(qb.build().find_all_vec(&txn).unwrap())
+        };
+
+        assert_eq!(find(0, 255, true, true), ids);
+        assert_eq!(find(0, 255, false, true), ids[1..]);
+        assert_eq!(find(0, 255, true, false), ids[..3]);
+        assert_eq!(find(0, 255, false, false), ids[1..3]);
+        assert_eq!(find(0, 0, true, true), vec![ids[0]]);
+        assert_eq!(find(0, 0, false, true), vec![]);
+        assert_eq!(find(0, 0, true, false), vec![]);
+        assert_eq!(find(255, 255, true, true), vec![ids[3]]);
+        assert_eq!(find(255, 255, false, true), vec![]);
+        assert_eq!(find(255, 255, true, false), vec![]);
+        assert_eq!(find(254, 255, false, true), vec![ids[3]]);
+        assert_eq!(find(0, 1, true, false), vec![ids[0]]);
+        assert_eq!(find(255, 0, true, true), vec![]);
+
+        let mut wc = col.create_secondary_where_clause(0).unwrap();
+        wc.add_byte(255, 0);
+        assert!(wc.is_empty());
+        let mut wc = col.create_secondary_where_clause(0).unwrap();
+        wc.add_byte(1, 1);
+        assert!(!wc.is_empty());
+    }
+
+    #[test]
+    fn test_where_clauses_overlapping() {
+        let (isar, ids) = get_col(vec![
+            (1, "a".to_string()),
+            (2, "b".to_string()),
+            (3, "c".to_string()),
+            (4, "d".to_string()),
+        ]);
+        let col = isar.get_collection(0).unwrap();
+        let txn = isar.begin_txn(false).unwrap();
+
+        let mut wc1 = col.create_secondary_where_clause(1).unwrap();
+        wc1.add_string_value(Some("a"), Some("a"));
+        let mut wc2 = col.create_secondary_where_clause(1).unwrap();
+        wc2.add_string_value(Some("c"), Some("d"));
+        let mut qb = isar.create_query_builder(col);
+        qb.add_where_clause(wc1.clone(), true, true);
+        qb.add_where_clause(wc2, true, true);
+        let q = qb.build();
+        assert!(!q.where_clauses_overlapping);
+        assert_eq!(
+            keys(q.find_all_vec(&txn).unwrap()),
+            vec![ids[0], ids[2], ids[3]]
+        );
+
+        let mut wc3 = col.create_secondary_where_clause(0).unwrap();
+        wc3.add_int(1, 2);
+        let mut qb = isar.create_query_builder(col);
+        qb.add_where_clause(wc1, true, true);
+        qb.add_where_clause(wc3, true, true);
+        let q = qb.build();
+        assert!(q.where_clauses_overlapping);
+        let results = keys(q.find_all_vec(&txn).unwrap());
+        assert_eq!(results.len(), 2);
+        let set: HashSet<ObjectId> = results.into_iter().collect();
+        assert_eq!(set, set!(ids[0], ids[1]));
+        assert_eq!(q.count(&txn).unwrap(), 2);
+        assert_eq!(q.count_fast(&txn).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_multi_entry_where_clause_overlapping() {
+        isar!(isar, col => col!(field1 => IntList; ind!(field1; false, false, true)));
+        let mut txn = isar.begin_txn(true).unwrap();
+        let mut o = col.get_object_builder();
+        o.write_int_list(Some(&[1, 5]));
+        let oid = col.put(&txn, None, o.finish().as_bytes()).unwrap();
+        txn.commit().unwrap();
+
+        let txn = isar.begin_txn(false).unwrap();
+        let mut wc = col.create_secondary_where_clause(0).unwrap();
+        wc.add_int(1, 5);
+        let mut qb = isar.create_query_builder(col);
+        qb.add_where_clause(wc, true, true);
+        let q = qb.build();
+        assert!(q.where_clauses_overlapping);
+        assert_eq!(keys(q.find_all_vec(&txn).unwrap()), vec![oid]);
+        let results = q.iter(&txn).unwrap().map(Result::unwrap).collect_vec();
+        assert_eq!(keys(results), vec![oid]);
+        assert_eq!(q.count_fast(&txn).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_excluded_where_clauses() {
+        let (isar, ids) = get_col(vec![(1, "aa".to_string()), (2, "bb".to_string())]);
+        let col = isar.get_collection(0).unwrap();
+        let txn = isar.begin_txn(false).unwrap();
+
+        let mut secondary_wc = col.create_secondary_where_clause(0).unwrap();
+        secondary_wc.add_int(1, 1);
+        let mut secondary_dup_wc = col.create_secondary_where_clause(1).unwrap();
+        secondary_dup_wc.add_string_value(Some("bb"), Some("bb"));
+
+        let mut qb = isar.create_query_builder(col);
+        qb.add_where_clause(secondary_wc.clone(), false, true);
+        qb.add_where_clause(secondary_dup_wc, true, true);
+        let results = qb.build().find_all_vec(&txn).unwrap();
+        assert_eq!(keys(results), vec![ids[1]]);
+
+        let mut qb = isar.create_query_builder(col);
+        qb.add_where_clause(secondary_wc, false, false);
+        let q = qb.build();
+        assert_eq!(q.count(&txn).unwrap(), 0);
+        assert_eq!(q.count_fast(&txn).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_merge_where_clauses() {
+        let (isar, _) = get_col(vec![
+            (0, "aa".to_string()),
+            (1, "ab".to_string()),
+            (2, "ab".to_string()),
+            (2, "bb".to_string()),
+            (3, "bb".to_string()),
+            (4, "bc".to_string()),
+        ]);
+        let col = isar.get_collection(0).unwrap();
+        let txn = isar.begin_txn(false).unwrap();
+
+        let int_wc = |lower: i32, upper: i32| {
+            let mut wc = col.create_secondary_where_clause(0).unwrap();
+            wc.add_int(lower, upper);
+            wc
+        };
+        let build = |wcs: &[WhereClause]| {
+            let mut qb = isar.create_query_builder(col);
+            for wc in wcs {
+                qb.add_where_clause(wc.clone(), true, true);
+            }
+            qb.build()
+        };
+        let assert_merged = |wcs: Vec<WhereClause>, clauses: usize, overlapping: bool| {
+            let mut unmerged = HashSet::new();
+            for wc in &wcs {
+                let q = build(std::slice::from_ref(wc));
+                unmerged.extend(keys(q.find_all_vec(&txn).unwrap()));
+            }
+
+            let q = build(&wcs);
+            assert_eq!(q.where_clauses.len(), clauses);
+            assert_eq!(q.where_clauses_overlapping, overlapping);
+            let results = keys(q.find_all_vec(&txn).unwrap());
+            assert_eq!(results.len(), unmerged.len());
+            assert_eq!(results.into_iter().collect::<HashSet<_>>(), unmerged);
+        };
+
+        assert_merged(vec![int_wc(1, 3), int_wc(2, 2)], 1, false);
+        assert_merged(vec![int_wc(2, 3), int_wc(0, 2), int_wc(1, 1)], 1, false);
+        assert_merged(vec![int_wc(0, 1), int_wc(2, 3)], 1, false);
+        assert_merged(vec![int_wc(3, 4), int_wc(0, 1)], 2, false);
+        assert_merged(vec![int_wc(0, 0), int_wc(4, 4), int_wc(2, 2)], 3, false);
+
+        let mut dup_wc = col.create_secondary_where_clause(1).unwrap();
+        dup_wc.add_string_value(Some("bb"), Some("bb"));
+        assert_merged(vec![int_wc(0, 2), dup_wc, int_wc(1, 3)], 2, true);
+    }
+
+    #[test]
+    fn test_descending_where_clauses() {
+        let (isar, ids) = get_col(vec![
+            (0, "aa".to_string()),
+            (1, "ab".to_string()),
+            (2, "ab".to_string()),
+            (3, "bb".to_string()),
+            (4, "bb".to_string()),
+        ]);
+        let col = isar.get_collection(0).unwrap();
+        let txn = isar.begin_txn(false).unwrap();
+
+        let mut qb = isar.create_query_builder(col);
+        for (lower, upper) in &[(0, 0), (3, 4), (1, 1)] {
+            let mut wc = col.create_secondary_where_clause(0).unwrap();
+            wc.add_int(*lower, *upper);
+            wc.set_sort(Sort::Descending);
+            qb.add_where_clause(wc, true, true);
+        }
+        let q = qb.build();
+
+        let results = q.find_all_vec(&txn).unwrap();
+        assert_eq!(keys(results), vec![ids[4], ids[3], ids[1], ids[0]]);
+    }
+
+    #[test]
+    fn test_count_fast() {
+        let (isar, _) = get_col(vec![
+            (1, "aa".to_string()),
+            (1, "ab".to_string()),
+            (0, "ab".to_string()),
+            (1, "bb".to_string()),
+            (0, "bb".to_string()),
+            (2, "bc".to_string()),
+        ]);
+        let col = isar.get_collection(0).unwrap();
+        let txn = isar.begin_txn(false).unwrap();
+
+        let assert_counts = |wcs: Vec<WhereClause>, expected: u32| {
+            let mut qb = isar.create_query_builder(col);
+            for wc in wcs {
+                qb.add_where_clause(wc, true, true);
+            }
+            let q = qb.build();
+            assert_eq!(q.count(&txn).unwrap(), expected);
+            assert_eq!(q.count_fast(&txn).unwrap(), expected);
+        };
+
+        assert_counts(vec![], 6);
+
+        let mut wc = col.create_secondary_where_clause(0).unwrap();
+        wc.add_int(1, 1);
+        assert_counts(vec![wc.clone()], 3);
+
+        let mut dup_wc = col.create_secondary_where_clause(1).unwrap();
+        dup_wc.add_string_value(Some("ab"), Some("bb"));
+        assert_counts(vec![dup_wc.clone()], 4);
+
+        assert_counts(vec![wc, dup_wc], 5);
+    }
+
+    #[test]
+    fn test_aggregate() {
+        isar!(isar, col => col!(field1 => Int; ind!(field1)));
+        let txn = isar.begin_txn(true).unwrap();
+        for value in &[4, -2, 10, Property::NULL_INT, 3] {
+            let mut builder = col.get_object_builder();
+            builder.write_int(*value);
+            col.put(&txn, None, builder.finish().as_bytes()).unwrap();
+        }
+        let property = col.get_properties().first().unwrap();
+
+        let aggregate = |wc: Option<WhereClause>, op: AggregationOp| {
+            let mut qb = isar.create_query_builder(col);
+            if let Some(wc) = wc {
+                qb.add_where_clause(wc, true, true);
+            }
+            qb.build().aggregate(&txn, property.clone(), op).unwrap()
+        };
+
+        assert_eq!(aggregate(None, AggregationOp::Min), Some(-2.0));
+        assert_eq!(aggregate(None, AggregationOp::Max), Some(10.0));
+        assert_eq!(aggregate(None, AggregationOp::Sum), Some(15.0));
+        assert_eq!(aggregate(None, AggregationOp::Average), Some(3.75));
+        assert_eq!(aggregate(None, AggregationOp::Count), Some(4.0));
+
+        let mut wc = col.create_secondary_where_clause(0).unwrap();
+        wc.add_int(100, 200);
+        assert_eq!(aggregate(Some(wc.clone()), AggregationOp::Min), None);
+        assert_eq!(aggregate(Some(wc.clone()), AggregationOp::Max), None);
+        assert_eq!(aggregate(Some(wc.clone()), AggregationOp::Sum), Some(0.0));
+        assert_eq!(aggregate(Some(wc.clone()), AggregationOp::Average), None);
+        assert_eq!(aggregate(Some(wc), AggregationOp::Count), Some(0.0));
+    }
+
+    #[test]
+    fn test_aggregate_double() {
+        isar!(isar, col => col!(field1 => Double));
+        let txn = isar.begin_txn(true).unwrap();
+        for value in &[1.5, Property::NULL_DOUBLE, -4.25, 0.5] {
+            let mut builder = col.get_object_builder();
+            builder.write_double(*value);
+            col.put(&txn, None, builder.finish().as_bytes()).unwrap();
+        }
+        let property = col.get_properties().first().unwrap();
+        let q = isar.create_query_builder(col).build();
+
+        let aggregate = |op: u8| {
+            let op = AggregationOp::from_ordinal(op).unwrap();
+            q.aggregate(&txn, property.clone(), op).unwrap()
+        };
+        assert_eq!(aggregate(0), Some(-4.25));
+        assert_eq!(aggregate(1), Some(1.5));
+        assert_eq!(aggregate(2), Some(-2.25));
+        assert_eq!(aggregate(3), Some(-0.75));
+        assert_eq!(aggregate(4), Some(3.0));
+        assert_eq!(AggregationOp::from_ordinal(5), None);
+    }
+
+    #[test]
+    fn test_aggregate_wrong_type() {
+        isar!(isar, col => col!(field1 => String));
+        let txn = isar.begin_txn(false).unwrap();
+        let property = col.get_properties().first().unwrap();
+        let q = isar.create_query_builder(col).build();
+        assert!(q
+            .aggregate(&txn, property.clone(), AggregationOp::Sum)
+            .is_err());
+    }
 }
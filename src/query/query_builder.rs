@@ -2,9 +2,10 @@ use crate::collection::IsarCollection;
 use crate::error::{illegal_arg, Result};
 use crate::index::IndexType;
 use crate::lmdb::db::Db;
+use crate::object::data_type::DataType;
 use crate::object::property::Property;
 use crate::option;
-use crate::query::filter::Filter;
+use crate::query::filter::{And, Filter};
 use crate::query::query::{Query, Sort};
 use crate::query::where_clause::WhereClause;
 use itertools::Itertools;
@@ -15,12 +16,11 @@ pub struct QueryBuilder<'col> {
     primary_db: Db,
     secondary_db: Db,
     secondary_dup_db: Db,
-    has_secondary_where: bool,
-    has_secondary_dup_where: bool,
-    filter: Option<Filter<'col>>,
+    filters: Vec<Filter<'col>>,
     sort: Vec<(Property, Sort)>,
-    distinct: Option<Vec<Property>>,
+    distinct: Option<(Vec<Property>, bool)>,
     offset_limit: Option<(usize, usize)>,
+    projection: Vec<Property>,
 }
 
 impl<'col> QueryBuilder<'col> {
@@ -36,12 +36,11 @@ impl<'col> QueryBuilder<'col> {
             primary_db,
             secondary_db,
             secondary_dup_db,
-            has_secondary_where: false,
-            has_secondary_dup_where: false,
-            filter: None,
+            filters: vec![],
             sort: vec![],
             distinct: None,
             offset_limit: None,
+            projection: vec![],
         }
     }
 
@@ -54,22 +53,29 @@ impl<'col> QueryBuilder<'col> {
         if !wc.try_exclude(include_lower, include_upper) {
             wc = WhereClause::empty();
         }
-        if wc.index_type == IndexType::Secondary {
-            self.has_secondary_where = true;
-        } else if wc.index_type == IndexType::SecondaryDup {
-            self.has_secondary_dup_where = true;
-        }
         self.where_clauses.push(wc);
     }
 
+    /// Replaces all previously set or added filters.
     pub fn set_filter(&mut self, filter: Filter<'col>) {
-        self.filter = Some(filter);
+        self.filters = vec![filter];
     }
 
-    pub fn add_sort(&mut self, property: Property, sort: Sort) {
-        self.sort.push((property, sort))
+    /// Adds a filter that has to match in addition to all previously set or added filters.
+    pub fn add_filter(&mut self, filter: Filter<'col>) {
+        self.filters.push(filter);
     }
 
+    pub fn add_sort(&mut self, property: Property, sort: Sort) -> Result<()> {
+        if property.data_type.is_dynamic() && property.data_type != DataType::String {
+            return illegal_arg("Lists cannot be sorted.");
+        }
+        self.sort.push((property, sort));
+        Ok(())
+    }
+
+    /// Offset and limit are applied last: where clauses, filter and distinct are evaluated
+    /// first, so `limit` counts distinct objects.
     pub fn add_offset_limit(&mut self, offset: Option<usize>, limit: Option<usize>) -> Result<()> {
         let offset = offset.unwrap_or(0);
         let limit = limit.unwrap_or(usize::MAX);
@@ -82,47 +88,71 @@ impl<'col> QueryBuilder<'col> {
         }
     }
 
-    pub fn set_distinct(&mut self, properties: &[Property]) {
-        self.distinct = Some(properties.iter().cloned().collect_vec());
+    pub fn set_limit(&mut self, limit: usize) {
+        self.offset_limit = Some((0, limit));
     }
 
-    /*pub fn merge_where_clauses(mut where_clauses: Vec<WhereClause>) -> Vec<WhereClause> {
-        where_clauses.sort_unstable_by(|a, b| a.lower_key.cmp(&b.lower_key));
+    /// Only the first object of each distinct combination of values is returned. For sorted
+    /// queries distinct is applied after sorting so the first object in sort order is kept.
+    /// Strings are compared ignoring their case if `case_sensitive` is `false`.
+    pub fn set_distinct(&mut self, properties: &[Property], case_sensitive: bool) {
+        self.distinct = Some((properties.iter().cloned().collect_vec(), case_sensitive));
+    }
 
-        let mut merged = vec![];
-        let mut i = 0;
-        while i < where_clauses.len() {
-            let a = where_clauses.get(i).unwrap();
-            let mut new_upper_key = None;
-            loop {
-                if let Some(b) = where_clauses.get(i + 1) {
-                    if b.lower_key <= a.upper_key {
-                        new_upper_key = Some(max(&a.upper_key, &b.upper_key));
-                        i += 1;
-                        continue;
-                    }
-                }
-                break;
+    /// Adds properties to the projection used by `Query::find_all_projected`.
+    pub fn add_projection(&mut self, properties: &[Property]) -> Result<()> {
+        let collection_properties = self.collection.get_properties();
+        for property in properties {
+            if !collection_properties.contains(property) {
+                return illegal_arg("Property does not belong to this collection.");
             }
-            if let Some(new_upper_key) = new_upper_key {
-                merged.push(WhereClause {
-                    lower_key: a.lower_key.clone(),
-                    upper_key: new_upper_key.clone(),
-                    index_type: a.index_type,
-                });
-                i += 2;
-            } else {
-                merged.push(a.deref().clone());
-                i += 1;
+            if !self.projection.contains(property) {
+                self.projection.push(property.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_where_clauses(mut where_clauses: Vec<WhereClause>) -> Vec<WhereClause> {
+        where_clauses.sort_unstable_by(|a, b| {
+            (a.index_type as u8, a.sort as u8)
+                .cmp(&(b.index_type as u8, b.sort as u8))
+                .then_with(|| a.lower_key.cmp(&b.lower_key))
+        });
+
+        let mut merged: Vec<WhereClause> = vec![];
+        for wc in where_clauses {
+            if let Some(last) = merged.last_mut() {
+                if let Some(merged_wc) = last.merge(&wc) {
+                    *last = merged_wc;
+                    continue;
+                }
             }
+            merged.push(wc);
         }
 
+        // descending where clauses have to be executed starting with the highest range
+        merged.sort_by(|a, b| {
+            (a.index_type as u8, a.sort as u8)
+                .cmp(&(b.index_type as u8, b.sort as u8))
+                .then_with(|| {
+                    let ordering = a.lower_key.cmp(&b.lower_key);
+                    if a.sort == Sort::Descending {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                })
+        });
         merged
-    }*/
+    }
 
-    pub fn build(self) -> Query<'col> {
-        let secondary_db = option!(self.has_secondary_where, self.secondary_db);
-        let secondary_dup_db = option!(self.has_secondary_dup_where, self.secondary_dup_db);
+    pub fn build(mut self) -> Query<'col> {
+        let filter = if self.filters.len() > 1 {
+            Some(And::filter(self.filters))
+        } else {
+            self.filters.pop()
+        };
         let where_clauses = if self.where_clauses.is_empty() {
             vec![self.collection.create_primary_where_clause()]
         } else {
@@ -134,18 +164,38 @@ impl<'col> QueryBuilder<'col> {
             if filtered.is_empty() {
                 vec![WhereClause::empty()]
             } else {
-                filtered
+                Self::merge_where_clauses(filtered)
             }
         };
+        // only open cursors for the indexes of the remaining where clauses
+        let has_index_type =
+            |index_type| where_clauses.iter().any(|wc| wc.index_type == index_type);
+        let secondary_db = option!(has_index_type(IndexType::Secondary), self.secondary_db);
+        let secondary_dup_db = option!(
+            has_index_type(IndexType::SecondaryDup),
+            self.secondary_dup_db
+        );
+        // merged where clauses of a single index cannot overlap unless the index is multi-entry
+        let where_clauses_overlapping = where_clauses.iter().any(|wc| wc.multi_entry)
+            || where_clauses
+                .iter()
+                .tuple_windows()
+                .any(|(a, b)| !a.is_same_index(b));
+        let projection = option!(
+            !self.projection.is_empty(),
+            (self.collection, self.projection)
+        );
         Query::new(
             where_clauses,
+            where_clauses_overlapping,
             self.primary_db,
             secondary_db,
             secondary_dup_db,
-            self.filter,
+            filter,
             self.sort,
             self.distinct,
             self.offset_limit,
+            projection,
         )
     }
 }
@@ -3,31 +3,48 @@ use crate::index::{Index, IndexType};
 use crate::lmdb::cursor::{Cursor, CursorIterator};
 use crate::lmdb::KeyVal;
 use crate::object::object_id::ObjectId;
+use crate::query::query::Sort;
+use std::cmp::Ordering;
 
 #[derive(Clone)]
 pub struct WhereClause {
-    lower_key: Vec<u8>,
+    pub(super) lower_key: Vec<u8>,
     upper_key: Vec<u8>,
     prefix_len: usize,
     pub(super) index_type: IndexType,
+    pub(super) sort: Sort,
+    case_sensitive: bool,
+    /// Objects of multi-entry indexes may be found multiple times.
+    pub(super) multi_entry: bool,
 }
 
 impl WhereClause {
-    pub(crate) fn new(prefix: &[u8], index_type: IndexType) -> Self {
+    pub(crate) fn new(
+        prefix: &[u8],
+        index_type: IndexType,
+        case_sensitive: bool,
+        multi_entry: bool,
+    ) -> Self {
         WhereClause {
             lower_key: prefix.to_vec(),
             upper_key: prefix.to_vec(),
             prefix_len: prefix.len(),
             index_type,
+            sort: Sort::Ascending,
+            case_sensitive,
+            multi_entry,
         }
     }
 
     pub(crate) fn empty() -> Self {
         WhereClause {
-            lower_key: vec![0],
-            upper_key: vec![10],
+            lower_key: vec![1],
+            upper_key: vec![0],
             prefix_len: 0,
             index_type: IndexType::Primary,
+            sort: Sort::Ascending,
+            case_sensitive: true,
+            multi_entry: false,
         }
     }
 
@@ -38,6 +55,55 @@ impl WhereClause {
         WhereClauseIterator::new(&self, cursor)
     }
 
+    /// Moves the cursor to the first entry in iteration order. The entry may be out of range.
+    pub(crate) fn move_to_first<'txn>(
+        &self,
+        cursor: &mut Cursor<'txn>,
+    ) -> Result<Option<KeyVal<'txn>>> {
+        match self.sort {
+            Sort::Ascending => cursor.move_to_gte(&self.lower_key),
+            Sort::Descending => self.move_to_upper_key(cursor),
+        }
+    }
+
+    /// Moves the cursor to the next entry in iteration order. The entry may be out of range.
+    pub(crate) fn move_to_next<'txn>(
+        &self,
+        cursor: &mut Cursor<'txn>,
+    ) -> Result<Option<KeyVal<'txn>>> {
+        match self.sort {
+            Sort::Ascending => cursor.move_to_next(),
+            Sort::Descending => cursor.move_to_prev(),
+        }
+    }
+
+    pub(crate) fn is_in_range(&self, key: &[u8]) -> bool {
+        if self.sort == Sort::Ascending {
+            self.check_below_upper_key(&key)
+        } else {
+            key >= &self.lower_key[..]
+        }
+    }
+
+    /// Moves the cursor to the last entry that is not above the upper key.
+    fn move_to_upper_key<'txn>(&self, cursor: &mut Cursor<'txn>) -> Result<Option<KeyVal<'txn>>> {
+        if let Some(successor) = self.upper_key_successor() {
+            if cursor.move_to_gte(&successor)?.is_some() {
+                return cursor.move_to_prev();
+            }
+        }
+        cursor.move_to_last()
+    }
+
+    pub fn set_sort(&mut self, sort: Sort) {
+        self.sort = sort;
+    }
+
+    /// Returns true if the where clause matches the whole index.
+    pub(super) fn is_unbounded(&self) -> bool {
+        self.lower_key.len() == self.prefix_len && self.upper_key.len() == self.prefix_len
+    }
+
     pub fn is_empty(&self) -> bool {
         !self.check_below_upper_key(&self.lower_key)
     }
@@ -51,39 +117,99 @@ impl WhereClause {
         upper_key >= key
     }
 
+    /// Excludes the lower and upper keys from the range. Bounds without a value are unbounded
+    /// and stay unchanged. Returns `false` if the range is empty afterwards.
     pub(crate) fn try_exclude(&mut self, include_lower: bool, include_upper: bool) -> bool {
-        if !include_lower {
-            let mut increased = false;
-            for i in (self.prefix_len..self.lower_key.len()).rev() {
-                if let Some(added) = self.lower_key[i].checked_add(1) {
-                    self.lower_key[i] = added;
-                    increased = true;
-                    break;
-                }
-            }
-            if !increased {
-                return false;
+        let include_lower = include_lower || self.lower_key.len() == self.prefix_len;
+        let include_upper = include_upper || self.upper_key.len() == self.prefix_len;
+        (include_lower || self.exclude_lower())
+            && (include_upper || self.exclude_upper())
+            && !self.is_empty()
+    }
+
+    /// Increases the lower key to the next key of the same length. Returns `false` if there is
+    /// no such key.
+    fn exclude_lower(&mut self) -> bool {
+        for i in (self.prefix_len..self.lower_key.len()).rev() {
+            if let Some(added) = self.lower_key[i].checked_add(1) {
+                self.lower_key[i] = added;
+                return true;
             }
+            self.lower_key[i] = 0;
         }
-        if !include_upper {
-            let mut decreased = false;
-            for i in (self.prefix_len..self.upper_key.len()).rev() {
-                if let Some(subtracted) = self.upper_key[i].checked_sub(1) {
-                    self.upper_key[i] = subtracted;
-                    decreased = true;
-                    break;
-                }
+        false
+    }
+
+    /// Decreases the upper key to the previous key of the same length. Returns `false` if there
+    /// is no such key.
+    fn exclude_upper(&mut self) -> bool {
+        for i in (self.prefix_len..self.upper_key.len()).rev() {
+            if let Some(subtracted) = self.upper_key[i].checked_sub(1) {
+                self.upper_key[i] = subtracted;
+                return true;
             }
-            if !decreased {
-                return false;
+            self.upper_key[i] = u8::MAX;
+        }
+        false
+    }
+
+    pub(super) fn is_same_index(&self, other: &WhereClause) -> bool {
+        self.index_type == other.index_type
+            && self.sort == other.sort
+            && self.prefix_len == other.prefix_len
+            && self.lower_key[..self.prefix_len] == other.lower_key[..other.prefix_len]
+    }
+
+    /// The smallest key that is above the upper key or `None` if there is no such key.
+    fn upper_key_successor(&self) -> Option<Vec<u8>> {
+        let mut successor = self.upper_key.clone();
+        while let Some(last) = successor.pop() {
+            if last != u8::MAX {
+                successor.push(last + 1);
+                return Some(successor);
             }
         }
-        true
+        None
     }
 
-    /*pub(super) fn merge(&self, other: &WhereClause) -> Option<WhereClause> {
-        unimplemented!()
-    }*/
+    /// Merges two where clauses of the same index if their ranges overlap or are adjacent.
+    pub(super) fn merge(&self, other: &WhereClause) -> Option<WhereClause> {
+        if !self.is_same_index(other) {
+            return None;
+        }
+        let (first, second) = if self.lower_key <= other.lower_key {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        if let Some(successor) = first.upper_key_successor() {
+            if second.lower_key > successor {
+                return None;
+            }
+        }
+
+        // upper keys also match all keys they are a prefix of
+        let len = first.upper_key.len().min(second.upper_key.len());
+        let first_is_upper = match first.upper_key[..len].cmp(&second.upper_key[..len]) {
+            Ordering::Equal => first.upper_key.len() <= second.upper_key.len(),
+            ordering => ordering == Ordering::Greater,
+        };
+        let upper_key = if first_is_upper {
+            first.upper_key.clone()
+        } else {
+            second.upper_key.clone()
+        };
+
+        Some(WhereClause {
+            lower_key: first.lower_key.clone(),
+            upper_key,
+            prefix_len: first.prefix_len,
+            index_type: first.index_type,
+            sort: first.sort,
+            case_sensitive: first.case_sensitive,
+            multi_entry: first.multi_entry,
+        })
+    }
 
     pub fn add_oid(&mut self, oid: ObjectId) {
         let bytes = oid.as_bytes_without_prefix();
@@ -92,54 +218,130 @@ impl WhereClause {
     }
 
     pub fn add_oid_time(&mut self, lower: u32, upper: u32) {
-        self.lower_key.extend_from_slice(&lower.to_be_bytes());
-        self.upper_key.extend_from_slice(&upper.to_be_bytes());
+        self.add_lower_oid_time(lower, true);
+        self.add_upper_oid_time(upper, true);
+    }
+
+    pub fn add_lower_oid_time(&mut self, value: u32, include: bool) -> bool {
+        self.lower_key.extend_from_slice(&value.to_be_bytes());
+        include || self.exclude_lower()
+    }
+
+    pub fn add_upper_oid_time(&mut self, value: u32, include: bool) -> bool {
+        self.upper_key.extend_from_slice(&value.to_be_bytes());
+        include || self.exclude_upper()
     }
 
     pub fn add_byte(&mut self, lower: u8, upper: u8) {
+        self.add_lower_byte(lower, true);
+        self.add_upper_byte(upper, true);
+    }
+
+    pub fn add_lower_byte(&mut self, value: u8, include: bool) -> bool {
         self.lower_key
-            .extend_from_slice(&Index::get_byte_key(lower));
+            .extend_from_slice(&Index::get_byte_key(value));
+        include || self.exclude_lower()
+    }
+
+    pub fn add_upper_byte(&mut self, value: u8, include: bool) -> bool {
         self.upper_key
-            .extend_from_slice(&Index::get_byte_key(upper));
+            .extend_from_slice(&Index::get_byte_key(value));
+        include || self.exclude_upper()
+    }
+
+    pub fn add_bool(&mut self, value: Option<bool>) {
+        let key = Index::get_bool_key(value);
+        self.lower_key.extend_from_slice(&key);
+        self.upper_key.extend_from_slice(&key);
     }
 
     pub fn add_int(&mut self, lower: i32, upper: i32) {
-        self.lower_key.extend_from_slice(&Index::get_int_key(lower));
-        self.upper_key.extend_from_slice(&Index::get_int_key(upper));
+        self.add_lower_int(lower, true);
+        self.add_upper_int(upper, true);
+    }
+
+    pub fn add_lower_int(&mut self, value: i32, include: bool) -> bool {
+        self.lower_key.extend_from_slice(&Index::get_int_key(value));
+        include || self.exclude_lower()
+    }
+
+    pub fn add_upper_int(&mut self, value: i32, include: bool) -> bool {
+        self.upper_key.extend_from_slice(&Index::get_int_key(value));
+        include || self.exclude_upper()
     }
 
     pub fn add_float(&mut self, lower: f32, upper: f32) {
+        self.add_lower_float(lower, true);
+        self.add_upper_float(upper, true);
+    }
+
+    pub fn add_lower_float(&mut self, value: f32, include: bool) -> bool {
         self.lower_key
-            .extend_from_slice(&Index::get_float_key(lower));
+            .extend_from_slice(&Index::get_float_key(value));
+        include || self.exclude_lower()
+    }
+
+    pub fn add_upper_float(&mut self, value: f32, include: bool) -> bool {
         self.upper_key
-            .extend_from_slice(&Index::get_float_key(upper));
+            .extend_from_slice(&Index::get_float_key(value));
+        include || self.exclude_upper()
     }
 
     pub fn add_long(&mut self, lower: i64, upper: i64) {
+        self.add_lower_long(lower, true);
+        self.add_upper_long(upper, true);
+    }
+
+    pub fn add_lower_long(&mut self, value: i64, include: bool) -> bool {
         self.lower_key
-            .extend_from_slice(&Index::get_long_key(lower));
+            .extend_from_slice(&Index::get_long_key(value));
+        include || self.exclude_lower()
+    }
+
+    pub fn add_upper_long(&mut self, value: i64, include: bool) -> bool {
         self.upper_key
-            .extend_from_slice(&Index::get_long_key(upper));
+            .extend_from_slice(&Index::get_long_key(value));
+        include || self.exclude_upper()
     }
 
     pub fn add_double(&mut self, lower: f64, upper: f64) {
+        self.add_lower_double(lower, true);
+        self.add_upper_double(upper, true);
+    }
+
+    pub fn add_lower_double(&mut self, value: f64, include: bool) -> bool {
         self.lower_key
-            .extend_from_slice(&Index::get_double_key(lower));
+            .extend_from_slice(&Index::get_double_key(value));
+        include || self.exclude_lower()
+    }
+
+    pub fn add_upper_double(&mut self, value: f64, include: bool) -> bool {
         self.upper_key
-            .extend_from_slice(&Index::get_double_key(upper));
+            .extend_from_slice(&Index::get_double_key(value));
+        include || self.exclude_upper()
     }
 
     pub fn add_string_hash(&mut self, value: Option<&str>) {
-        let hash = Index::get_string_hash_key(value);
+        let hash = Index::get_string_hash_key(value, self.case_sensitive);
         self.lower_key.extend_from_slice(&hash);
         self.upper_key.extend_from_slice(&hash);
     }
 
     pub fn add_string_value(&mut self, lower: Option<&str>, upper: Option<&str>) {
+        self.add_lower_string_value(lower, true);
+        self.add_upper_string_value(upper, true);
+    }
+
+    pub fn add_lower_string_value(&mut self, value: Option<&str>, include: bool) -> bool {
         self.lower_key
-            .extend_from_slice(&Index::get_string_value_key(lower));
+            .extend_from_slice(&Index::get_string_value_key(value, self.case_sensitive));
+        include || self.exclude_lower()
+    }
+
+    pub fn add_upper_string_value(&mut self, value: Option<&str>, include: bool) -> bool {
         self.upper_key
-            .extend_from_slice(&Index::get_string_value_key(upper));
+            .extend_from_slice(&Index::get_string_value_key(value, self.case_sensitive));
+        include || self.exclude_upper()
     }
 }
 
@@ -150,12 +352,16 @@ pub struct WhereClauseIterator<'a, 'txn> {
 
 impl<'a, 'txn> WhereClauseIterator<'a, 'txn> {
     fn new(where_clause: &'a WhereClause, cursor: &'a mut Cursor<'txn>) -> Result<Option<Self>> {
-        let result = cursor.move_to_gte(&where_clause.lower_key)?;
+        if where_clause.is_empty() {
+            return Ok(None);
+        }
+        let result = where_clause.move_to_first(cursor)?;
         if result.is_some() {
-            Ok(Some(WhereClauseIterator {
-                where_clause,
-                iter: cursor.iter(),
-            }))
+            let iter = match where_clause.sort {
+                Sort::Ascending => cursor.iter(),
+                Sort::Descending => cursor.iter_prev(),
+            };
+            Ok(Some(WhereClauseIterator { where_clause, iter }))
         } else {
             Ok(None)
         }
@@ -169,7 +375,7 @@ impl<'a, 'txn> Iterator for WhereClauseIterator<'a, 'txn> {
         let next = self.iter.next();
         match next? {
             Ok((key, val)) => {
-                if self.where_clause.check_below_upper_key(&key) {
+                if self.where_clause.is_in_range(key) {
                     Some(Ok((key, val)))
                 } else {
                     None
@@ -182,7 +388,9 @@ impl<'a, 'txn> Iterator for WhereClauseIterator<'a, 'txn> {
 
 #[cfg(test)]
 mod tests {
-    //use super::*;
+    use super::*;
+    use crate::lmdb::db::Db;
+    use crate::lmdb::env::tests::get_env;
     //use itertools::Itertools;
 
     #[macro_export]
@@ -245,4 +453,159 @@ mod tests {
 
     #[test]
     fn test_add_upper_oid() {}
+
+    #[test]
+    fn test_empty() {
+        let env = get_env();
+        let txn = env.txn(true).unwrap();
+        let db = Db::open(&txn, "test", false, false).unwrap();
+        for key in &[[0u8, 1], [5, 1], [10, 1], [255, 1]] {
+            db.put(&txn, key, b"val").unwrap();
+        }
+
+        let wc = WhereClause::empty();
+        assert!(wc.is_empty());
+        let mut cursor = db.cursor(&txn).unwrap();
+        assert_eq!(wc.iter(&mut cursor).unwrap().map_or(0, |i| i.count()), 0);
+    }
+
+    #[test]
+    fn test_add_exclusive_bounds() {
+        let keys = |add: &dyn Fn(&mut WhereClause) -> bool| {
+            let mut wc = WhereClause::new(&[0], IndexType::Secondary, true, false);
+            assert!(add(&mut wc));
+            (wc.lower_key, wc.upper_key)
+        };
+
+        macro_rules! assert_exclusive (
+            ($lower:ident, $upper:ident, $value:expr, $next:expr) => {
+                assert_eq!(
+                    keys(&|wc| wc.$lower($value, false)),
+                    keys(&|wc| wc.$lower($next, true))
+                );
+                assert_eq!(
+                    keys(&|wc| wc.$upper($next, false)),
+                    keys(&|wc| wc.$upper($value, true))
+                );
+            };
+        );
+
+        assert_exclusive!(add_lower_oid_time, add_upper_oid_time, 255, 256);
+        assert_exclusive!(add_lower_byte, add_upper_byte, 5, 6);
+        assert_exclusive!(add_lower_int, add_upper_int, -1, 0);
+        assert_exclusive!(add_lower_long, add_upper_long, 255, 256);
+        assert_exclusive!(
+            add_lower_float,
+            add_upper_float,
+            1.0,
+            f32::from_bits(1.0f32.to_bits() + 1)
+        );
+        assert_exclusive!(
+            add_lower_double,
+            add_upper_double,
+            -1.0,
+            -f64::from_bits(1.0f64.to_bits() - 1)
+        );
+
+        let mut wc = WhereClause::new(&[0], IndexType::Secondary, true, false);
+        assert!(!wc.add_lower_byte(u8::MAX, false));
+        let mut wc = WhereClause::new(&[0], IndexType::Secondary, true, false);
+        assert!(!wc.add_upper_long(i64::MIN, false));
+    }
+
+    #[test]
+    fn test_try_exclude() {
+        let wc = |lower: i32, upper: i32| {
+            let mut wc = WhereClause::new(&[0], IndexType::Secondary, true, false);
+            wc.add_int(lower, upper);
+            wc
+        };
+
+        let mut single = wc(5, 5);
+        assert!(single.try_exclude(true, true));
+        assert!(!single.is_empty());
+        assert!(!wc(5, 5).try_exclude(false, true));
+        assert!(!wc(5, 5).try_exclude(true, false));
+
+        let mut excluded = wc(5, 5);
+        assert!(!excluded.try_exclude(false, false));
+        assert!(excluded.is_empty());
+
+        // exclusion carries into the first byte of the value
+        let mut adjacent = wc(-1, 0);
+        assert!(!adjacent.try_exclude(false, false));
+        assert!(adjacent.is_empty());
+        let mut carried = wc(-1, 1);
+        assert!(carried.try_exclude(false, false));
+        assert_eq!(carried.lower_key, wc(0, 0).lower_key);
+        assert_eq!(carried.upper_key, wc(0, 0).upper_key);
+
+        assert!(!wc(i32::MAX, i32::MAX).try_exclude(false, true));
+        assert!(!wc(i32::MIN, i32::MIN).try_exclude(true, false));
+
+        let mut unbounded = WhereClause::new(&[0], IndexType::Secondary, true, false);
+        unbounded.add_lower_int(5, true);
+        assert!(unbounded.try_exclude(true, false));
+        assert_eq!(unbounded.upper_key, vec![0]);
+        assert!(unbounded.try_exclude(false, true));
+        assert_eq!(unbounded.lower_key, wc(6, 6).lower_key);
+    }
+
+    #[test]
+    fn test_iter_empty_range() {
+        let env = get_env();
+        let txn = env.txn(true).unwrap();
+        let db = Db::open(&txn, "test", false, false).unwrap();
+        for key in &[[0u8, 1], [0, 2], [0, 3]] {
+            db.put(&txn, key, b"val").unwrap();
+        }
+
+        let wc = WhereClause {
+            lower_key: vec![0, 3],
+            upper_key: vec![0, 1],
+            prefix_len: 1,
+            index_type: IndexType::Secondary,
+            sort: Sort::Descending,
+            case_sensitive: true,
+            multi_entry: false,
+        };
+        let mut cursor = db.cursor(&txn).unwrap();
+        assert!(wc.iter(&mut cursor).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_merge() {
+        let wc = |lower: &[u8], upper: &[u8]| WhereClause {
+            lower_key: lower.to_vec(),
+            upper_key: upper.to_vec(),
+            prefix_len: 1,
+            index_type: IndexType::Secondary,
+            sort: Sort::Ascending,
+            case_sensitive: true,
+            multi_entry: false,
+        };
+        let keys = |wc: Option<WhereClause>| wc.map(|wc| (wc.lower_key, wc.upper_key));
+
+        let merged = wc(&[0, 1], &[0, 5]).merge(&wc(&[0, 2], &[0, 3]));
+        assert_eq!(keys(merged), Some((vec![0, 1], vec![0, 5])));
+
+        let merged = wc(&[0, 3], &[0, 7]).merge(&wc(&[0, 1], &[0, 5]));
+        assert_eq!(keys(merged), Some((vec![0, 1], vec![0, 7])));
+
+        let merged = wc(&[0, 1], &[0, 5]).merge(&wc(&[0, 6], &[0, 7]));
+        assert_eq!(keys(merged), Some((vec![0, 1], vec![0, 7])));
+
+        let merged = wc(&[0, 1, 255], &[0, 1, 255]).merge(&wc(&[0, 2], &[0, 3]));
+        assert_eq!(keys(merged), Some((vec![0, 1, 255], vec![0, 3])));
+
+        let merged = wc(&[0, 1], &[0, 5]).merge(&wc(&[0, 5, 1], &[0, 5, 9]));
+        assert_eq!(keys(merged), Some((vec![0, 1], vec![0, 5])));
+
+        assert!(wc(&[0, 1], &[0, 5]).merge(&wc(&[0, 7], &[0, 9])).is_none());
+        assert!(wc(&[0, 1], &[0, 5]).merge(&wc(&[1, 1], &[1, 5])).is_none());
+
+        let mut primary = wc(&[0, 1], &[0, 5]);
+        primary.index_type = IndexType::Primary;
+        assert!(wc(&[0, 1], &[0, 5]).merge(&primary).is_none());
+    }
 }
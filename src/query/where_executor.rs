@@ -36,24 +36,60 @@ impl<'a, 'txn> WhereExecutor<'a, 'txn> {
     where
         F: FnMut(&'txn ObjectId, &'txn [u8]) -> bool,
     {
-        match self.where_clauses.len() {
-            1 => {
-                let where_clause = self.where_clauses.first().unwrap();
-                self.execute_where_clause(&where_clause, &mut None, &mut callback)?;
+        let mut hash_set = HashSet::new();
+        let mut result_ids = option!(self.where_clauses_overlapping, &mut hash_set);
+        for where_clause in self.where_clauses {
+            let result =
+                self.execute_where_clause(&where_clause, &mut result_ids, &mut callback)?;
+            if !result {
+                return Ok(());
             }
-            _ => {
-                let mut hash_set = HashSet::new();
-                let mut result_ids = option!(self.where_clauses_overlapping, &mut hash_set);
-                for where_clause in self.where_clauses {
-                    let result =
-                        self.execute_where_clause(&where_clause, &mut result_ids, &mut callback)?;
-                    if !result {
-                        return Ok(());
+        }
+        Ok(())
+    }
+
+    /// Pull-based alternative to `run`. The cursors are only advanced when the next object is
+    /// requested.
+    pub fn into_iter(self) -> WhereExecutorIter<'a, 'txn> {
+        WhereExecutorIter {
+            executor: self,
+            where_clause_index: 0,
+            started: false,
+            result_ids: HashSet::new(),
+        }
+    }
+
+    pub fn count(&mut self) -> Result<u32> {
+        let dedup = self.where_clauses_overlapping;
+        let mut result_ids = HashSet::new();
+        let mut count = 0;
+        for where_clause in self.where_clauses {
+            let cursor = match where_clause.index_type {
+                IndexType::Primary => &mut self.primary_cursor,
+                index_type => Self::get_secondary_cursor(
+                    &mut self.secondary_cursor,
+                    &mut self.secondary_dup_cursor,
+                    index_type,
+                )?,
+            };
+            if let Some(iter) = where_clause.iter(cursor)? {
+                for entry in iter {
+                    let (key, val) = entry?;
+                    if dedup {
+                        let oid = if where_clause.index_type == IndexType::Primary {
+                            key
+                        } else {
+                            val
+                        };
+                        if !result_ids.insert(oid) {
+                            continue;
+                        }
                     }
+                    count += 1;
                 }
             }
         }
-        Ok(())
+        Ok(count)
     }
 
     fn execute_where_clause(
@@ -98,11 +134,11 @@ impl<'a, 'txn> WhereExecutor<'a, 'txn> {
         result_ids: &mut Option<&mut HashSet<&'txn [u8]>>,
         callback: &mut impl FnMut(&'txn ObjectId, &'txn [u8]) -> bool,
     ) -> Result<bool> {
-        let cursor = if where_clause.index_type == IndexType::Secondary {
-            self.secondary_cursor.as_mut().unwrap()
-        } else {
-            self.secondary_dup_cursor.as_mut().unwrap()
-        };
+        let cursor = Self::get_secondary_cursor(
+            &mut self.secondary_cursor,
+            &mut self.secondary_dup_cursor,
+            where_clause.index_type,
+        )?;
         if let Some(iter) = where_clause.iter(cursor)? {
             for index_entry in iter {
                 let (_, key) = index_entry?;
@@ -127,32 +163,135 @@ impl<'a, 'txn> WhereExecutor<'a, 'txn> {
         }
         Ok(true)
     }
+
+    fn get_secondary_cursor<'c>(
+        secondary_cursor: &'c mut Option<Cursor<'txn>>,
+        secondary_dup_cursor: &'c mut Option<Cursor<'txn>>,
+        index_type: IndexType,
+    ) -> Result<&'c mut Cursor<'txn>> {
+        let cursor = if index_type == IndexType::Secondary {
+            secondary_cursor
+        } else {
+            secondary_dup_cursor
+        };
+        cursor.as_mut().ok_or_else(|| IsarError::IllegalState {
+            message: "The query has no cursor for a secondary where clause.".to_string(),
+        })
+    }
+}
+
+pub(super) struct WhereExecutorIter<'a, 'txn> {
+    executor: WhereExecutor<'a, 'txn>,
+    where_clause_index: usize,
+    started: bool,
+    result_ids: HashSet<&'txn [u8]>,
+}
+
+impl<'a, 'txn> WhereExecutorIter<'a, 'txn> {
+    fn next_entry(&mut self) -> Result<Option<(&'txn ObjectId, &'txn [u8])>> {
+        let executor = &mut self.executor;
+        let dedup = executor.where_clauses_overlapping;
+        while let Some(where_clause) = executor.where_clauses.get(self.where_clause_index) {
+            let cursor = match where_clause.index_type {
+                IndexType::Primary => &mut executor.primary_cursor,
+                index_type => WhereExecutor::get_secondary_cursor(
+                    &mut executor.secondary_cursor,
+                    &mut executor.secondary_dup_cursor,
+                    index_type,
+                )?,
+            };
+            let entry = if self.started {
+                where_clause.move_to_next(cursor)?
+            } else {
+                self.started = true;
+                where_clause.move_to_first(cursor)?
+            };
+
+            let (key, val) = match entry {
+                Some((key, val)) if where_clause.is_in_range(key) => (key, val),
+                _ => {
+                    self.where_clause_index += 1;
+                    self.started = false;
+                    continue;
+                }
+            };
+
+            let oid = if where_clause.index_type == IndexType::Primary {
+                key
+            } else {
+                val
+            };
+            if dedup && !self.result_ids.insert(oid) {
+                continue;
+            }
+
+            if where_clause.index_type == IndexType::Primary {
+                return Ok(Some((ObjectId::from_bytes(key), val)));
+            } else if let Some((_, object)) = executor.primary_cursor.move_to(oid)? {
+                return Ok(Some((ObjectId::from_bytes(oid), object)));
+            } else {
+                return Err(IsarError::DbCorrupted {
+                    source: None,
+                    message: "Could not find object specified in index.".to_string(),
+                });
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<'a, 'txn> Iterator for WhereExecutorIter<'a, 'txn> {
+    type Item = Result<(&'txn ObjectId, &'txn [u8])>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_entry() {
+            Ok(Some(entry)) => Some(Ok(entry)),
+            Ok(None) => None,
+            Err(e) => {
+                self.where_clause_index = self.executor.where_clauses.len();
+                Some(Err(e))
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::instance::IsarInstance;
+    use crate::object::property::Property;
+    use crate::query::query::Sort;
+    use crate::txn::IsarTxn;
     use crate::utils::debug::fill_db;
     use crate::*;
+    use itertools::Itertools;
 
-    fn execute_where_clauses(
+    fn create_executor<'a, 'txn>(
         isar: &IsarInstance,
-        wc: &[WhereClause],
+        txn: &'txn IsarTxn,
+        wc: &'a [WhereClause],
         overlapping: bool,
-    ) -> Vec<u32> {
-        let txn = isar.begin_txn(false).unwrap();
-        let lmdb_txn = txn.get_txn();
+    ) -> WhereExecutor<'a, 'txn> {
+        let lmdb_txn = txn.get_txn().unwrap();
         let primary_cursor = isar.debug_get_primary_db().cursor(lmdb_txn).unwrap();
         let secondary_cursor = isar.debug_get_secondary_db().cursor(lmdb_txn).unwrap();
         let secondary_dup_cursor = isar.debug_get_secondary_dup_db().cursor(lmdb_txn).unwrap();
-        let mut executer = WhereExecutor::new(
+        WhereExecutor::new(
             primary_cursor,
             Some(secondary_cursor),
             Some(secondary_dup_cursor),
             &wc,
             overlapping,
-        );
+        )
+    }
+
+    fn execute_where_clauses(
+        isar: &IsarInstance,
+        wc: &[WhereClause],
+        overlapping: bool,
+    ) -> Vec<u32> {
+        let txn = isar.begin_txn(false).unwrap();
+        let mut executer = create_executor(isar, &txn, wc, overlapping);
         let mut entries = vec![];
         executer
             .run(|oid, _| {
@@ -160,13 +299,48 @@ mod tests {
                 true
             })
             .unwrap();
+
+        let iter_entries = create_executor(isar, &txn, wc, overlapping)
+            .into_iter()
+            .map(|entry| entry.unwrap().0.get_time())
+            .collect_vec();
+        assert_eq!(entries, iter_entries);
+
         entries
     }
 
+    #[test]
+    fn test_run_missing_secondary_cursor() {
+        let isar = get_test_db();
+        let col = isar.get_collection(0).unwrap();
+        let txn = isar.begin_txn(false).unwrap();
+        let lmdb_txn = txn.get_txn().unwrap();
+        let wc = [col.create_secondary_where_clause(0).unwrap()];
+
+        let primary_cursor = isar.debug_get_primary_db().cursor(lmdb_txn).unwrap();
+        let mut executor = WhereExecutor::new(primary_cursor, None, None, &wc, false);
+        let result = executor.run(|_, _| true);
+        assert!(matches!(result, Err(IsarError::IllegalState { .. })));
+
+        let primary_cursor = isar.debug_get_primary_db().cursor(lmdb_txn).unwrap();
+        let mut executor = WhereExecutor::new(primary_cursor, None, None, &wc, false);
+        assert!(matches!(
+            executor.count(),
+            Err(IsarError::IllegalState { .. })
+        ));
+
+        let primary_cursor = isar.debug_get_primary_db().cursor(lmdb_txn).unwrap();
+        let executor = WhereExecutor::new(primary_cursor, None, None, &wc, false);
+        let mut iter = executor.into_iter();
+        assert!(matches!(
+            iter.next(),
+            Some(Err(IsarError::IllegalState { .. }))
+        ));
+        assert!(iter.next().is_none());
+    }
+
     fn get_test_db() -> IsarInstance {
         isar!(isar, col => col!(f1 => Int, f2=> Int, f3 => String; ind!(f1, f3), ind!(f2; true)));
-        let mut txn = isar.begin_txn(true).unwrap();
-
         let build_value = |field1: i32, field2: i32, field3: &str| {
             let mut builder = col.get_object_builder();
             builder.write_int(field1);
@@ -185,8 +359,11 @@ mod tests {
             (oid(5), build_value(3, 5, "bbb")),
             (oid(6), build_value(3, 6, "bcc")),
         ];
-        fill_db(col, &mut txn, &data);
-        txn.commit().unwrap();
+        {
+            let mut txn = isar.begin_txn(true).unwrap();
+            fill_db(col, &mut txn, &data);
+            txn.commit().unwrap();
+        }
 
         isar
     }
@@ -263,6 +440,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_run_bool_where_clause() {
+        isar!(isar, col => col!(f1 => Byte; ind!(f1)));
+        let mut txn = isar.begin_txn(true).unwrap();
+        let data = [Some(true), None, Some(false), Some(true)]
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let mut builder = col.get_object_builder();
+                builder.write_byte(Property::bool_to_byte(*value));
+                (Some(col.get_object_id(i as u32, 0, 0)), builder.finish())
+            })
+            .collect::<Vec<_>>();
+        fill_db(col, &mut txn, &data);
+        txn.commit().unwrap();
+
+        let bool_wc = |value: Option<bool>| {
+            let mut wc = col.create_secondary_where_clause(0).unwrap();
+            wc.add_bool(value);
+            wc
+        };
+        assert_eq!(
+            execute_where_clauses(&isar, &[bool_wc(Some(true))], false),
+            vec![0, 3]
+        );
+        assert_eq!(
+            execute_where_clauses(&isar, &[bool_wc(Some(false))], false),
+            vec![2]
+        );
+        assert_eq!(
+            execute_where_clauses(&isar, &[bool_wc(None)], false),
+            vec![1]
+        );
+
+        let all_wc = col.create_secondary_where_clause(0).unwrap();
+        assert_eq!(
+            execute_where_clauses(&isar, &[all_wc], false),
+            vec![1, 2, 0, 3]
+        );
+    }
+
     #[test]
     fn test_run_single_secondary_compound_where_clause() {
         let isar = get_test_db();
@@ -275,8 +493,167 @@ mod tests {
             vec![3, 4, 5, 6]
         );
 
-        //wc.add_int(4, 5);
-        //assert_eq!(execute_where_clauses(&isar, &[wc], false), vec![4, 5]);
+        let mut wc = col.create_secondary_where_clause(0).unwrap();
+        wc.add_int(2, 2);
+        wc.add_lower_string_value(Some("b"), true);
+        assert_eq!(execute_where_clauses(&isar, &[wc], false), vec![4]);
+
+        let mut wc = col.create_secondary_where_clause(0).unwrap();
+        wc.add_int(1, 1);
+        wc.add_upper_string_value(Some("ab"), true);
+        assert_eq!(execute_where_clauses(&isar, &[wc], false), vec![1]);
+
+        let mut wc = col.create_secondary_where_clause(0).unwrap();
+        wc.add_int(1, 1);
+        wc.add_upper_string_value(Some("abb"), true);
+        assert_eq!(execute_where_clauses(&isar, &[wc], false), vec![1, 2]);
+
+        let mut wc = col.create_secondary_where_clause(0).unwrap();
+        wc.add_lower_int(2, true);
+        wc.add_lower_string_value(Some("b"), true);
+        wc.add_upper_int(3, true);
+        wc.add_upper_string_value(Some("bbb"), true);
+        assert_eq!(execute_where_clauses(&isar, &[wc], false), vec![4, 5]);
+    }
+
+    #[test]
+    fn test_run_three_property_where_clause() {
+        isar!(isar, col => col!(f1 => Int, f2 => Long, f3 => String; ind!(f1, f2, f3; false, true)));
+        let build_value = |field1: i32, field2: i64, field3: &str| {
+            let mut builder = col.get_object_builder();
+            builder.write_int(field1);
+            builder.write_long(field2);
+            builder.write_string(Some(field3));
+            builder.finish()
+        };
+        let oid = |time: u32| Some(col.get_object_id(time, 0, 0));
+        let data = vec![
+            (oid(1), build_value(1, 20, "a")),
+            (oid(2), build_value(1, 10, "b")),
+            (oid(3), build_value(1, 10, "a")),
+            (oid(4), build_value(2, 10, "a")),
+            (oid(5), build_value(1, -5, "a")),
+            (oid(6), build_value(1, 15, "c")),
+        ];
+        {
+            let mut txn = isar.begin_txn(true).unwrap();
+            fill_db(col, &mut txn, &data);
+            txn.commit().unwrap();
+        }
+
+        let mut wc = col.create_secondary_where_clause(0).unwrap();
+        wc.add_int(1, 1);
+        let result = execute_where_clauses(&isar, &[wc], false);
+        assert_eq!(result[0], 5);
+        assert_eq!(result[3], 6);
+        assert_eq!(result[4], 1);
+        assert_eq!(
+            result.iter().sorted().collect_vec(),
+            vec![&1, &2, &3, &5, &6]
+        );
+
+        let mut wc = col.create_secondary_where_clause(0).unwrap();
+        wc.add_int(1, 1);
+        wc.add_long(0, 15);
+        let result = execute_where_clauses(&isar, &[wc], false);
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[2], 6);
+
+        let mut wc = col.create_secondary_where_clause(0).unwrap();
+        wc.add_int(1, 1);
+        wc.add_long(10, 10);
+        wc.add_string_hash(Some("a"));
+        assert_eq!(execute_where_clauses(&isar, &[wc], false), vec![3]);
+
+        let mut wc = col.create_secondary_where_clause(0).unwrap();
+        wc.add_int(2, 2);
+        wc.add_long(10, 10);
+        wc.add_string_hash(Some("b"));
+        assert!(execute_where_clauses(&isar, &[wc], false).is_empty());
+    }
+
+    #[test]
+    fn test_run_exclusive_primary_where_clause() {
+        let isar = get_test_db();
+        let col = isar.get_collection(0).unwrap();
+
+        let oid_wc = |lower: u32, include_lower: bool, upper: u32, include_upper: bool| {
+            let mut wc = col.create_primary_where_clause();
+            assert!(wc.add_lower_oid_time(lower, include_lower));
+            assert!(wc.add_upper_oid_time(upper, include_upper));
+            wc
+        };
+        assert_eq!(
+            execute_where_clauses(&isar, &[oid_wc(2, true, 4, true)], false),
+            vec![2, 3, 4]
+        );
+        assert_eq!(
+            execute_where_clauses(&isar, &[oid_wc(2, false, 4, true)], false),
+            vec![3, 4]
+        );
+        assert_eq!(
+            execute_where_clauses(&isar, &[oid_wc(2, true, 4, false)], false),
+            vec![2, 3]
+        );
+        assert_eq!(
+            execute_where_clauses(&isar, &[oid_wc(2, false, 4, false)], false),
+            vec![3]
+        );
+        assert_eq!(
+            execute_where_clauses(&isar, &[oid_wc(3, false, 4, false)], false),
+            Vec::<u32>::new()
+        );
+    }
+
+    #[test]
+    fn test_run_descending_where_clause() {
+        let isar = get_test_db();
+        let col = isar.get_collection(0).unwrap();
+
+        let mut wc = col.create_primary_where_clause();
+        wc.add_oid_time(2, 4);
+        wc.set_sort(Sort::Descending);
+        assert_eq!(execute_where_clauses(&isar, &[wc], false), vec![4, 3, 2]);
+
+        let mut wc = col.create_secondary_where_clause(0).unwrap();
+        wc.add_int(2, i32::MAX);
+        wc.set_sort(Sort::Descending);
+        assert_eq!(execute_where_clauses(&isar, &[wc], false), vec![6, 5, 4, 3]);
+
+        let mut wc = col.create_secondary_where_clause(1).unwrap();
+        wc.add_int(i32::MIN, 2);
+        wc.set_sort(Sort::Descending);
+        assert_eq!(execute_where_clauses(&isar, &[wc], false), vec![2, 1]);
+
+        let mut wc = col.create_secondary_where_clause(1).unwrap();
+        wc.add_int(50, i32::MAX);
+        wc.set_sort(Sort::Descending);
+        assert_eq!(
+            execute_where_clauses(&isar, &[wc], false),
+            Vec::<u32>::new()
+        );
+    }
+
+    #[test]
+    fn test_into_iter_stops_early() {
+        let isar = get_test_db();
+        let col = isar.get_collection(0).unwrap();
+        let txn = isar.begin_txn(false).unwrap();
+
+        let wc = col.create_primary_where_clause();
+        let wcs = [wc];
+        let mut iter = create_executor(&isar, &txn, &wcs, false).into_iter();
+        let entries = iter
+            .by_ref()
+            .take(2)
+            .map(|entry| entry.unwrap().0.get_time())
+            .collect_vec();
+        assert_eq!(entries, vec![1, 2]);
+
+        let (key, _) = iter.executor.primary_cursor.get().unwrap().unwrap();
+        assert_eq!(ObjectId::from_bytes(key).get_time(), 2);
+
+        assert_eq!(iter.next().unwrap().unwrap().0.get_time(), 3);
     }
 
     #[test]
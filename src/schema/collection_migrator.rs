@@ -1,11 +1,13 @@
 use crate::collection::IsarCollection;
 use crate::error::Result;
 use crate::index::Index;
+use crate::link::Link;
 use crate::lmdb::db::Db;
 use crate::lmdb::txn::Txn;
 use crate::object::data_type::DataType;
 use crate::object::object_builder::ObjectBuilder;
 use crate::object::property::Property;
+use crate::object::property_value::PropertyValue;
 
 pub struct CollectionMigrator<'a> {
     retained_properties: Vec<Option<&'a Property>>,
@@ -13,43 +15,44 @@ pub struct CollectionMigrator<'a> {
     object_migration_required: bool,
     removed_indexes: Vec<&'a Index>,
     added_indexes: Vec<&'a Index>,
+    removed_links: Vec<&'a Link>,
 }
 
 impl<'a> CollectionMigrator<'a> {
-    pub fn create(collection: &'a IsarCollection, existing_collection: &'a IsarCollection) -> Self {
+    pub fn create(
+        collection: &'a IsarCollection,
+        existing_collection: &'a IsarCollection,
+        renamed_properties: &[(String, String)],
+    ) -> Self {
         let properties = collection.get_properties();
         let existing_properties = existing_collection.get_properties();
 
         let mut retained_properties = vec![];
         for property in properties {
-            let existing_property = existing_properties
-                .iter()
-                .find(|p| property.name == p.name && property.data_type == p.data_type);
+            let existing_property = Self::find_existing_property(
+                &property.name,
+                renamed_properties,
+                existing_properties,
+                |p| &p.name,
+            )
+            .filter(|p| Self::is_convertible(p.data_type, property.data_type));
             retained_properties.push(existing_property);
         }
-        let object_migration_required = retained_properties.iter().any(|p| p.is_none());
-
-        let mut added_indexes = vec![];
-        for index in collection.get_indexes() {
-            let existed = existing_collection
-                .get_indexes()
-                .iter()
-                .any(|i| i.get_id() == index.get_id());
-            if !existed {
-                added_indexes.push(index);
-            }
-        }
+        let object_migration_required = retained_properties
+            .iter()
+            .zip(properties)
+            .any(|(existing, p)| !matches!(existing, Some(e) if e.data_type == p.data_type));
 
-        let mut removed_indexes = vec![];
-        for existing_index in existing_collection.get_indexes() {
-            let still_exists = collection
-                .get_indexes()
-                .iter()
-                .any(|i| i.get_id() == existing_index.get_id());
-            if !still_exists {
-                removed_indexes.push(existing_index);
-            }
-        }
+        let (added_indexes, removed_indexes) = Self::compare(
+            collection.get_indexes(),
+            existing_collection.get_indexes(),
+            |i1, i2| i1.get_id() == i2.get_id(),
+        );
+        let (_, removed_links) = Self::compare(
+            collection.get_all_links(),
+            existing_collection.get_all_links(),
+            |l1, l2| l1.get_id() == l2.get_id(),
+        );
 
         CollectionMigrator {
             retained_properties,
@@ -57,6 +60,7 @@ impl<'a> CollectionMigrator<'a> {
             object_migration_required,
             added_indexes,
             removed_indexes,
+            removed_links,
         }
     }
 
@@ -65,20 +69,23 @@ impl<'a> CollectionMigrator<'a> {
             removed_index.clear(txn)?;
         }
 
+        for removed_link in self.removed_links {
+            removed_link.clear(txn)?;
+        }
+
         if !self.added_indexes.is_empty() || self.object_migration_required {
+            let prefix = self.collection.get_id().to_le_bytes();
             let mut cursor = primary_db.cursor(txn)?;
-            if cursor.move_to_first()?.is_none() {
-                return Ok(());
-            }
 
             if self.object_migration_required {
-                for entry in cursor.iter() {
+                let mut ob = self.collection.get_object_builder();
+                for entry in cursor.iter_prefix(&prefix) {
                     let (key, object) = entry?;
-                    let mut ob = self.collection.get_object_builder();
-                    for property in &self.retained_properties {
-                        Self::write_property_to_ob(&mut ob, *property, object);
+                    let properties = self.collection.get_properties();
+                    for (property, existing) in properties.iter().zip(&self.retained_properties) {
+                        Self::write_property_to_ob(&mut ob, property, *existing, object)?;
                     }
-                    let ob_result = ob.finish();
+                    let ob_result = ob.build();
                     let new_object = ob_result.as_bytes();
                     primary_db.put(txn, key, new_object)?;
                     for index in &self.added_indexes {
@@ -86,7 +93,7 @@ impl<'a> CollectionMigrator<'a> {
                     }
                 }
             } else {
-                for entry in cursor.iter() {
+                for entry in cursor.iter_prefix(&prefix) {
                     let (key, object) = entry?;
                     for index in &self.added_indexes {
                         index.create_for_object(&txn, key, object)?;
@@ -98,27 +105,87 @@ impl<'a> CollectionMigrator<'a> {
         Ok(())
     }
 
-    fn write_property_to_ob(ob: &mut ObjectBuilder, property: Option<&Property>, object: &[u8]) {
-        if let Some(p) = property {
-            match p.data_type {
-                DataType::Byte => ob.write_byte(p.get_byte(object)),
-                DataType::Int => ob.write_int(p.get_int(object)),
-                DataType::Float => ob.write_float(p.get_float(object)),
-                DataType::Long => ob.write_long(p.get_long(object)),
-                DataType::Double => ob.write_double(p.get_double(object)),
-                DataType::String => ob.write_string(p.get_string(object)),
-                DataType::ByteList => ob.write_byte_list(p.get_byte_list(object)),
-                DataType::IntList => ob.write_int_list(p.get_int_list(object)),
-                DataType::FloatList => ob.write_float_list(p.get_float_list(object)),
-                DataType::LongList => ob.write_long_list(p.get_long_list(object)),
-                DataType::DoubleList => ob.write_double_list(p.get_double_list(object)),
-                DataType::StringList => {
-                    unimplemented!("String list migration not ready yet")
-                }
-            }
+    /// Returns the existing property that `name` had before the `renamed_properties` (pairs of
+    /// old and new name) were applied.
+    pub(super) fn find_existing_property<'p, P>(
+        name: &str,
+        renamed_properties: &[(String, String)],
+        existing_properties: &'p [P],
+        get_name: impl Fn(&P) -> &str,
+    ) -> Option<&'p P> {
+        let existing_name = renamed_properties
+            .iter()
+            .find(|(_, new_name)| new_name == name)
+            .map_or(name, |(old_name, _)| old_name);
+        existing_properties
+            .iter()
+            .find(|p| get_name(p) == existing_name)
+    }
+
+    /// Returns the items that only exist in `items` and the items that only exist in
+    /// `existing_items`.
+    pub(super) fn compare<'i, T>(
+        items: &'i [T],
+        existing_items: &'i [T],
+        matches: impl Fn(&T, &T) -> bool,
+    ) -> (Vec<&'i T>, Vec<&'i T>) {
+        let added = items
+            .iter()
+            .filter(|i| !existing_items.iter().any(|e| matches(i, e)))
+            .collect();
+        let removed = existing_items
+            .iter()
+            .filter(|e| !items.iter().any(|i| matches(i, e)))
+            .collect();
+        (added, removed)
+    }
+
+    /// Returns `true` if values of type `from` can be migrated to type `to` without loss.
+    pub(super) fn is_convertible(from: DataType, to: DataType) -> bool {
+        matches!(
+            (from, to),
+            (DataType::Int, DataType::Long)
+                | (DataType::Float, DataType::Double)
+                | (DataType::Int, DataType::Double)
+        ) || from == to
+    }
+
+    fn write_property_to_ob(
+        ob: &mut ObjectBuilder,
+        property: &Property,
+        existing_property: Option<&Property>,
+        object: &[u8],
+    ) -> Result<()> {
+        let existing_property = if let Some(existing_property) = existing_property {
+            existing_property
         } else {
             ob.write_null();
+            return Ok(());
+        };
+
+        match (existing_property.data_type, property.data_type) {
+            (DataType::Int, DataType::Long) => {
+                let value = existing_property.get_int(object);
+                if value == Property::NULL_INT {
+                    ob.write_long(Property::NULL_LONG);
+                } else {
+                    ob.write_long(value as i64);
+                }
+            }
+            (DataType::Float, DataType::Double) => {
+                ob.write_double(existing_property.get_float(object) as f64);
+            }
+            (DataType::Int, DataType::Double) => {
+                let value = existing_property.get_int(object);
+                if value == Property::NULL_INT {
+                    ob.write_double(Property::NULL_DOUBLE);
+                } else {
+                    ob.write_double(value as f64);
+                }
+            }
+            _ => ob.write_value(&PropertyValue::read(existing_property, object))?,
         }
+        Ok(())
     }
 }
 
@@ -1,12 +1,14 @@
 use crate::collection::IsarCollection;
 use crate::data_dbs::DataDbs;
-use crate::error::{illegal_arg, Result};
+use crate::error::{illegal_arg, invalid_index, IsarError, Result};
 use crate::index::{Index, IndexType};
+use crate::link::Link;
 use crate::object::data_type::DataType;
 use crate::object::object_id::ObjectId;
 use crate::object::object_info::ObjectInfo;
 use crate::object::property::Property;
 use crate::schema::index_schema::IndexSchema;
+use crate::schema::link_schema::LinkSchema;
 use crate::schema::property_schema::PropertySchema;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
@@ -17,8 +19,14 @@ use std::cmp::Ordering;
 pub struct CollectionSchema {
     pub(crate) id: Option<u16>,
     pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) version: u32,
     pub(crate) properties: Vec<PropertySchema>,
     pub(crate) indexes: Vec<IndexSchema>,
+    #[serde(default)]
+    pub(crate) links: Vec<LinkSchema>,
+    #[serde(skip)]
+    pub(crate) renamed_properties: Vec<(String, String)>,
 }
 
 impl CollectionSchema {
@@ -26,29 +34,44 @@ impl CollectionSchema {
         CollectionSchema {
             id: None,
             name: name.to_string(),
+            version: 0,
             properties: vec![],
             indexes: vec![],
+            links: vec![],
+            renamed_properties: vec![],
         }
     }
 
+    /// Sets the schema version of this collection. The version has to be increased whenever
+    /// the collection changes. Collections with an unchanged version are trusted and not
+    /// migrated, changing a collection without increasing its version is a bug of the caller
+    /// and leaves stored objects and indexes that do not match the schema.
+    /// Version `0` disables version checks and always compares the full collection.
+    pub fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+
     pub fn add_property(&mut self, name: &str, data_type: DataType) -> Result<()> {
         if name.is_empty() {
             illegal_arg("Empty properties are not allowed")?;
         }
 
         if self.properties.iter().any(|f| f.name == name) {
-            illegal_arg("Property already exists")?;
+            return Err(IsarError::SchemaDuplicateProperty {
+                property: name.to_string(),
+            });
         }
 
         if let Some(previous) = self.properties.last() {
-            match data_type.cmp(&previous.data_type) {
-                Ordering::Equal => {
-                    if name < &previous.name {
-                        illegal_arg("Propertys with same type need to be ordered alphabetically")?;
-                    }
-                }
-                Ordering::Less => illegal_arg("Propertys need to be ordered by type")?,
-                Ordering::Greater => {}
+            let ordered = match data_type.cmp(&previous.data_type) {
+                Ordering::Equal => name >= &previous.name,
+                Ordering::Less => false,
+                Ordering::Greater => true,
+            };
+            if !ordered {
+                return Err(IsarError::SchemaPropertyOrder {
+                    property: name.to_string(),
+                });
             }
         }
 
@@ -60,18 +83,44 @@ impl CollectionSchema {
         Ok(())
     }
 
+    /// Marks `new_name` as the new name of the property `old_name` so its data is retained
+    /// during migration. The renamed property needs to keep its data type.
+    pub fn rename_property(&mut self, old_name: &str, new_name: &str) -> Result<()> {
+        if !self.properties.iter().any(|p| p.name == new_name) {
+            illegal_arg("Renamed property does not exist.")?;
+        }
+
+        if self.properties.iter().any(|p| p.name == old_name) {
+            illegal_arg("A property with the old name still exists.")?;
+        }
+
+        let already_renamed = self
+            .renamed_properties
+            .iter()
+            .any(|(old, new)| old == old_name || new == new_name);
+        if already_renamed {
+            illegal_arg("Property has already been renamed.")?;
+        }
+
+        self.renamed_properties
+            .push((old_name.to_string(), new_name.to_string()));
+        Ok(())
+    }
+
     pub fn add_index(
         &mut self,
         property_names: &[&str],
         unique: bool,
         hash_value: bool,
+        multi_entry: bool,
+        case_sensitive: bool,
     ) -> Result<()> {
         if property_names.is_empty() {
-            illegal_arg("At least one property needs to be added to a valid index.")?;
+            invalid_index("At least one property needs to be added to a valid index.")?;
         }
 
         if property_names.len() > 3 {
-            illegal_arg("No more than three properties may be used as a composite index.")?;
+            invalid_index("No more than three properties may be used as a composite index.")?;
         }
 
         let properties: Option<Vec<_>> = property_names
@@ -84,7 +133,7 @@ impl CollectionSchema {
             })
             .collect();
         if properties.is_none() {
-            illegal_arg("Index property does not exist.")?;
+            invalid_index("Index property does not exist.")?;
         }
         let properties = properties.unwrap();
 
@@ -93,48 +142,91 @@ impl CollectionSchema {
             i.properties[..min_len] == properties[..min_len]
         });
         if duplicate {
-            illegal_arg("Index already exists.")?;
+            invalid_index("Index already exists.")?;
         }
 
-        let illegal_data_type = properties
-            .iter()
-            .any(|p| p.data_type.is_dynamic() && p.data_type != DataType::String);
-        if illegal_data_type {
-            illegal_arg("Illegal index data type.")?;
+        if multi_entry {
+            if properties.len() != 1 {
+                invalid_index("Multi-entry indexes must contain exactly one property.")?;
+            }
+            let property = properties.first().unwrap();
+            if property.data_type.is_static()
+                || property.data_type == DataType::String
+                || property.data_type == DataType::BytesList
+            {
+                invalid_index("Multi-entry indexes are only supported for list properties.")?;
+            }
+        } else {
+            let illegal_data_type = properties
+                .iter()
+                .any(|p| p.data_type.is_dynamic() && p.data_type != DataType::String);
+            if illegal_data_type {
+                invalid_index("Illegal index data type.")?;
+            }
         }
 
-        let has_string_properties = properties.iter().any(|p| p.data_type == DataType::String);
+        let has_string_properties = properties
+            .iter()
+            .any(|p| p.data_type == DataType::String || p.data_type == DataType::StringList);
         if !has_string_properties && hash_value {
-            illegal_arg("Only string indexes can be hashed.")?;
+            invalid_index("Only string indexes can be hashed.")?;
+        }
+
+        if !has_string_properties && !case_sensitive {
+            invalid_index("Only string indexes can be case insensitive.")?;
         }
 
         if !hash_value {
             for (index, property) in properties.iter().enumerate() {
                 if property.data_type == DataType::String && index < properties.len() - 1 {
-                    illegal_arg(
+                    invalid_index(
                         "Non-hashed string indexes must only be at the end of a composite index.",
                     )?;
                 }
             }
         }
 
-        self.indexes
-            .push(IndexSchema::new(properties, unique, hash_value));
+        self.indexes.push(IndexSchema::new(
+            properties,
+            unique,
+            hash_value,
+            multi_entry,
+            case_sensitive,
+        ));
+
+        Ok(())
+    }
+
+    pub fn add_link(&mut self, name: &str, target_collection: &str) -> Result<()> {
+        if name.is_empty() {
+            illegal_arg("Empty links are not allowed.")?;
+        }
+
+        if self.links.iter().any(|l| l.name == name) {
+            illegal_arg("Link already exists.")?;
+        }
 
+        self.links.push(LinkSchema::new(name, target_collection));
         Ok(())
     }
 
-    pub(super) fn get_isar_collection(&self, dbs: DataDbs) -> IsarCollection {
+    pub(super) fn get_isar_collection(
+        &self,
+        dbs: DataDbs,
+        collections: &[CollectionSchema],
+    ) -> Result<IsarCollection> {
         let properties = self.get_properties();
         let indexes = self.get_indexes(&properties, dbs);
+        let links = self.get_links(collections, dbs)?;
         let object_info = ObjectInfo::new(properties);
-        IsarCollection::new(
+        Ok(IsarCollection::new(
             self.id.unwrap(),
             self.name.clone(),
             object_info,
             indexes,
+            links,
             dbs.primary,
-        )
+        ))
     }
 
     fn get_properties(&self) -> Vec<Property> {
@@ -181,12 +273,54 @@ impl CollectionSchema {
                     properties,
                     index_type,
                     index.hash_value,
+                    index.multi_entry,
+                    index.case_sensitive,
                     db,
                 )
             })
             .collect()
     }
 
+    fn get_links(&self, collections: &[CollectionSchema], dbs: DataDbs) -> Result<Vec<Link>> {
+        self.links
+            .iter()
+            .map(|link| {
+                let target = collections
+                    .iter()
+                    .find(|c| c.name == link.target_collection);
+                if let Some(target) = target {
+                    Ok(Link::new(
+                        link.id.unwrap(),
+                        self.id.unwrap(),
+                        target.id.unwrap(),
+                        dbs.links,
+                    ))
+                } else {
+                    illegal_arg("Link target collection does not exist.")
+                }
+            })
+            .collect()
+    }
+
+    /// Returns `true` if the `existing` collection has the same non-zero version and no
+    /// migration is required. The collections are not compared. Fails if the version is lower
+    /// than the existing version.
+    pub(super) fn is_unchanged(&self, existing: &CollectionSchema) -> Result<bool> {
+        if self.version == 0 || existing.version == 0 {
+            Ok(false)
+        } else if self.version < existing.version {
+            Err(IsarError::MigrationError {
+                source: None,
+                message: format!(
+                    "The version of collection {} is lower than the existing version.",
+                    self.name
+                ),
+            })
+        } else {
+            Ok(self.version == existing.version)
+        }
+    }
+
     pub(super) fn update_with_existing_collections(
         &mut self,
         existing_collections: &[CollectionSchema],
@@ -197,10 +331,25 @@ impl CollectionSchema {
         let id = existing_collection.map_or_else(|| get_id(), |e| e.id.unwrap());
         self.id = Some(id);
 
+        let existing_properties: &[PropertySchema] =
+            existing_collection.map_or(&[], |e| &e.properties);
+        let properties = &self.properties;
+        self.renamed_properties.retain(|(old_name, new_name)| {
+            let new_property = properties.iter().find(|p| &p.name == new_name).unwrap();
+            existing_properties
+                .iter()
+                .any(|p| &p.name == old_name && p.data_type == new_property.data_type)
+        });
+
         let existing_indexes: &[IndexSchema] = existing_collection.map_or(&[], |e| &e.indexes);
         for index in &mut self.indexes {
             index.update_with_existing_indexes(existing_indexes, get_id);
         }
+
+        let existing_links: &[LinkSchema] = existing_collection.map_or(&[], |e| &e.links);
+        for link in &mut self.links {
+            link.update_with_existing_links(existing_links, get_id);
+        }
     }
 }
 
@@ -218,7 +367,10 @@ mod tests {
     fn test_add_property_duplicate_name() {
         let mut col = CollectionSchema::new("col");
         col.add_property("prop", DataType::Int).unwrap();
-        assert!(col.add_property("prop", DataType::Int).is_err())
+        assert!(matches!(
+            col.add_property("prop", DataType::Int),
+            Err(IsarError::SchemaDuplicateProperty { .. })
+        ))
     }
 
     #[test]
@@ -226,7 +378,10 @@ mod tests {
         let mut col = CollectionSchema::new("col");
 
         col.add_property("b", DataType::Int).unwrap();
-        assert!(col.add_property("a", DataType::Int).is_err())
+        assert!(matches!(
+            col.add_property("a", DataType::Int),
+            Err(IsarError::SchemaPropertyOrder { .. })
+        ))
     }
 
     #[test]
@@ -234,14 +389,20 @@ mod tests {
         let mut col = CollectionSchema::new("col");
 
         col.add_property("a", DataType::Long).unwrap();
-        assert!(col.add_property("b", DataType::Int).is_err())
+        assert!(matches!(
+            col.add_property("b", DataType::Int),
+            Err(IsarError::SchemaPropertyOrder { .. })
+        ))
     }
 
     #[test]
     fn test_add_index_without_properties() {
         let mut col = CollectionSchema::new("col");
 
-        assert!(col.add_index(&[], false, false).is_err())
+        assert!(matches!(
+            col.add_index(&[], false, false, false, true),
+            Err(IsarError::SchemaInvalidIndex { .. })
+        ))
     }
 
     #[test]
@@ -249,8 +410,12 @@ mod tests {
         let mut col = CollectionSchema::new("col");
         col.add_property("prop1", DataType::Int).unwrap();
 
-        col.add_index(&["prop1"], false, false).unwrap();
-        assert!(col.add_index(&["wrongprop"], false, false).is_err())
+        col.add_index(&["prop1"], false, false, false, true)
+            .unwrap();
+        assert!(matches!(
+            col.add_index(&["wrongprop"], false, false, false, true),
+            Err(IsarError::SchemaInvalidIndex { .. })
+        ))
     }
 
     #[test]
@@ -265,14 +430,75 @@ mod tests {
         col.add_property("byteList", DataType::ByteList).unwrap();
         col.add_property("intList", DataType::IntList).unwrap();
 
-        col.add_index(&["byte"], false, false).unwrap();
-        col.add_index(&["int"], false, false).unwrap();
-        col.add_index(&["float"], false, false).unwrap();
-        col.add_index(&["long"], false, false).unwrap();
-        col.add_index(&["double"], false, false).unwrap();
-        col.add_index(&["str"], false, false).unwrap();
-        assert!(col.add_index(&["byteList"], false, false).is_err());
-        assert!(col.add_index(&["intList"], false, false).is_err());
+        col.add_index(&["byte"], false, false, false, true).unwrap();
+        col.add_index(&["int"], false, false, false, true).unwrap();
+        col.add_index(&["float"], false, false, false, true)
+            .unwrap();
+        col.add_index(&["long"], false, false, false, true).unwrap();
+        col.add_index(&["double"], false, false, false, true)
+            .unwrap();
+        col.add_index(&["str"], false, false, false, true).unwrap();
+        assert!(matches!(
+            col.add_index(&["byteList"], false, false, false, true),
+            Err(IsarError::SchemaInvalidIndex { .. })
+        ));
+        assert!(matches!(
+            col.add_index(&["intList"], false, false, false, true),
+            Err(IsarError::SchemaInvalidIndex { .. })
+        ));
+    }
+
+    #[test]
+    fn test_add_multi_entry_index() {
+        let mut col = CollectionSchema::new("col");
+        col.add_property("int", DataType::Int).unwrap();
+        col.add_property("str", DataType::String).unwrap();
+        col.add_property("byteList", DataType::ByteList).unwrap();
+        col.add_property("intList", DataType::IntList).unwrap();
+        col.add_property("strList", DataType::StringList).unwrap();
+        col.add_property("bytesList", DataType::BytesList).unwrap();
+
+        assert!(matches!(
+            col.add_index(&["int"], false, false, true, true),
+            Err(IsarError::SchemaInvalidIndex { .. })
+        ));
+        assert!(matches!(
+            col.add_index(&["bytesList"], false, false, true, true),
+            Err(IsarError::SchemaInvalidIndex { .. })
+        ));
+        assert!(matches!(
+            col.add_index(&["str"], false, false, true, true),
+            Err(IsarError::SchemaInvalidIndex { .. })
+        ));
+        assert!(matches!(
+            col.add_index(&["intList"], false, true, true, true),
+            Err(IsarError::SchemaInvalidIndex { .. })
+        ));
+        assert!(matches!(
+            col.add_index(&["byteList", "intList"], false, false, true, true),
+            Err(IsarError::SchemaInvalidIndex { .. })
+        ));
+        col.add_index(&["byteList"], false, false, true, true)
+            .unwrap();
+        col.add_index(&["intList"], true, false, true, true)
+            .unwrap();
+        col.add_index(&["strList"], false, true, true, true)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_add_case_insensitive_index() {
+        let mut col = CollectionSchema::new("col");
+        col.add_property("int", DataType::Int).unwrap();
+        col.add_property("str", DataType::String).unwrap();
+
+        assert!(matches!(
+            col.add_index(&["int"], false, false, false, false),
+            Err(IsarError::SchemaInvalidIndex { .. })
+        ));
+        col.add_index(&["str"], false, false, false, false).unwrap();
+        col.add_index(&["int", "str"], false, true, false, false)
+            .unwrap();
     }
 
     #[test]
@@ -283,9 +509,16 @@ mod tests {
         col.add_property("prop3", DataType::Int).unwrap();
         col.add_property("prop4", DataType::Int).unwrap();
 
-        assert!(col
-            .add_index(&["prop1", "prop2", "prop3", "prop4"], false, false)
-            .is_err())
+        assert!(matches!(
+            col.add_index(
+                &["prop1", "prop2", "prop3", "prop4"],
+                false,
+                false,
+                false,
+                true
+            ),
+            Err(IsarError::SchemaInvalidIndex { .. })
+        ))
     }
 
     #[test]
@@ -294,10 +527,18 @@ mod tests {
         col.add_property("prop1", DataType::Int).unwrap();
         col.add_property("prop2", DataType::Int).unwrap();
 
-        col.add_index(&["prop2"], false, false).unwrap();
-        col.add_index(&["prop1", "prop2"], false, false).unwrap();
-        assert!(col.add_index(&["prop1", "prop2"], false, false).is_err());
-        assert!(col.add_index(&["prop1"], false, false).is_err());
+        col.add_index(&["prop2"], false, false, false, true)
+            .unwrap();
+        col.add_index(&["prop1", "prop2"], false, false, false, true)
+            .unwrap();
+        assert!(matches!(
+            col.add_index(&["prop1", "prop2"], false, false, false, true),
+            Err(IsarError::SchemaInvalidIndex { .. })
+        ));
+        assert!(matches!(
+            col.add_index(&["prop1"], false, false, false, true),
+            Err(IsarError::SchemaInvalidIndex { .. })
+        ));
     }
 
     #[test]
@@ -306,9 +547,14 @@ mod tests {
         col.add_property("int", DataType::Int).unwrap();
         col.add_property("str", DataType::String).unwrap();
 
-        col.add_index(&["int", "str"], false, false).unwrap();
-        assert!(col.add_index(&["str", "int"], false, false).is_err());
-        col.add_index(&["str", "int"], false, true).unwrap();
+        col.add_index(&["int", "str"], false, false, false, true)
+            .unwrap();
+        assert!(matches!(
+            col.add_index(&["str", "int"], false, false, false, true),
+            Err(IsarError::SchemaInvalidIndex { .. })
+        ));
+        col.add_index(&["str", "int"], false, true, false, true)
+            .unwrap();
     }
 
     #[test]
@@ -316,7 +562,9 @@ mod tests {
         fn get_offsets(mut schema: CollectionSchema) -> Vec<usize> {
             let mut get_id = || 1;
             schema.update_with_existing_collections(&[], &mut get_id);
-            let col = schema.get_isar_collection(DataDbs::debug_new());
+            let col = schema
+                .get_isar_collection(DataDbs::debug_new(), &[])
+                .unwrap();
             let mut offsets = vec![];
             for i in 0..schema.properties.len() {
                 offsets.push(col.get_properties().get(i).unwrap().offset);
@@ -350,8 +598,8 @@ mod tests {
         let mut col = CollectionSchema::new("col");
         col.add_property("byte", DataType::Byte).unwrap();
         col.add_property("int", DataType::Int).unwrap();
-        col.add_index(&["byte"], true, false).unwrap();
-        col.add_index(&["int"], true, false).unwrap();
+        col.add_index(&["byte"], true, false, false, true).unwrap();
+        col.add_index(&["int"], true, false, false, true).unwrap();
 
         let mut counter = 0;
         let mut get_id = || {
@@ -376,8 +624,8 @@ mod tests {
         let mut col1 = CollectionSchema::new("col");
         col1.add_property("byte", DataType::Byte).unwrap();
         col1.add_property("int", DataType::Int).unwrap();
-        col1.add_index(&["byte"], true, false).unwrap();
-        col1.add_index(&["int"], true, false).unwrap();
+        col1.add_index(&["byte"], true, false, false, true).unwrap();
+        col1.add_index(&["int"], true, false, false, true).unwrap();
 
         col1.update_with_existing_collections(&[], &mut get_id);
         assert_eq!(col1.id, Some(1));
@@ -387,8 +635,9 @@ mod tests {
         let mut col2 = CollectionSchema::new("col");
         col2.add_property("byte", DataType::Byte).unwrap();
         col2.add_property("int", DataType::Int).unwrap();
-        col2.add_index(&["byte"], true, false).unwrap();
-        col2.add_index(&["int", "byte"], true, false).unwrap();
+        col2.add_index(&["byte"], true, false, false, true).unwrap();
+        col2.add_index(&["int", "byte"], true, false, false, true)
+            .unwrap();
 
         col2.update_with_existing_collections(&[col1], &mut get_id);
         assert_eq!(col2.id, Some(1));
@@ -399,4 +648,47 @@ mod tests {
         col3.update_with_existing_collections(&[col2], &mut get_id);
         assert_eq!(col3.id, Some(5));
     }
+
+    #[test]
+    fn test_add_link() {
+        let mut col = CollectionSchema::new("col");
+        assert!(col.add_link("", "other").is_err());
+        col.add_link("link", "other").unwrap();
+        col.add_link("link2", "other").unwrap();
+        assert!(col.add_link("link", "col").is_err());
+    }
+
+    #[test]
+    fn test_rename_property() {
+        let mut col = CollectionSchema::new("col");
+        col.add_property("int", DataType::Int).unwrap();
+        col.add_property("long", DataType::Long).unwrap();
+
+        assert!(col.rename_property("oldInt", "missing").is_err());
+        assert!(col.rename_property("long", "int").is_err());
+        col.rename_property("oldInt", "int").unwrap();
+        assert!(col.rename_property("oldInt", "long").is_err());
+        assert!(col.rename_property("otherInt", "int").is_err());
+    }
+
+    #[test]
+    fn update_with_renamed_properties() {
+        let mut get_id = || 1;
+
+        let mut col1 = CollectionSchema::new("col");
+        col1.add_property("a", DataType::Int).unwrap();
+        col1.add_property("b", DataType::Int).unwrap();
+        col1.update_with_existing_collections(&[], &mut get_id);
+
+        let mut col2 = CollectionSchema::new("col");
+        col2.add_property("c", DataType::Int).unwrap();
+        col2.add_property("d", DataType::Long).unwrap();
+        col2.rename_property("a", "c").unwrap();
+        col2.rename_property("b", "d").unwrap(); // changed type
+        col2.update_with_existing_collections(&[col1], &mut get_id);
+        assert_eq!(
+            col2.renamed_properties,
+            vec![("a".to_string(), "c".to_string())]
+        );
+    }
 }
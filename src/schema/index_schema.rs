@@ -9,18 +9,43 @@ pub struct IndexSchema {
     pub(crate) unique: bool,
     #[serde(rename = "hashValue")]
     pub(crate) hash_value: bool,
+    #[serde(rename = "multiEntry", default)]
+    pub(crate) multi_entry: bool,
+    #[serde(rename = "caseSensitive", default = "default_case_sensitive")]
+    pub(crate) case_sensitive: bool,
+}
+
+fn default_case_sensitive() -> bool {
+    true
 }
 
 impl IndexSchema {
-    pub fn new(properties: Vec<PropertySchema>, unique: bool, hash_value: bool) -> IndexSchema {
+    pub fn new(
+        properties: Vec<PropertySchema>,
+        unique: bool,
+        hash_value: bool,
+        multi_entry: bool,
+        case_sensitive: bool,
+    ) -> IndexSchema {
         IndexSchema {
             id: None,
             properties,
             unique,
             hash_value,
+            multi_entry,
+            case_sensitive,
         }
     }
 
+    /// Whether both schemas describe the same index, ignoring their ids.
+    pub(crate) fn matches(&self, other: &IndexSchema) -> bool {
+        self.properties == other.properties
+            && self.unique == other.unique
+            && self.hash_value == other.hash_value
+            && self.multi_entry == other.multi_entry
+            && self.case_sensitive == other.case_sensitive
+    }
+
     pub(crate) fn update_with_existing_indexes<F>(
         &mut self,
         existing_indexes: &[IndexSchema],
@@ -28,11 +53,7 @@ impl IndexSchema {
     ) where
         F: FnMut() -> u16,
     {
-        let existing_index = existing_indexes.iter().find(|i| {
-            i.properties == self.properties
-                && i.unique == self.unique
-                && i.hash_value == self.hash_value
-        });
+        let existing_index = existing_indexes.iter().find(|i| i.matches(self));
         if let Some(existing_index) = existing_index {
             self.id = existing_index.id;
         } else {
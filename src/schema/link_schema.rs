@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(PartialEq, Serialize, Deserialize, Clone, Debug)]
+pub struct LinkSchema {
+    pub(crate) id: Option<u16>,
+    pub(crate) name: String,
+    #[serde(rename = "targetCollection")]
+    pub(crate) target_collection: String,
+}
+
+impl LinkSchema {
+    pub fn new(name: &str, target_collection: &str) -> LinkSchema {
+        LinkSchema {
+            id: None,
+            name: name.to_string(),
+            target_collection: target_collection.to_string(),
+        }
+    }
+
+    /// Whether both schemas describe the same link, ignoring their ids.
+    pub(crate) fn matches(&self, other: &LinkSchema) -> bool {
+        self.name == other.name && self.target_collection == other.target_collection
+    }
+
+    pub(crate) fn update_with_existing_links<F>(
+        &mut self,
+        existing_links: &[LinkSchema],
+        get_id: &mut F,
+    ) where
+        F: FnMut() -> u16,
+    {
+        let existing_link = existing_links.iter().find(|l| l.matches(self));
+        if let Some(existing_link) = existing_link {
+            self.id = existing_link.id;
+        } else {
+            self.id = Some(get_id());
+        }
+    }
+}
@@ -1,13 +1,16 @@
 mod collection_migrator;
 pub mod collection_schema;
 pub mod index_schema;
+pub mod link_schema;
 pub mod property_schema;
+pub mod schema_diff;
 pub(super) mod schema_manager;
 
 use crate::collection::IsarCollection;
 use crate::data_dbs::DataDbs;
 use crate::error::{illegal_arg, Result};
 use crate::schema::collection_schema::CollectionSchema;
+use crate::schema::schema_diff::SchemaDiff;
 use hashbrown::HashSet;
 use rand::random;
 use serde::{Deserialize, Serialize};
@@ -32,10 +35,15 @@ impl Schema {
         Ok(())
     }
 
-    pub(crate) fn build_collections(self, dbs: DataDbs) -> Vec<IsarCollection> {
+    /// Compares this schema with the `existing` schema without performing a migration.
+    pub fn diff(&self, existing: &Schema) -> SchemaDiff {
+        SchemaDiff::create(&self.collections, &existing.collections)
+    }
+
+    pub(crate) fn build_collections(&self, dbs: DataDbs) -> Result<Vec<IsarCollection>> {
         self.collections
             .iter()
-            .map(|c| c.get_isar_collection(dbs))
+            .map(|c| c.get_isar_collection(dbs, &self.collections))
             .collect()
     }
 
@@ -56,6 +64,14 @@ impl Schema {
                     );
                 }
             }
+            for link in &collection.links {
+                if let Some(id) = link.id {
+                    assert!(
+                        ids.insert(id),
+                        "Something is wrong, schema contains duplicate id."
+                    );
+                }
+            }
         }
         ids
     }
@@ -94,6 +110,8 @@ impl Schema {
 mod tests {
     use super::*;
     use crate::object::data_type::DataType;
+    use crate::schema::index_schema::IndexSchema;
+    use crate::schema::property_schema::PropertySchema;
 
     #[test]
     fn test_add_collection() {
@@ -117,11 +135,11 @@ mod tests {
         col.add_property("intProperty", DataType::Int)?;
         col.add_property("longProperty", DataType::Long)?;
         col.add_property("stringProperty", DataType::String)?;
-        col.add_index(&["byteProperty"], false, false)?;
-        col.add_index(&["intProperty", "byteProperty"], true, false)?;
-        col.add_index(&["longProperty"], false, false)?;
-        col.add_index(&["intProperty", "longProperty"], false, false)?;
-        col.add_index(&["stringProperty"], false, true)?;
+        col.add_index(&["byteProperty"], false, false, false, true)?;
+        col.add_index(&["intProperty", "byteProperty"], true, false, false, true)?;
+        col.add_index(&["longProperty"], false, false, false, true)?;
+        col.add_index(&["intProperty", "longProperty"], false, false, false, true)?;
+        col.add_index(&["stringProperty"], false, true, false, true)?;
         schema1.add_collection(col)?;
 
         let mut counter = 0;
@@ -144,11 +162,11 @@ mod tests {
         col.add_property("intProperty", DataType::Int)?;
         col.add_property("longProperty", DataType::Double)?; // changed type
         col.add_property("stringProperty", DataType::String)?;
-        col.add_index(&["byteProperty"], false, false)?;
-        col.add_index(&["intProperty", "byteProperty"], false, false)?; // changed unique
-        col.add_index(&["longProperty"], false, false)?; // changed property type
-        col.add_index(&["intProperty", "longProperty"], false, false)?; // changed property type-
-        col.add_index(&["stringProperty"], false, false)?; // changed hash_value
+        col.add_index(&["byteProperty"], false, false, false, true)?;
+        col.add_index(&["intProperty", "byteProperty"], false, false, false, true)?; // changed unique
+        col.add_index(&["longProperty"], false, false, false, true)?; // changed property type
+        col.add_index(&["intProperty", "longProperty"], false, false, false, true)?; // changed property type-
+        col.add_index(&["stringProperty"], false, false, false, true)?; // changed hash_value
         schema2.add_collection(col)?;
 
         let mut counter = 0;
@@ -167,4 +185,198 @@ mod tests {
 
         Ok(())
     }
+
+    fn diff_schema(col: CollectionSchema) -> Schema {
+        let mut schema = Schema::new();
+        schema.add_collection(col).unwrap();
+        schema
+    }
+
+    fn existing_col() -> Result<CollectionSchema> {
+        let mut col = CollectionSchema::new("col");
+        col.add_property("intProperty", DataType::Int)?;
+        col.add_property("longProperty", DataType::Long)?;
+        col.add_index(&["intProperty"], false, false, false, true)?;
+        Ok(col)
+    }
+
+    #[test]
+    fn test_diff_unchanged() -> Result<()> {
+        let existing = diff_schema(existing_col()?);
+        let diff = diff_schema(existing_col()?).diff(&existing);
+        assert_eq!(diff, SchemaDiff::default());
+        assert!(!diff.requires_migration());
+        assert!(!diff.is_destructive());
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_collections() -> Result<()> {
+        let existing = diff_schema(existing_col()?);
+        let mut schema = Schema::new();
+        schema.add_collection(CollectionSchema::new("other"))?;
+
+        let diff = schema.diff(&existing);
+        assert_eq!(diff.added_collections, vec!["other".to_string()]);
+        assert_eq!(diff.removed_collections, vec!["col".to_string()]);
+        assert!(diff.changed_collections.is_empty());
+        assert!(diff.requires_migration());
+        assert!(diff.is_destructive());
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_added_property() -> Result<()> {
+        let existing = diff_schema(existing_col()?);
+        let mut col = existing_col()?;
+        col.add_property("stringProperty", DataType::String)?;
+
+        let diff = diff_schema(col).diff(&existing);
+        let col_diff = &diff.changed_collections[0];
+        assert_eq!(
+            col_diff.added_properties,
+            vec![PropertySchema::new("stringProperty", DataType::String)]
+        );
+        assert!(col_diff.removed_properties.is_empty());
+        assert!(col_diff.changed_properties.is_empty());
+        assert!(diff.requires_migration());
+        assert!(!diff.is_destructive());
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_removed_property() -> Result<()> {
+        let existing = diff_schema(existing_col()?);
+        let mut col = CollectionSchema::new("col");
+        col.add_property("intProperty", DataType::Int)?;
+        col.add_index(&["intProperty"], false, false, false, true)?;
+
+        let diff = diff_schema(col).diff(&existing);
+        assert_eq!(
+            diff.changed_collections[0].removed_properties,
+            vec![PropertySchema::new("longProperty", DataType::Long)]
+        );
+        assert!(diff.is_destructive());
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_changed_property() -> Result<()> {
+        let existing = diff_schema(existing_col()?);
+        let mut col = CollectionSchema::new("col");
+        col.add_property("intProperty", DataType::Int)?;
+        col.add_property("longProperty", DataType::Double)?;
+        col.add_index(&["intProperty"], false, false, false, true)?;
+
+        let diff = diff_schema(col).diff(&existing);
+        let col_diff = &diff.changed_collections[0];
+        assert_eq!(
+            col_diff.changed_properties,
+            vec![(
+                PropertySchema::new("longProperty", DataType::Long),
+                PropertySchema::new("longProperty", DataType::Double)
+            )]
+        );
+        assert!(col_diff.added_properties.is_empty());
+        assert!(col_diff.removed_properties.is_empty());
+        assert!(diff.is_destructive());
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_converted_property() -> Result<()> {
+        let existing = diff_schema(existing_col()?);
+        let mut col = CollectionSchema::new("col");
+        col.add_property("intProperty", DataType::Long)?;
+        col.add_property("longProperty", DataType::Long)?;
+        col.add_index(&["intProperty"], false, false, false, true)?;
+
+        let diff = diff_schema(col).diff(&existing);
+        assert_eq!(
+            diff.changed_collections[0].changed_properties,
+            vec![(
+                PropertySchema::new("intProperty", DataType::Int),
+                PropertySchema::new("intProperty", DataType::Long)
+            )]
+        );
+        assert!(diff.requires_migration());
+        assert!(!diff.is_destructive());
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_renamed_property() -> Result<()> {
+        let existing = diff_schema(existing_col()?);
+        let mut col = CollectionSchema::new("col");
+        col.add_property("intProperty", DataType::Int)?;
+        col.add_property("newLongProperty", DataType::Long)?;
+        col.add_index(&["intProperty"], false, false, false, true)?;
+        col.rename_property("longProperty", "newLongProperty")?;
+
+        let diff = diff_schema(col).diff(&existing);
+        assert!(!diff.requires_migration());
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_indexes() -> Result<()> {
+        let existing = diff_schema(existing_col()?);
+        let mut col = CollectionSchema::new("col");
+        col.add_property("intProperty", DataType::Int)?;
+        col.add_property("longProperty", DataType::Long)?;
+        col.add_index(&["intProperty"], true, false, false, true)?; // changed unique
+        col.add_index(&["longProperty"], false, false, false, true)?;
+
+        let diff = diff_schema(col).diff(&existing);
+        let col_diff = &diff.changed_collections[0];
+        let int_property = PropertySchema::new("intProperty", DataType::Int);
+        let long_property = PropertySchema::new("longProperty", DataType::Long);
+        assert_eq!(
+            col_diff.added_indexes,
+            vec![
+                IndexSchema::new(vec![int_property.clone()], true, false, false, true),
+                IndexSchema::new(vec![long_property], false, false, false, true),
+            ]
+        );
+        assert_eq!(
+            col_diff.removed_indexes,
+            vec![IndexSchema::new(
+                vec![int_property],
+                false,
+                false,
+                false,
+                true
+            )]
+        );
+        assert!(diff.requires_migration());
+        assert!(!diff.is_destructive());
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_links() -> Result<()> {
+        let mut col = existing_col()?;
+        col.add_link("link", "col")?;
+        let existing = diff_schema(col);
+
+        let mut col = existing_col()?;
+        col.add_link("otherLink", "col")?;
+        let diff = diff_schema(col).diff(&existing);
+        let col_diff = &diff.changed_collections[0];
+        assert_eq!(col_diff.added_links.len(), 1);
+        assert_eq!(col_diff.added_links[0].name, "otherLink");
+        assert_eq!(col_diff.removed_links.len(), 1);
+        assert_eq!(col_diff.removed_links[0].name, "link");
+        assert!(diff.requires_migration());
+        assert!(diff.is_destructive());
+
+        let mut col = existing_col()?;
+        col.add_link("link", "col")?;
+        col.add_link("otherLink", "col")?;
+        let diff = diff_schema(col).diff(&existing);
+        assert!(diff.changed_collections[0].removed_links.is_empty());
+        assert!(diff.requires_migration());
+        assert!(!diff.is_destructive());
+        Ok(())
+    }
 }
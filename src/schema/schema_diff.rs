@@ -0,0 +1,137 @@
+use crate::schema::collection_migrator::CollectionMigrator;
+use crate::schema::collection_schema::CollectionSchema;
+use crate::schema::index_schema::IndexSchema;
+use crate::schema::link_schema::LinkSchema;
+use crate::schema::property_schema::PropertySchema;
+
+/// The changes between an existing schema and a new one. Opening an instance with the new
+/// schema performs a migration if [`SchemaDiff::requires_migration`] returns `true`.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct SchemaDiff {
+    pub added_collections: Vec<String>,
+    pub removed_collections: Vec<String>,
+    pub changed_collections: Vec<CollectionDiff>,
+}
+
+/// The changes of a collection that exists in both schemas.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct CollectionDiff {
+    pub name: String,
+    pub added_properties: Vec<PropertySchema>,
+    pub removed_properties: Vec<PropertySchema>,
+    /// Pairs of existing and new property whose data type changed. The data of these
+    /// properties is lost during migration unless it can be converted without loss (e.g. `Int`
+    /// to `Long`).
+    pub changed_properties: Vec<(PropertySchema, PropertySchema)>,
+    pub added_indexes: Vec<IndexSchema>,
+    pub removed_indexes: Vec<IndexSchema>,
+    pub added_links: Vec<LinkSchema>,
+    /// The links of these schemas are deleted during migration.
+    pub removed_links: Vec<LinkSchema>,
+}
+
+impl SchemaDiff {
+    pub(super) fn create(collections: &[CollectionSchema], existing: &[CollectionSchema]) -> Self {
+        let mut diff = SchemaDiff::default();
+        for collection in collections {
+            if let Some(existing) = existing.iter().find(|c| c.name == collection.name) {
+                let collection_diff = CollectionDiff::create(collection, existing);
+                if collection_diff.has_changes() {
+                    diff.changed_collections.push(collection_diff);
+                }
+            } else {
+                diff.added_collections.push(collection.name.clone());
+            }
+        }
+        for existing_collection in existing {
+            if !collections
+                .iter()
+                .any(|c| c.name == existing_collection.name)
+            {
+                diff.removed_collections
+                    .push(existing_collection.name.clone());
+            }
+        }
+        diff
+    }
+
+    pub fn requires_migration(&self) -> bool {
+        !self.added_collections.is_empty()
+            || !self.removed_collections.is_empty()
+            || !self.changed_collections.is_empty()
+    }
+
+    /// Whether the migration would delete existing data.
+    pub fn is_destructive(&self) -> bool {
+        !self.removed_collections.is_empty()
+            || self.changed_collections.iter().any(|c| c.is_destructive())
+    }
+}
+
+impl CollectionDiff {
+    fn create(collection: &CollectionSchema, existing: &CollectionSchema) -> Self {
+        let mut diff = CollectionDiff {
+            name: collection.name.clone(),
+            ..Default::default()
+        };
+
+        let mut retained_names = vec![];
+        for property in &collection.properties {
+            let existing_property = CollectionMigrator::find_existing_property(
+                &property.name,
+                &collection.renamed_properties,
+                &existing.properties,
+                |p| &p.name,
+            );
+            if let Some(existing_property) = existing_property {
+                if existing_property.data_type != property.data_type {
+                    diff.changed_properties
+                        .push((existing_property.clone(), property.clone()));
+                }
+                retained_names.push(&existing_property.name);
+            } else {
+                diff.added_properties.push(property.clone());
+            }
+        }
+        for existing_property in &existing.properties {
+            if !retained_names.contains(&&existing_property.name) {
+                diff.removed_properties.push(existing_property.clone());
+            }
+        }
+
+        let (added_indexes, removed_indexes) =
+            CollectionMigrator::compare(&collection.indexes, &existing.indexes, |i1, i2| {
+                i1.matches(i2)
+            });
+        diff.added_indexes = added_indexes.into_iter().cloned().collect();
+        diff.removed_indexes = removed_indexes.into_iter().cloned().collect();
+
+        let (added_links, removed_links) =
+            CollectionMigrator::compare(&collection.links, &existing.links, |l1, l2| {
+                l1.matches(l2)
+            });
+        diff.added_links = added_links.into_iter().cloned().collect();
+        diff.removed_links = removed_links.into_iter().cloned().collect();
+
+        diff
+    }
+
+    fn has_changes(&self) -> bool {
+        !self.added_properties.is_empty()
+            || !self.removed_properties.is_empty()
+            || !self.changed_properties.is_empty()
+            || !self.added_indexes.is_empty()
+            || !self.removed_indexes.is_empty()
+            || !self.added_links.is_empty()
+            || !self.removed_links.is_empty()
+    }
+
+    /// Whether the migration of this collection would delete existing property or link data.
+    pub fn is_destructive(&self) -> bool {
+        !self.removed_properties.is_empty()
+            || !self.removed_links.is_empty()
+            || self.changed_properties.iter().any(|(existing, new)| {
+                !CollectionMigrator::is_convertible(existing.data_type, new.data_type)
+            })
+    }
+}
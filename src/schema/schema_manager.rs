@@ -5,6 +5,7 @@ use crate::lmdb::env::Env;
 use crate::lmdb::txn::Txn;
 use crate::schema::collection_migrator::CollectionMigrator;
 use crate::schema::Schema;
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use serde_json::{Deserializer, Serializer};
 use std::convert::TryInto;
@@ -16,22 +17,27 @@ const INFO_SCHEMA_KEY: &[u8] = b"schema";
 pub struct SchemaManger<'env> {
     env: &'env Env,
     dbs: DataDbs,
+    read_only: bool,
 }
 
 impl<'env> SchemaManger<'env> {
-    pub fn new(env: &'env Env, dbs: DataDbs) -> Self {
-        SchemaManger { env, dbs }
+    pub fn new(env: &'env Env, dbs: DataDbs, read_only: bool) -> Self {
+        SchemaManger {
+            env,
+            dbs,
+            read_only,
+        }
     }
 
     pub fn check_isar_version(&self) -> Result<()> {
-        let txn = self.env.txn(true)?;
+        let txn = self.env.txn(!self.read_only)?;
         let version = self.dbs.info.get(&txn, INFO_VERSION_KEY)?;
         if let Some(version) = version {
             let version_num = u64::from_le_bytes(version.try_into().unwrap());
             if version_num != ISAR_VERSION {
                 return Err(IsarError::VersionError {});
             }
-        } else {
+        } else if !self.read_only {
             let version_bytes = &ISAR_VERSION.to_le_bytes();
             self.dbs.info.put(&txn, INFO_VERSION_KEY, version_bytes)?;
         }
@@ -39,34 +45,88 @@ impl<'env> SchemaManger<'env> {
         Ok(())
     }
 
-    pub fn get_collections(&self, mut schema: Schema) -> Result<Vec<IsarCollection>> {
-        let txn = self.env.txn(true)?;
-        let existing_schema_bytes = self.dbs.info.get(&txn, INFO_SCHEMA_KEY)?;
-
-        let existing_collections = if let Some(existing_schema_bytes) = existing_schema_bytes {
+    fn get_existing_schema(&self, txn: &Txn) -> Result<Option<Schema>> {
+        let existing_schema_bytes = self.dbs.info.get(txn, INFO_SCHEMA_KEY)?;
+        if let Some(existing_schema_bytes) = existing_schema_bytes {
             let mut deser = Deserializer::from_slice(existing_schema_bytes);
             let existing_schema =
                 Schema::deserialize(&mut deser).map_err(|e| IsarError::DbCorrupted {
                     source: Some(Box::new(e)),
                     message: "Could not deserialize existing schema.".to_string(),
                 })?;
-            schema.update_with_existing_schema(Some(&existing_schema));
-            existing_schema.build_collections(self.dbs)
+            Ok(Some(existing_schema))
         } else {
-            schema.update_with_existing_schema(None);
-            vec![]
+            Ok(None)
+        }
+    }
+
+    pub fn get_collections(&self, mut schema: Schema) -> Result<Vec<IsarCollection>> {
+        if self.read_only {
+            return self.get_collections_read_only(schema);
+        }
+
+        let txn = self.env.txn(true)?;
+        let existing_schema = self.get_existing_schema(&txn)?;
+        schema.update_with_existing_schema(existing_schema.as_ref());
+        let (existing_collections, unchanged) = if let Some(existing_schema) = existing_schema {
+            let unchanged = schema
+                .collections
+                .iter()
+                .map(|c| {
+                    let existing = existing_schema
+                        .collections
+                        .iter()
+                        .find(|e| e.name == c.name);
+                    if let Some(existing) = existing {
+                        c.is_unchanged(existing)
+                    } else {
+                        Ok(false)
+                    }
+                })
+                .collect::<Result<Vec<_>>>()?;
+            (existing_schema.build_collections(self.dbs)?, unchanged)
+        } else {
+            (vec![], vec![false; schema.collections.len()])
         };
 
+        let collections = schema.build_collections(self.dbs)?;
         self.save_schema(&txn, &schema)?;
-        let collections = schema.build_collections(self.dbs);
-        self.perform_migration(&txn, &collections, &existing_collections)?;
+        let renamed_properties = schema
+            .collections
+            .iter()
+            .map(|c| c.renamed_properties.clone())
+            .collect_vec();
+        self.perform_migration(
+            &txn,
+            &collections,
+            &existing_collections,
+            &renamed_properties,
+            &unchanged,
+        )?;
 
         txn.commit()?;
 
         Ok(collections)
     }
 
-    fn save_schema(&self, txn: &Txn, schema: &Schema) -> Result<()> {
+    fn get_collections_read_only(&self, mut schema: Schema) -> Result<Vec<IsarCollection>> {
+        let txn = self.env.txn(false)?;
+        let existing_schema = self
+            .get_existing_schema(&txn)?
+            .ok_or(IsarError::DbNotFound {})?;
+        txn.abort();
+
+        schema.update_with_existing_schema(Some(&existing_schema));
+        if Self::serialize_schema(&schema)? != Self::serialize_schema(&existing_schema)? {
+            return Err(IsarError::MigrationError {
+                source: None,
+                message: "The schema differs from the existing schema and read-only instances cannot be migrated.".to_string(),
+            });
+        }
+        schema.build_collections(self.dbs)
+    }
+
+    fn serialize_schema(schema: &Schema) -> Result<Vec<u8>> {
         let mut bytes = vec![];
         let mut ser = Serializer::new(&mut bytes);
         schema
@@ -75,6 +135,11 @@ impl<'env> SchemaManger<'env> {
                 source: Some(Box::new(e)),
                 message: "Could not serialize schema.".to_string(),
             })?;
+        Ok(bytes)
+    }
+
+    fn save_schema(&self, txn: &Txn, schema: &Schema) -> Result<()> {
+        let bytes = Self::serialize_schema(schema)?;
         self.dbs.info.put(txn, INFO_SCHEMA_KEY, &bytes)?;
         Ok(())
     }
@@ -84,6 +149,8 @@ impl<'env> SchemaManger<'env> {
         txn: &Txn,
         collections: &[IsarCollection],
         existing_collections: &[IsarCollection],
+        renamed_properties: &[Vec<(String, String)>],
+        unchanged: &[bool],
     ) -> Result<()> {
         let removed_collections = existing_collections
             .iter()
@@ -93,13 +160,19 @@ impl<'env> SchemaManger<'env> {
             col.delete_all_internal(txn)?;
         }
 
-        for col in collections {
+        for ((col, renamed_properties), unchanged) in
+            collections.iter().zip(renamed_properties).zip(unchanged)
+        {
+            if *unchanged {
+                continue;
+            }
+
             let existing = existing_collections
                 .iter()
                 .find(|existing| existing.get_id() == col.get_id());
 
             if let Some(existing) = existing {
-                let migrator = CollectionMigrator::create(col, existing);
+                let migrator = CollectionMigrator::create(col, existing, renamed_properties);
                 migrator.migrate(txn, self.dbs.primary)?;
             }
         }
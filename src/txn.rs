@@ -1,43 +1,222 @@
-use crate::error::{IsarError, Result};
+use crate::error::{illegal_arg, IsarError, Result};
 use crate::lmdb::txn::Txn;
+use std::cell::Cell;
 
 pub struct IsarTxn<'env> {
-    txn: Txn<'env>,
+    txn: Option<Txn<'env>>,
     write: bool,
+    nested_txns: bool,
+    failed: Cell<bool>,
 }
 
 impl<'env> IsarTxn<'env> {
-    pub(crate) fn new(txn: Txn<'env>, write: bool) -> Self {
-        IsarTxn { txn, write }
+    /// `nested_txns` is `false` if the environment does not support nested transactions.
+    pub(crate) fn new(txn: Txn<'env>, write: bool, nested_txns: bool) -> Self {
+        IsarTxn {
+            txn: Some(txn),
+            write,
+            nested_txns,
+            failed: Cell::new(false),
+        }
     }
 
+    /// Runs `job` in a nested write transaction. Fails with `WriteTxnRequired` before `job`
+    /// is called if this transaction is read-only.
+    ///
+    /// Without nested transaction support `job` runs in this transaction. If it fails, its
+    /// changes cannot be rolled back and this transaction can only be aborted.
     pub(crate) fn exec_atomic_write<T, F>(&self, job: F) -> Result<T>
     where
         F: FnOnce(&Txn) -> Result<T>,
     {
+        if !self.nested_txns {
+            let result = job(self.get_write_txn()?);
+            if result.is_err() {
+                self.failed.set(true);
+            }
+            return result;
+        }
         let nested_txn = self.get_write_txn()?.nested_txn(true)?;
         let result = job(&nested_txn)?;
         nested_txn.commit()?;
         Ok(result)
     }
 
-    pub(crate) fn get_txn(&self) -> &Txn {
-        &self.txn
+    /// Runs `job` in a child transaction. Its changes are committed to this transaction if
+    /// `job` succeeds and rolled back otherwise without affecting earlier changes. This
+    /// transaction must not be used until `job` returns.
+    pub fn savepoint<T, F>(&self, job: F) -> Result<T>
+    where
+        F: FnOnce(&IsarTxn) -> Result<T>,
+    {
+        if !self.nested_txns {
+            return illegal_arg("Savepoints are not supported with write_map.");
+        }
+        let nested_txn = self.get_write_txn()?.nested_txn(true)?;
+        let mut savepoint = IsarTxn::new(nested_txn, true, true);
+        match job(&savepoint) {
+            Ok(result) => {
+                savepoint.commit()?;
+                Ok(result)
+            }
+            Err(e) => {
+                savepoint.abort();
+                Err(e)
+            }
+        }
+    }
+
+    /// Returns the LMDB transaction or `TransactionClosed` after `commit` or `abort`.
+    pub(crate) fn get_txn(&self) -> Result<&Txn> {
+        if self.failed.get() {
+            return Err(Self::failed_error());
+        }
+        self.txn.as_ref().ok_or(IsarError::TransactionClosed {})
+    }
+
+    fn failed_error() -> IsarError {
+        IsarError::IllegalState {
+            message: "A write failed and could not be rolled back. The transaction has to be \
+                aborted."
+                .to_string(),
+        }
     }
 
     pub(crate) fn get_write_txn(&self) -> Result<&Txn> {
         if self.write {
-            Ok(&self.txn)
+            self.get_txn()
         } else {
             Err(IsarError::WriteTxnRequired {})
         }
     }
 
-    pub fn commit(self) -> Result<()> {
-        self.txn.commit()
+    pub fn is_active(&self) -> bool {
+        self.txn.is_some()
+    }
+
+    pub fn commit(&mut self) -> Result<()> {
+        if self.failed.get() {
+            self.abort();
+            return Err(Self::failed_error());
+        }
+        if let Some(txn) = self.txn.take() {
+            txn.commit()
+        } else {
+            Err(IsarError::TransactionClosed {})
+        }
+    }
+
+    pub fn abort(&mut self) {
+        if let Some(txn) = self.txn.take() {
+            txn.abort();
+        }
+    }
+}
+
+/// Transactions that are neither committed nor aborted are aborted when they are dropped.
+/// Uncommitted changes are rolled back and the write lock is released.
+impl<'env> Drop for IsarTxn<'env> {
+    fn drop(&mut self) {
+        self.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IsarTxn;
+    use crate::collection::IsarCollection;
+    use crate::error::{IsarError, Result};
+    use crate::object::object_id::ObjectId;
+    use crate::{col, isar};
+
+    fn put_int(col: &IsarCollection, txn: &IsarTxn, value: i32) -> Result<ObjectId> {
+        let mut builder = col.get_object_builder();
+        builder.write_int(value);
+        col.put(txn, None, builder.finish().as_bytes())
+    }
+
+    #[test]
+    fn test_savepoint() {
+        isar!(isar, col => col!(f1 => Int));
+
+        let mut txn = isar.begin_txn(true).unwrap();
+        let oid1 = put_int(col, &txn, 1).unwrap();
+
+        let mut rolled_back_oid = None;
+        let result = txn.savepoint(|txn| -> Result<()> {
+            rolled_back_oid = Some(put_int(col, txn, 2)?);
+            Err(IsarError::IllegalArg {
+                message: "rollback".to_string(),
+            })
+        });
+        assert!(matches!(result, Err(IsarError::IllegalArg { .. })));
+
+        let oid3 = txn.savepoint(|txn| put_int(col, txn, 3)).unwrap();
+        txn.commit().unwrap();
+
+        let txn = isar.begin_txn(false).unwrap();
+        assert!(col.get(&txn, oid1).unwrap().is_some());
+        assert!(col.get(&txn, rolled_back_oid.unwrap()).unwrap().is_none());
+        assert!(col.get(&txn, oid3).unwrap().is_some());
+        assert!(matches!(
+            txn.savepoint(|_| Ok(())),
+            Err(IsarError::WriteTxnRequired {})
+        ));
+    }
+
+    #[test]
+    fn test_drop_aborts() {
+        isar!(isar, col => col!(f1 => Int));
+
+        let txn = isar.begin_txn(true).unwrap();
+        let oid = put_int(col, &txn, 1).unwrap();
+        drop(txn);
+
+        let mut txn = isar.begin_txn(true).unwrap();
+        assert!(col.get(&txn, oid).unwrap().is_none());
+        put_int(col, &txn, 2).unwrap();
+        txn.commit().unwrap();
     }
 
-    pub fn abort(self) {
-        self.txn.abort();
+    #[test]
+    fn test_use_after_commit() {
+        isar!(isar, col => col!(f1 => Int));
+
+        let mut txn = isar.begin_txn(true).unwrap();
+        let oid = put_int(col, &txn, 1).unwrap();
+        assert!(txn.is_active());
+        txn.commit().unwrap();
+
+        assert!(!txn.is_active());
+        assert!(matches!(
+            txn.get_txn(),
+            Err(IsarError::TransactionClosed {})
+        ));
+        assert!(matches!(
+            put_int(col, &txn, 2),
+            Err(IsarError::TransactionClosed {})
+        ));
+        assert!(matches!(
+            col.get(&txn, oid),
+            Err(IsarError::TransactionClosed {})
+        ));
+        assert!(matches!(txn.commit(), Err(IsarError::TransactionClosed {})));
+    }
+
+    #[test]
+    fn test_use_after_abort() {
+        isar!(isar, col => col!(f1 => Int));
+
+        let mut txn = isar.begin_txn(false).unwrap();
+        txn.abort();
+        assert!(matches!(
+            txn.get_txn(),
+            Err(IsarError::TransactionClosed {})
+        ));
+        assert!(matches!(
+            isar.create_query_builder(col).build().count(&txn),
+            Err(IsarError::TransactionClosed {})
+        ));
+        txn.abort();
     }
 }
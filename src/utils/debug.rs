@@ -40,7 +40,13 @@ macro_rules! isar (
         let col = $schema;
         schema.add_collection(col).unwrap();
         )+
-        let $isar = crate::instance::IsarInstance::create($path, 10000000, schema).unwrap();
+        let $isar = crate::instance::IsarInstance::create(
+            $path,
+            10000000,
+            126,
+            crate::instance::EnvOptions::default(),
+            schema,
+        ).unwrap();
         isar!(x $isar, 0, $($col),+);
     };
 
@@ -71,8 +77,8 @@ macro_rules! col (
             let mut collection = crate::schema::collection_schema::CollectionSchema::new(stringify!($($field)+));
             $(collection.add_property(stringify!($field), crate::object::data_type::DataType::$type).unwrap();)+
             $(
-                let (fields, unique, hash) = $index;
-                collection.add_index(fields, unique, hash).unwrap();
+                let (fields, unique, hash, multi_entry, case_sensitive) = $index;
+                collection.add_index(fields, unique, hash, multi_entry, case_sensitive).unwrap();
             )*
             collection
         }
@@ -87,8 +93,8 @@ macro_rules! col (
             let mut collection = crate::schema::collection_schema::CollectionSchema::new($name);
             $(collection.add_property(stringify!($field), crate::object::data_type::DataType::$type).unwrap();)+
             $(
-                let (fields, unique, hash) = $index;
-                collection.add_index(fields, unique, hash).unwrap();
+                let (fields, unique, hash, multi_entry, case_sensitive) = $index;
+                collection.add_index(fields, unique, hash, multi_entry, case_sensitive).unwrap();
             )*
             collection
         }
@@ -106,7 +112,15 @@ macro_rules! ind (
     };
 
     ($($index:expr),+; $unique:expr, $hash:expr) => {
-        (&[$(stringify!($index)),+], $unique, $hash)
+        ind!($($index),+; $unique, $hash, false);
+    };
+
+    ($($index:expr),+; $unique:expr, $hash:expr, $multi_entry:expr) => {
+        ind!($($index),+; $unique, $hash, $multi_entry, true);
+    };
+
+    ($($index:expr),+; $unique:expr, $hash:expr, $multi_entry:expr, $case_sensitive:expr) => {
+        (&[$(stringify!($index)),+], $unique, $hash, $multi_entry, $case_sensitive)
     };
 );
 
@@ -129,21 +143,10 @@ pub fn ref_map<K: Eq + Hash, V>(map: &HashMap<K, V>) -> HashMap<&K, &V> {
 
 pub fn dump_db(db: Db, txn: &IsarTxn, prefix: Option<&[u8]>) -> HashSet<(Vec<u8>, Vec<u8>)> {
     let mut set = HashSet::new();
-    let mut cursor = db.cursor(txn.get_txn()).unwrap();
-
-    let result = if let Some(prefix) = prefix {
-        cursor.move_to_gte(prefix).unwrap()
-    } else {
-        cursor.move_to_first().unwrap()
-    };
-    if result.is_some() {
-        for kv in cursor.iter() {
-            let (key, val) = kv.unwrap();
-            if prefix.is_some() && !key.starts_with(prefix.unwrap()) {
-                break;
-            }
-            set.insert((key.to_vec(), val.to_vec()));
-        }
+    let mut cursor = db.cursor(txn.get_txn().unwrap()).unwrap();
+    for kv in cursor.iter_prefix(prefix.unwrap_or(&[])) {
+        let (key, val) = kv.unwrap();
+        set.insert((key.to_vec(), val.to_vec()));
     }
     set
 }
@@ -32,6 +32,22 @@ macro_rules! map_option (
     };
 );
 
+/// Whether the `debug-log` feature of isar-core is enabled. `debug_log!` checks this constant
+/// because `cfg!` in an exported macro would check the features of the calling crate.
+#[doc(hidden)]
+pub const DEBUG_LOG: bool = cfg!(feature = "debug-log");
+
+/// Prints diagnostics to stderr if the `debug-log` feature is enabled. Otherwise the
+/// arguments are not evaluated.
+#[macro_export]
+macro_rules! debug_log (
+    ($($arg:tt)*) => {
+        if $crate::utils::DEBUG_LOG {
+            eprintln!($($arg)*);
+        }
+    };
+);
+
 #[repr(C, align(8))]
 struct Align8([u8; 8]);
 
@@ -45,3 +61,18 @@ pub fn aligned_vec(size: usize) -> Vec<u8> {
 
     unsafe { Vec::from_raw_parts(ptr as *mut u8, 0, size) }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    #[test]
+    fn test_debug_log() {
+        let evaluated = Cell::new(false);
+        debug_log!("{}", {
+            evaluated.set(true);
+            "message"
+        });
+        assert_eq!(evaluated.get(), cfg!(feature = "debug-log"));
+    }
+}